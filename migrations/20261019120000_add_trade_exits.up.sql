@@ -0,0 +1,12 @@
+-- Add up migration script here
+create table trade_exits (
+    id uuid primary key default uuid_generate_v4(),
+    trade_id uuid not null references trades(id) on delete cascade,
+    exited_at timestamptz not null,
+    exit_price numeric(20,10) not null,
+    quantity numeric(20,10) not null,
+    exit_fee numeric(20,10) default 0,
+    kind varchar(20) not null
+);
+
+CREATE INDEX idx_trade_exits_trade_id ON trade_exits(trade_id);
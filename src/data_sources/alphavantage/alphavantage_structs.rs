@@ -94,7 +94,6 @@ impl ApiResponse for AlphaVantageApiResponse {
                     high: ts.high.parse::<f64>()?,
                     low: ts.low.parse::<f64>()?,
                     volume: ts.volume.parse::<f64>()?,
-                    indicators: HashMap::new(),
                 })
             })
             .collect();
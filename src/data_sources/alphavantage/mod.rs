@@ -9,9 +9,13 @@ use anyhow::{anyhow, Result};
 use reqwest;
 use std::env;
 
+/// Intervals Alpha Vantage provides candles for, other intervals are
+/// resampled.
+pub const NATIVE_INTERVALS: [Interval; 1] = [Interval::Day1];
+
 pub async fn get(symbol: &str, interval: &Interval) -> Result<TimeSeries> {
     let function = "DIGITAL_CURRENCY_DAILY";
-    let url = construct_url(function, symbol, interval);
+    let url = construct_url(function, symbol, interval)?;
 
     let response = reqwest::get(url).await?;
 
@@ -33,22 +37,22 @@ async fn convert_data(
     timeseries.map(|ts| ts)
 }
 
-fn construct_url(function: &str, symbol: &str, interval: &Interval) -> String {
-    //TODO: Implement different intervals
-    match interval {
-        Interval::Day1 => (),
-        _ => panic!("{} interval not supported by Alpha Vantage.", interval),
+/// Only native intervals can be requested, `DataSource::get_historical_data`
+/// resamples other intervals from daily candles.
+fn construct_url(function: &str, symbol: &str, interval: &Interval) -> Result<String> {
+    if !NATIVE_INTERVALS.contains(interval) {
+        return Err(anyhow!(
+            "Alpha Vantage does not support {} candles.",
+            interval
+        ));
     }
 
     let market = "USD";
-    let key = env::var("ALPHA_VANTAGE_KEY");
-
-    if let Ok(key) = key {
-        format!(
-            "https://www.alphavantage.co/query?function={}&symbol={}&market={}&apikey={}",
-            function, symbol, market, key
-        )
-    } else {
-        panic!("Unable to read Alpha Vantage API key.");
-    }
+    let key = env::var("ALPHA_VANTAGE_KEY")
+        .map_err(|_| anyhow!("Unable to read Alpha Vantage API key."))?;
+
+    Ok(format!(
+        "https://www.alphavantage.co/query?function={}&symbol={}&market={}&apikey={}",
+        function, symbol, market, key
+    ))
 }
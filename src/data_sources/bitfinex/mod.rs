@@ -1,2 +1,33 @@
 pub mod rest;
 pub mod ws;
+
+use crate::models::interval::{Interval, IntervalUnit};
+use anyhow::{anyhow, Result};
+
+/// Intervals Bitfinex provides candles for, other intervals are resampled.
+pub const NATIVE_INTERVALS: [Interval; 8] = [
+    Interval::Minute5,
+    Interval::Minute15,
+    Interval::Minute30,
+    Interval::Hour1,
+    Interval::Hour4,
+    Interval::Day1,
+    Interval::Week1,
+    Interval::Month1,
+];
+
+fn interval_to_str(interval: &Interval) -> Result<String> {
+    if !NATIVE_INTERVALS.contains(interval) {
+        return Err(anyhow!("Bitfinex does not support {} candles.", interval));
+    }
+
+    let interval = match interval.unit_and_count() {
+        (IntervalUnit::Minute, n) => format!("{}m", n),
+        (IntervalUnit::Hour, n) => format!("{}h", n),
+        (IntervalUnit::Day, n) => format!("{}D", n),
+        (IntervalUnit::Week, n) => format!("{}D", n * 7),
+        (IntervalUnit::Month, n) => format!("{}M", n),
+    };
+
+    Ok(interval)
+}
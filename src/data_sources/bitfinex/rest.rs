@@ -1,4 +1,5 @@
 use crate::{
+    data_sources::bitfinex::interval_to_str,
     models::{
         candle::Candle, interval::Interval, timeseries::TimeSeries,
         timeseries_builder::TimeSeriesBuilder,
@@ -7,7 +8,6 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use reqwest::Client;
-use std::collections::HashMap;
 
 pub async fn get(symbol: &str, interval: &Interval) -> Result<TimeSeries> {
     let url = generate_url(symbol, interval)?;
@@ -27,18 +27,7 @@ pub async fn get(symbol: &str, interval: &Interval) -> Result<TimeSeries> {
 }
 
 fn generate_url(symbol: &str, interval: &Interval) -> Result<String> {
-    let interval = match interval {
-        // Interval::Minute1 => "1m",
-        Interval::Minute5 => "5m",
-        Interval::Minute15 => "15m",
-        Interval::Minute30 => "30m",
-        Interval::Hour1 => "1h",
-        Interval::Hour4 => "4h",
-        Interval::Day1 => "1D",
-        Interval::Week1 => "7D",
-        _ => return Err(anyhow!("Bitfinex does not support this interval.")),
-        // Interval::Month1 => "1M",
-    };
+    let interval = interval_to_str(interval)?;
 
     Ok(format!(
         "https://api-pub.bitfinex.com/v2/candles/trade:{}:t{}/hist?limit=2000",
@@ -63,7 +52,6 @@ fn generate_timeseries(
                 high: entry[3],
                 low: entry[4],
                 volume: entry[5],
-                indicators: HashMap::new(),
             })
         })
         .collect::<Result<Vec<Candle>>>()?;
@@ -39,6 +39,7 @@ pub async fn connect_ws(client: &Addr<WebsocketClient>, interval: &Interval) ->
             ok: true,
             message: Some(i.to_string()),
             candle: None,
+            closed: true,
         };
 
         client.do_send(payload);
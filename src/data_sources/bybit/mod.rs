@@ -1,3 +1,3 @@
 pub mod rest;
-mod util;
+pub mod util;
 pub mod ws;
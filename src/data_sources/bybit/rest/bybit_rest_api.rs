@@ -1,8 +1,11 @@
 use crate::{
-    data_sources::bybit::rest::{kline, order_create, server_time, tickers, wallet_balance},
+    data_sources::{
+        bybit::rest::{kline, order_create, server_time, tickers, wallet_balance},
+        datasource::DataSource,
+    },
     models::{
-        candle::Candle, interval::Interval, net_version::NetVersion, timeseries::TimeSeries,
-        wallet::Wallet,
+        candle::Candle, interval::Interval, net_version::NetVersion, resampler::Resampler,
+        timeseries::TimeSeries, wallet::Wallet,
     },
 };
 use anyhow::Result;
@@ -27,7 +30,13 @@ impl BybitRestApi {
         from: i64,
         to: i64,
     ) -> Result<Vec<Candle>> {
-        Ok(kline::get_candles_between(symbol, interval, net, from, to).await?)
+        match DataSource::Bybit.resample_base(interval)? {
+            Some(base) => {
+                let candles = kline::get_candles_between(symbol, &base, net, from, to).await?;
+                Resampler::resample(&candles, &base, interval)
+            }
+            None => Ok(kline::get_candles_between(symbol, interval, net, from, to).await?),
+        }
     }
 
     pub async fn market_buy(symbol: &str, quantity: f64) -> Result<()> {
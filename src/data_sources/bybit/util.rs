@@ -1,36 +1,53 @@
-use crate::models::interval::Interval;
-use anyhow::{anyhow, Result};
+use crate::models::interval::{Interval, IntervalUnit};
+use anyhow::{anyhow, Context, Result};
+
+/// Intervals Bybit provides klines for, other intervals are resampled.
+pub const NATIVE_INTERVALS: [Interval; 13] = [
+    Interval::Minute1,
+    Interval::Custom(3),
+    Interval::Minute5,
+    Interval::Minute15,
+    Interval::Minute30,
+    Interval::Hour1,
+    Interval::Custom(120),
+    Interval::Hour4,
+    Interval::Custom(360),
+    Interval::Hour12,
+    Interval::Day1,
+    Interval::Week1,
+    Interval::Month1,
+];
 
 #[allow(dead_code)]
 pub fn interval_to_str(interval: &Interval) -> Result<String> {
-    let interval = match interval {
-        Interval::Minute1 => "1",
-        Interval::Minute5 => "5",
-        Interval::Minute15 => "15",
-        Interval::Minute30 => "30",
-        Interval::Hour1 => "60",
-        Interval::Hour4 => "240",
-        Interval::Day1 => "D",
-        Interval::Week1 => "W",
-        // Interval::Month1 => "1M",
-        _ => return Err(anyhow!("Bybit does not support this interval.")),
+    if !NATIVE_INTERVALS.contains(interval) {
+        return Err(anyhow!("Bybit does not support {} klines.", interval));
+    }
+
+    let interval = match interval.unit_and_count() {
+        (IntervalUnit::Day, 1) => "D".to_string(),
+        (IntervalUnit::Week, 1) => "W".to_string(),
+        (IntervalUnit::Month, 1) => "M".to_string(),
+        _ => interval
+            .minutes()
+            .context("Expected interval of fixed length.")?
+            .to_string(),
     };
 
-    Ok(interval.to_string())
+    Ok(interval)
 }
 
 #[allow(dead_code)]
 pub fn str_to_interval(interval_str: &str) -> Result<Interval> {
-    match interval_str {
-        "1" => Ok(Interval::Minute1),
-        "5" => Ok(Interval::Minute5),
-        "15" => Ok(Interval::Minute15),
-        "30" => Ok(Interval::Minute30),
-        "60" => Ok(Interval::Hour1),
-        "240" => Ok(Interval::Hour4),
-        "D" => Ok(Interval::Day1),
-        "W" => Ok(Interval::Week1),
-        // "1M" => Ok(Interval::Month1),
-        _ => Err(anyhow!("Unsupported interval string.")),
+    let interval = match interval_str {
+        "D" => Interval::Day1,
+        "W" => Interval::Week1,
+        "M" => Interval::Month1,
+        minutes => Interval::from_minutes(minutes.parse()?),
+    };
+
+    match NATIVE_INTERVALS.contains(&interval) {
+        true => Ok(interval),
+        false => Err(anyhow!("Unsupported interval string.")),
     }
 }
@@ -1,7 +1,10 @@
 use crate::{
-    data_sources::bybit::ws::{
-        incoming_message::{IncomingMessage, Kline, KlineResponse},
-        outgoing_message::{OutgoingMessage, OutgoingMessageArg},
+    data_sources::bybit::{
+        util::interval_to_str,
+        ws::{
+            incoming_message::{IncomingMessage, Kline, KlineResponse},
+            outgoing_message::{OutgoingMessage, OutgoingMessageArg},
+        },
     },
     models::{
         interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
@@ -10,7 +13,7 @@ use crate::{
     utils::constants::DEFAULT_SYMBOL,
 };
 use actix::{spawn, Addr};
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use tokio::{
     net::TcpStream,
@@ -26,13 +29,15 @@ use tungstenite::{Error, Message};
 pub struct BybitWebsocketApi {
     client: Addr<WebsocketClient>,
     interval: Interval,
+    intra_candle: bool,
 }
 
 impl BybitWebsocketApi {
-    pub fn new(client: &Addr<WebsocketClient>, interval: Interval) -> Self {
+    pub fn new(client: &Addr<WebsocketClient>, interval: Interval, intra_candle: bool) -> Self {
         Self {
             client: client.clone(),
             interval,
+            intra_candle,
         }
     }
 
@@ -49,7 +54,8 @@ impl BybitWebsocketApi {
         let (tx, rx) = channel(32);
 
         let ping_handle = Self::spawn_ping_task(tx).await;
-        let message_handle = Self::spawn_message_task(self.client.clone(), ws_stream, rx).await;
+        let message_handle =
+            Self::spawn_message_task(self.client.clone(), ws_stream, rx, self.intra_candle).await;
 
         try_join!(ping_handle, message_handle)?;
 
@@ -62,7 +68,7 @@ impl BybitWebsocketApi {
     ) -> Result<()> {
         let args = vec![OutgoingMessageArg {
             stream: "kline".to_string(),
-            interval: self.interval_as_string()?,
+            interval: interval_to_str(&self.interval)?,
             symbol: DEFAULT_SYMBOL.to_string(),
         }];
         let sub = OutgoingMessage::new("subscribe", args);
@@ -73,21 +79,6 @@ impl BybitWebsocketApi {
         Ok(())
     }
 
-    fn interval_as_string(&self) -> Result<String> {
-        let interval = match self.interval {
-            Interval::Minute1 => "1",
-            Interval::Minute5 => "5",
-            Interval::Minute15 => "15",
-            Interval::Minute30 => "30",
-            Interval::Hour1 => "60",
-            Interval::Day1 => "D",
-            Interval::Week1 => "W",
-            _ => return Err(anyhow!("Bybit does not support this interval.")),
-        };
-
-        Ok(interval.to_string())
-    }
-
     async fn send_ping(
         req_id: Option<String>,
         ws_stream: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
@@ -113,6 +104,7 @@ impl BybitWebsocketApi {
         client: Addr<WebsocketClient>,
         mut ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
         mut rx: Receiver<&'static str>,
+        intra_candle: bool,
     ) -> JoinHandle<()> {
         // Keep track of previous kline. Used to determine when a new candle
         // has been formed.
@@ -123,7 +115,7 @@ impl BybitWebsocketApi {
                 let ws_msg: Option<Result<Message, Error>> = ws_stream.next().await;
                 select! {
                     _ = Self::handle_ping_message(&mut rx, &mut ws_stream) => {}
-                    _ = Self::handle_websocket_message(&client, ws_msg, &mut prev_kline) => {}
+                    _ = Self::handle_websocket_message(&client, ws_msg, &mut prev_kline, intra_candle) => {}
                 }
             }
         })
@@ -147,9 +139,10 @@ impl BybitWebsocketApi {
         client: &Addr<WebsocketClient>,
         ws_msg: Option<Result<Message, Error>>,
         prev_kline: &mut Option<Kline>,
+        intra_candle: bool,
     ) -> Result<(), ()> {
         if let Some(msg) = ws_msg {
-            Self::handle_message(client, msg, prev_kline)
+            Self::handle_message(client, msg, prev_kline, intra_candle)
                 .await
                 .map_err(|e| {
                     eprintln!("Error in Websockets: {:#?}", e);
@@ -164,6 +157,7 @@ impl BybitWebsocketApi {
         client: &Addr<WebsocketClient>,
         msg: Result<Message, tungstenite::Error>,
         prev_kline: &mut Option<Kline>,
+        intra_candle: bool,
     ) -> Result<()> {
         let msg = msg?;
 
@@ -175,7 +169,7 @@ impl BybitWebsocketApi {
                 IncomingMessage::Pong(_) => {}
                 IncomingMessage::Subscribe(sub) => println!("Subscribe: {:#?}", sub),
                 IncomingMessage::Kline(kline_response) => {
-                    Self::handle_kline(kline_response, client, prev_kline).await?
+                    Self::handle_kline(kline_response, client, prev_kline, intra_candle).await?
                 }
             }
         }
@@ -187,6 +181,7 @@ impl BybitWebsocketApi {
         kline_response: KlineResponse,
         client: &Addr<WebsocketClient>,
         prev_kline: &mut Option<Kline>,
+        intra_candle: bool,
     ) -> Result<()> {
         let kline = kline_response.get_kline()?;
 
@@ -207,6 +202,20 @@ impl BybitWebsocketApi {
                 ok: true,
                 message: None,
                 candle: Some(candle),
+                closed: true,
+            };
+
+            client.do_send(payload);
+        }
+
+        // Forward updates of the forming candle, closed candles are sent above
+        // once the next candle starts.
+        if intra_candle && !kline.confirm {
+            let payload = WebsocketPayload {
+                ok: true,
+                message: None,
+                candle: Some(kline.to_candle()?),
+                closed: false,
             };
 
             client.do_send(payload);
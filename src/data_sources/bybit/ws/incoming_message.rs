@@ -2,7 +2,6 @@ use crate::{models::candle::Candle, utils::millis_to_datetime};
 use anyhow::Result;
 use serde::{de::Error, Deserialize, Deserializer};
 use serde_json::{from_value, Value};
-use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub enum IncomingMessage {
@@ -108,7 +107,6 @@ impl Kline {
             high: self.high.parse::<f64>()?,
             low: self.low.parse::<f64>()?,
             volume: self.volume.parse::<f64>()?,
-            indicators: HashMap::new(),
         })
     }
 }
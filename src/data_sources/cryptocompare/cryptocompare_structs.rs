@@ -8,7 +8,6 @@ use crate::{
 };
 use anyhow::Result;
 use serde::Deserialize;
-use std::collections::HashMap;
 
 #[allow(dead_code)]
 #[derive(Deserialize, Debug)]
@@ -43,7 +42,6 @@ impl ApiResponse for CryptoCompareApiResponse {
                     high: entry.high,
                     low: entry.low,
                     volume: entry.volume_from,
-                    indicators: HashMap::new(),
                 })
             })
             .collect();
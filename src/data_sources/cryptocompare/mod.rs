@@ -4,19 +4,37 @@ pub mod websockets;
 use self::cryptocompare_structs::CryptoCompareApiResponse;
 use crate::{
     data_sources::api_response::ApiResponse,
-    models::{interval::Interval, timeseries::TimeSeries},
+    models::{
+        interval::{Interval, IntervalUnit},
+        timeseries::TimeSeries,
+    },
 };
 use anyhow::{anyhow, Result};
 use reqwest::Client;
 use std::env;
 
+/// Intervals CryptoCompare provides candles for, other intervals are
+/// resampled. Weekly candles are built from daily candles to have them open
+/// on Mondays.
+pub const NATIVE_INTERVALS: [Interval; 9] = [
+    Interval::Minute1,
+    Interval::Minute5,
+    Interval::Minute15,
+    Interval::Minute30,
+    Interval::Hour1,
+    Interval::Hour4,
+    Interval::Hour12,
+    Interval::Day1,
+    Interval::Day5,
+];
+
 pub async fn get(
     symbol: &str,
     interval: &Interval,
     exchange: Option<String>,
 ) -> Result<TimeSeries> {
     let api_key = env::var("CRYPTOCOMPARE_KEY")?;
-    let url = construct_url(symbol, interval, 2000, exchange);
+    let url = construct_url(symbol, interval, 2000, exchange)?;
 
     let client = Client::new();
     let response = client
@@ -40,31 +58,25 @@ fn construct_url(
     interval: &Interval,
     limit: u32,
     exchange: Option<String>,
-) -> String {
-    let market = "USD";
-    let minute = "histominute";
-    let hour = "histohour";
-    let day = "histoday";
+) -> Result<String> {
+    if !NATIVE_INTERVALS.contains(interval) {
+        return Err(anyhow!(
+            "CryptoCompare does not support {} candles.",
+            interval
+        ));
+    }
 
+    let market = "USD";
     let exchange = exchange.unwrap_or("CCCAGG".to_string());
 
-    let (interval, aggregate) = match interval {
-        Interval::Minute1 => (minute, 1),
-        Interval::Minute5 => (minute, 5),
-        Interval::Minute15 => (minute, 15),
-        Interval::Minute30 => (minute, 30),
-        Interval::Hour1 => (hour, 1),
-        Interval::Hour4 => (hour, 4),
-        Interval::Hour12 => (hour, 12),
-        Interval::Day1 => (day, 1),
-        Interval::Day5 => (day, 5),
-        Interval::Week1 => (day, 5),
+    let (interval, aggregate) = match interval.unit_and_count() {
+        (IntervalUnit::Minute, n) => ("histominute", n),
+        (IntervalUnit::Hour, n) => ("histohour", n),
+        _ => ("histoday", interval.to_duration().num_days() as u32),
     };
 
-    // TODO: Enable multiples using the aggregate parameter in the api
-
-    format!(
+    Ok(format!(
         "https://min-api.cryptocompare.com/data/v2/{}?fsym={}&tsym={}&limit={}&aggregate={}&e={}",
         interval, symbol, market, limit, aggregate, exchange
-    )
+    ))
 }
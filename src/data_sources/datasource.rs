@@ -1,12 +1,13 @@
 use crate::{
     data_sources::{
         alphavantage, bitfinex,
-        bybit::{rest::bybit_rest_api::BybitRestApi, ws::bybit_ws_api::BybitWebsocketApi},
+        bybit::{self, rest::bybit_rest_api::BybitRestApi, ws::bybit_ws_api::BybitWebsocketApi},
         coinmarketcap, cryptocompare, local,
     },
     models::{
         candle::Candle, interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
-        net_version::NetVersion, timeseries::TimeSeries, wallet::Wallet,
+        net_version::NetVersion, resampler::Resampler, timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder, wallet::Wallet,
         websockets::wsclient::WebsocketClient,
     },
 };
@@ -76,12 +77,72 @@ impl DataSource {
         }
     }
 
+    /// Returns the intervals the source provides candles for directly, other
+    /// intervals are resampled from these. Dummy data is generated for any
+    /// interval.
+    pub fn native_intervals(&self) -> Vec<Interval> {
+        match self {
+            DataSource::AlphaVantage => alphavantage::NATIVE_INTERVALS.to_vec(),
+            DataSource::Bitfinex => bitfinex::NATIVE_INTERVALS.to_vec(),
+            DataSource::Bybit => bybit::util::NATIVE_INTERVALS.to_vec(),
+            DataSource::CoinMarketCap => vec![Interval::Day1],
+            DataSource::CryptoCompare(_) => cryptocompare::NATIVE_INTERVALS.to_vec(),
+            DataSource::Dummy(_) => vec![],
+        }
+    }
+
+    /// Returns the interval candles are fetched in to build candles of the
+    /// given interval, None if the source provides the interval natively.
+    pub fn resample_base(&self, interval: &Interval) -> Result<Option<Interval>> {
+        let native = self.native_intervals();
+
+        if native.contains(interval) || matches!(self, DataSource::Dummy(_)) {
+            return Ok(None);
+        }
+
+        match interval.resample_base(&native) {
+            Some(base) => Ok(Some(base)),
+            None => Err(anyhow!("{} does not support {} candles.", self, interval)),
+        }
+    }
+
     pub async fn get_historical_data(
         &self,
         symbol: &str,
         interval: &Interval,
         len: usize,
         net: &NetVersion,
+    ) -> Result<TimeSeries> {
+        let base = match self.resample_base(interval)? {
+            Some(base) => base,
+            None => return self.fetch_historical_data(symbol, interval, len, net).await,
+        };
+
+        // Fetch one extra resampled candle worth of data since the first one
+        // is likely to be incomplete.
+        let factor = (interval.to_millis() / base.to_millis()) as usize;
+        let ts = self
+            .fetch_historical_data(symbol, &base, (len + 1) * factor, net)
+            .await?;
+        let candles = Resampler::resample(&ts.candles, &base, interval)?;
+        let start = candles.len().saturating_sub(len);
+
+        let ts = TimeSeriesBuilder::new()
+            .symbol(symbol.to_string())
+            .interval(interval.clone())
+            .net(*net)
+            .candles(candles[start..].to_vec())
+            .build();
+
+        Ok(ts)
+    }
+
+    async fn fetch_historical_data(
+        &self,
+        symbol: &str,
+        interval: &Interval,
+        len: usize,
+        net: &NetVersion,
     ) -> Result<TimeSeries> {
         let ts = match self {
             DataSource::AlphaVantage => alphavantage::get(symbol, &interval).await?,
@@ -106,12 +167,13 @@ impl DataSource {
         &self,
         client: Addr<WebsocketClient>,
         interval: Interval,
+        intra_candle: bool,
         net: &NetVersion,
     ) -> Result<()> {
         match self {
             DataSource::Bitfinex => bitfinex::ws::connect_ws(&client, &interval).await?,
             DataSource::Bybit => {
-                let mut api = BybitWebsocketApi::new(&client, interval);
+                let mut api = BybitWebsocketApi::new(&client, interval, intra_candle);
                 api.connect(net).await?
             }
             DataSource::Dummy(d) => spawn_dummy_generator(client, d),
@@ -137,6 +199,7 @@ fn spawn_dummy_generator(client: Addr<WebsocketClient>, d: &u64) {
                 ok: true,
                 message: None,
                 candle: Some(candle.clone()),
+                closed: true,
             };
             client.do_send(payload);
             prev = candle;
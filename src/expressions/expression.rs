@@ -0,0 +1,525 @@
+use crate::{
+    expressions::{functions, parser::Parser},
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle::Candle,
+        candle_window::CandleView,
+        traits::{has_min_length::HasMinLength, requires_indicators::RequiresIndicators},
+    },
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Bool(_) => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            Value::Number(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueKind {
+    Number,
+    Bool,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueKind::Number => write!(f, "number"),
+            ValueKind::Bool => write!(f, "boolean"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CandleField {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+impl CandleField {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "open" => Some(CandleField::Open),
+            "high" => Some(CandleField::High),
+            "low" => Some(CandleField::Low),
+            "close" => Some(CandleField::Close),
+            "volume" => Some(CandleField::Volume),
+            _ => None,
+        }
+    }
+
+    fn value(&self, candle: &Candle) -> f64 {
+        match self {
+            CandleField::Open => candle.open,
+            CandleField::High => candle.high,
+            CandleField::Low => candle.low,
+            CandleField::Close => candle.close,
+            CandleField::Volume => candle.volume,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Number(f64),
+    Bool(bool),
+    Candle(CandleField),
+    Indicator {
+        indicator_type: IndicatorType,
+        field: &'static str,
+    },
+    Unary(UnaryOp, Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    /// Value of the expression `n` candles back
+    Prev(Box<Node>, usize),
+    Crossover(Box<Node>, Box<Node>),
+    Crossunder(Box<Node>, Box<Node>),
+    Abs(Box<Node>),
+    Min(Box<Node>, Box<Node>),
+    Max(Box<Node>, Box<Node>),
+}
+
+impl Node {
+    /// Evaluates the node for the last candle of the segment. Returns None if
+    /// an indicator value is missing or there are not enough candles.
+    pub fn eval(&self, candles: CandleView<'_>) -> Option<Value> {
+        let value = match self {
+            Node::Number(n) => Value::Number(*n),
+            Node::Bool(b) => Value::Bool(*b),
+            Node::Candle(field) => Value::Number(field.value(candles.last()?)),
+            Node::Indicator {
+                indicator_type,
+                field,
+            } => functions::field_value(candles.last_indicator(indicator_type)?, field)?,
+            Node::Unary(UnaryOp::Neg, node) => Value::Number(-node.number(candles)?),
+            Node::Unary(UnaryOp::Not, node) => Value::Bool(!node.bool(candles)?),
+            Node::Binary(op, lhs, rhs) => Self::eval_binary(*op, lhs, rhs, candles)?,
+            Node::Prev(node, n) => node.eval(candles.window(0, candles.len().checked_sub(*n)?))?,
+            Node::Crossover(a, b) | Node::Crossunder(a, b) => {
+                let prev = candles.window(0, candles.len().checked_sub(1)?);
+                let (a_prev, b_prev) = (a.number(prev)?, b.number(prev)?);
+                let (a_now, b_now) = (a.number(candles)?, b.number(candles)?);
+
+                Value::Bool(match self {
+                    Node::Crossover(..) => a_prev <= b_prev && a_now > b_now,
+                    _ => a_prev >= b_prev && a_now < b_now,
+                })
+            }
+            Node::Abs(node) => Value::Number(node.number(candles)?.abs()),
+            Node::Min(a, b) => Value::Number(a.number(candles)?.min(b.number(candles)?)),
+            Node::Max(a, b) => Value::Number(a.number(candles)?.max(b.number(candles)?)),
+        };
+
+        Some(value)
+    }
+
+    fn eval_binary(op: BinaryOp, lhs: &Node, rhs: &Node, candles: CandleView<'_>) -> Option<Value> {
+        let value = match op {
+            // Short circuit boolean operators
+            BinaryOp::And => Value::Bool(lhs.bool(candles)? && rhs.bool(candles)?),
+            BinaryOp::Or => Value::Bool(lhs.bool(candles)? || rhs.bool(candles)?),
+            BinaryOp::Eq => Value::Bool(lhs.eval(candles)? == rhs.eval(candles)?),
+            BinaryOp::Ne => Value::Bool(lhs.eval(candles)? != rhs.eval(candles)?),
+            _ => {
+                let (a, b) = (lhs.number(candles)?, rhs.number(candles)?);
+
+                match op {
+                    BinaryOp::Add => Value::Number(a + b),
+                    BinaryOp::Sub => Value::Number(a - b),
+                    BinaryOp::Mul => Value::Number(a * b),
+                    BinaryOp::Div => Value::Number(a / b),
+                    BinaryOp::Lt => Value::Bool(a < b),
+                    BinaryOp::Le => Value::Bool(a <= b),
+                    BinaryOp::Gt => Value::Bool(a > b),
+                    _ => Value::Bool(a >= b),
+                }
+            }
+        };
+
+        Some(value)
+    }
+
+    fn number(&self, candles: CandleView<'_>) -> Option<f64> {
+        self.eval(candles)?.as_number()
+    }
+
+    fn bool(&self, candles: CandleView<'_>) -> Option<bool> {
+        self.eval(candles)?.as_bool()
+    }
+
+    /// Kind of value the node evaluates to.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Node::Number(_)
+            | Node::Candle(_)
+            | Node::Unary(UnaryOp::Neg, _)
+            | Node::Abs(_)
+            | Node::Min(..)
+            | Node::Max(..) => ValueKind::Number,
+            Node::Bool(_)
+            | Node::Unary(UnaryOp::Not, _)
+            | Node::Crossover(..)
+            | Node::Crossunder(..) => ValueKind::Bool,
+            Node::Indicator {
+                indicator_type,
+                field,
+            } => functions::fields(indicator_type)
+                .iter()
+                .find(|(name, _)| name == field)
+                .map_or(ValueKind::Number, |(_, kind)| *kind),
+            Node::Binary(op, ..) => match op {
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => ValueKind::Number,
+                _ => ValueKind::Bool,
+            },
+            Node::Prev(node, _) => node.kind(),
+        }
+    }
+
+    /// Number of candles preceding the last one needed for evaluation.
+    pub fn lookback(&self) -> usize {
+        match self {
+            Node::Number(_) | Node::Bool(_) | Node::Candle(_) | Node::Indicator { .. } => 0,
+            Node::Unary(_, node) | Node::Abs(node) => node.lookback(),
+            Node::Prev(node, n) => node.lookback() + n,
+            Node::Crossover(a, b) | Node::Crossunder(a, b) => 1 + a.lookback().max(b.lookback()),
+            Node::Binary(_, a, b) | Node::Min(a, b) | Node::Max(a, b) => {
+                a.lookback().max(b.lookback())
+            }
+        }
+    }
+
+    fn collect_indicators(&self, indicators: &mut Vec<IndicatorType>) {
+        match self {
+            Node::Number(_) | Node::Bool(_) | Node::Candle(_) => (),
+            Node::Indicator { indicator_type, .. } => {
+                if !indicators.contains(indicator_type) {
+                    indicators.push(*indicator_type);
+                }
+            }
+            Node::Unary(_, node) | Node::Abs(node) | Node::Prev(node, _) => {
+                node.collect_indicators(indicators)
+            }
+            Node::Binary(_, a, b)
+            | Node::Crossover(a, b)
+            | Node::Crossunder(a, b)
+            | Node::Min(a, b)
+            | Node::Max(a, b) => {
+                a.collect_indicators(indicators);
+                b.collect_indicators(indicators);
+            }
+        }
+    }
+}
+
+/// # Expression
+///
+/// Parsed expression over candle fields and indicator values, e.g.
+/// `crossover(ema(21), ema(55))` or `rsi(14) < 30 and close > sma(200)`.
+///
+/// Indicators are written as function calls taking their arguments in the
+/// order of their `IndicatorType`, omitted arguments use the indicator
+/// defaults. Indicators with several outputs expose them as fields, e.g.
+/// `bb(20, 2).upper` or `macd(12, 26, 9).histogram`. Percentiles (PMARP,
+/// BBWP) are fractions between 0 and 1.
+///
+/// Besides arithmetic, comparisons and `and`/`or`/`not` the functions
+/// `crossover(a, b)`, `crossunder(a, b)`, `prev(a, n)`, `abs(a)`, `min(a, b)`
+/// and `max(a, b)` are available.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let root = Parser::new(source)?.parse()?;
+
+        Ok(Self {
+            source: source.trim().to_string(),
+            root,
+        })
+    }
+
+    /// Parses an expression which has to evaluate to a boolean.
+    pub fn parse_condition(source: &str) -> Result<Self> {
+        let expression = Self::parse(source)?;
+
+        if expression.kind() != ValueKind::Bool {
+            return Err(anyhow!(
+                "Expected condition '{}' to be a boolean expression",
+                expression.source
+            ));
+        }
+
+        Ok(expression)
+    }
+
+    pub fn kind(&self) -> ValueKind {
+        self.root.kind()
+    }
+
+    /// Evaluates the expression for the last candle of the segment, the
+    /// required indicators need to be populated on the candles.
+    pub fn evaluate(&self, candles: CandleView<'_>) -> Option<Value> {
+        self.root.eval(candles)
+    }
+
+    /// Returns whether the expression evaluates to true for the last candle.
+    /// Missing values (e.g. indicators not yet available) count as false.
+    pub fn is_true(&self, candles: CandleView<'_>) -> bool {
+        self.evaluate(candles)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Number of candles needed in the segment passed to `evaluate`.
+    pub fn candles_needed(&self) -> usize {
+        self.root.lookback() + 1
+    }
+}
+
+impl RequiresIndicators for Expression {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![];
+        self.root.collect_indicators(&mut indicators);
+
+        indicators
+    }
+}
+
+impl HasMinLength for Expression {
+    fn min_length(&self) -> usize {
+        let indicator_len = self
+            .required_indicators()
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1);
+
+        indicator_len + self.root.lookback()
+    }
+}
+
+impl FromStr for Expression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.source
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expression, Value, ValueKind};
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            fixed_point::FixedPoint,
+            interval::Interval,
+            ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{has_min_length::HasMinLength, requires_indicators::RequiresIndicators},
+        },
+    };
+
+    #[test]
+    fn expression_required_indicators() {
+        let expression =
+            Expression::parse("pmarp(20, 350, ema) > 0.65 and bbwp(13, 252) < 0.2").unwrap();
+
+        assert_eq!(expression.kind(), ValueKind::Bool);
+        assert_eq!(
+            expression.required_indicators(),
+            vec![
+                IndicatorType::PMARP(20, 350, 20, MAType::EMA),
+                IndicatorType::BBWP(13, 252, 5, FixedPoint::new(1.0), MAType::SMA),
+            ]
+        );
+
+        let expression = Expression::parse("squeeze(20, 2, 1).is_on or squeeze(20).is_on").unwrap();
+
+        assert_eq!(
+            expression.required_indicators(),
+            vec![
+                IndicatorType::Squeeze(20, FixedPoint::new(2.0), FixedPoint::new(1.0)),
+                IndicatorType::Squeeze(20, FixedPoint::new(2.0), FixedPoint::new(1.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn expression_min_length() {
+        let expression = Expression::parse("crossover(ema(21), prev(ema(55), 2))").unwrap();
+
+        assert_eq!(expression.candles_needed(), 4);
+        assert_eq!(
+            expression.min_length(),
+            IndicatorType::EMA(55).min_length() + 3
+        );
+    }
+
+    #[test]
+    fn expression_crossover() {
+        let candles = Candle::dummy_from_arr(&[
+            10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 6.0, 8.0, 10.0, 12.0, 11.0, 9.0, 7.0, 5.0,
+        ]);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let crossover = Expression::parse_condition("crossover(ema(2), ema(5))").unwrap();
+        let crossunder = Expression::parse_condition("crossunder(ema(2), ema(5))").unwrap();
+        ts.add_indicators(&crossover.required_indicators()).unwrap();
+
+        let ema = |i: usize, len: usize| {
+            ts.indicator(i, &IndicatorType::EMA(len))
+                .and_then(|indicator| indicator.as_ema())
+                .map(|ema| ema.value)
+        };
+
+        let mut crosses = 0;
+
+        for end in 1..=ts.candles.len() {
+            let window = ts.view().window(0, end);
+            let i = end - 1;
+
+            let expected = match (
+                ema(i - i.min(1), 2),
+                ema(i - i.min(1), 5),
+                ema(i, 2),
+                ema(i, 5),
+            ) {
+                (Some(fast_prev), Some(slow_prev), Some(fast), Some(slow)) if end > 1 => (
+                    fast_prev <= slow_prev && fast > slow,
+                    fast_prev >= slow_prev && fast < slow,
+                ),
+                _ => (false, false),
+            };
+
+            assert_eq!(crossover.is_true(window), expected.0);
+            assert_eq!(crossunder.is_true(window), expected.1);
+            crosses += expected.0 as usize + expected.1 as usize;
+        }
+
+        assert_eq!(crosses, 2);
+    }
+
+    #[test]
+    fn expression_evaluate() {
+        let candles = Candle::dummy_data(30, "negative", 500.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let oversold = Expression::parse("rsi(14) < 30 and close < prev(close)").unwrap();
+        let spread = Expression::parse("-(close - prev(close, 2)) / 2").unwrap();
+        ts.add_indicators(&oversold.required_indicators()).unwrap();
+
+        assert!(oversold.is_true(ts.view()));
+        assert!(!oversold.is_true(ts.view().window(0, 10)));
+
+        let last = ts.candles.len() - 1;
+        let expected = -(ts.candles[last].close - ts.candles[last - 2].close) / 2.0;
+        assert_eq!(spread.evaluate(ts.view()), Some(Value::Number(expected)));
+        assert_eq!(spread.evaluate(ts.view().window(0, 2)), None);
+    }
+
+    #[test]
+    fn expression_invalid() {
+        let invalid = [
+            "",
+            "rsi(14) and true",
+            "rsi(14) <",
+            "foo(1) > 2",
+            "macd().nope > 0",
+            "sma(0) > close",
+            "sma(20, 3) > close",
+            "supertrend().is_uptrend > 1",
+            "crossover(rsi(14) > 2, 3)",
+            "close > 1 2",
+            "unknown > 2",
+        ];
+
+        for source in invalid {
+            assert!(Expression::parse(source).is_err(), "{}", source);
+        }
+
+        assert!(Expression::parse_condition("rsi(14) + 1").is_err());
+        assert!(Expression::parse_condition("supertrend().is_uptrend == true").is_ok());
+        assert!(Expression::parse_condition("not squeeze(20).is_on").is_ok());
+    }
+}
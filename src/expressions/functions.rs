@@ -0,0 +1,395 @@
+use crate::{
+    expressions::expression::{Value, ValueKind},
+    indicators::{
+        adx::ADX, atr::ATR, bbwp::BBWP, bollinger_bands::BollingerBands,
+        donchian_channels::DonchianChannels, dynamic_pivots::DynamicPivots, ema::EMA,
+        ichimoku::Ichimoku, indicator::Indicator, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, keltner_channels::KeltnerChannels, macd::MACD, pmar::PMAR,
+        pmarp::PMARP, rsi::RSI, sma::SMA, squeeze::Squeeze, stochastic::Stochastic,
+        supertrend::Supertrend, volume_sma::VolumeSMA, vwap::VWAP, vwma::VWMA,
+    },
+    models::{fixed_point::FixedPoint, ma_type::MAType, vwap_anchor::VWAPAnchor},
+};
+use anyhow::{anyhow, Result};
+
+/// Literal argument of an indicator function call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Number(f64),
+    Ident(String),
+}
+
+/// Positional arguments of an indicator function, omitted trailing arguments
+/// fall back to the defaults of the indicator.
+struct Args<'a> {
+    name: &'a str,
+    args: &'a [Arg],
+}
+
+impl<'a> Args<'a> {
+    fn max(&self, n: usize) -> Result<()> {
+        if self.args.len() > n {
+            return Err(anyhow!(
+                "{}() takes at most {} arguments, got {}",
+                self.name,
+                n,
+                self.args.len()
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn len(&self, i: usize, default: usize) -> Result<usize> {
+        match self.args.get(i) {
+            None => Ok(default),
+            Some(Arg::Number(n)) if *n >= 1.0 && n.fract() == 0.0 => Ok(*n as usize),
+            Some(arg) => Err(anyhow!(
+                "Argument {} of {}() must be a positive integer, got {:?}",
+                i + 1,
+                self.name,
+                arg
+            )),
+        }
+    }
+
+    fn float(&self, i: usize, default: f64) -> Result<f64> {
+        match self.args.get(i) {
+            None => Ok(default),
+            Some(Arg::Number(n)) => Ok(*n),
+            Some(arg) => Err(anyhow!(
+                "Argument {} of {}() must be a number, got {:?}",
+                i + 1,
+                self.name,
+                arg
+            )),
+        }
+    }
+
+    fn ma_type(&self, i: usize, default: MAType) -> Result<MAType> {
+        match self.args.get(i) {
+            None => Ok(default),
+            Some(Arg::Ident(ident)) => match ident.to_lowercase().as_str() {
+                "sma" => Ok(MAType::SMA),
+                "ema" => Ok(MAType::EMA),
+                "vwma" => Ok(MAType::VWMA),
+                _ => Err(anyhow!("Unknown moving average type '{}'", ident)),
+            },
+            Some(arg) => Err(anyhow!(
+                "Argument {} of {}() must be one of sma, ema or vwma, got {:?}",
+                i + 1,
+                self.name,
+                arg
+            )),
+        }
+    }
+
+    fn anchor(&self, i: usize, default: VWAPAnchor) -> Result<VWAPAnchor> {
+        match self.args.get(i) {
+            None => Ok(default),
+            Some(Arg::Ident(ident)) => match ident.to_lowercase().as_str() {
+                "daily" => Ok(VWAPAnchor::Daily),
+                "weekly" => Ok(VWAPAnchor::Weekly),
+                "monthly" => Ok(VWAPAnchor::Monthly),
+                _ => Err(anyhow!("Unknown VWAP anchor '{}'", ident)),
+            },
+            Some(Arg::Number(_)) => Ok(VWAPAnchor::Custom(self.len(i, 1)? as u32)),
+        }
+    }
+}
+
+/// Returns the indicator type of an indicator function call, or None if the
+/// name is not an indicator function.
+///
+/// Percentile indicators take their arguments as `(len, lookback, ma, sma_len)`,
+/// e.g. `pmarp(20, 350, ema)`.
+pub fn indicator_type(name: &str, args: &[Arg]) -> Result<Option<IndicatorType>> {
+    let a = Args { name, args };
+
+    let indicator_type = match name.to_lowercase().as_str() {
+        "sma" => {
+            a.max(1)?;
+            IndicatorType::SMA(a.len(0, SMA::default_args().len_res()?)?)
+        }
+        "ema" => {
+            a.max(1)?;
+            IndicatorType::EMA(a.len(0, EMA::default_args().len_res()?)?)
+        }
+        "vwma" => {
+            a.max(1)?;
+            IndicatorType::VWMA(a.len(0, VWMA::default_args().len_res()?)?)
+        }
+        "rsi" => {
+            a.max(1)?;
+            IndicatorType::RSI(a.len(0, RSI::default_args().len_res()?)?)
+        }
+        "atr" => {
+            a.max(1)?;
+            IndicatorType::ATR(a.len(0, ATR::default_args().len_res()?)?)
+        }
+        "bb" | "bbw" => {
+            a.max(3)?;
+            let (len, std_n, ma_type) = BollingerBands::default_args().bb_res()?;
+            let len = a.len(0, len)?;
+            let std_n = FixedPoint::new(a.float(1, std_n)?);
+            let ma_type = a.ma_type(2, ma_type)?;
+
+            if name.eq_ignore_ascii_case("bb") {
+                IndicatorType::BollingerBands(len, std_n, ma_type)
+            } else {
+                IndicatorType::BBW(len, std_n, ma_type)
+            }
+        }
+        "bbwp" => {
+            a.max(5)?;
+            let (len, lookback, sma_len, std_n, ma_type) = BBWP::default_args().bbwp_res()?;
+            IndicatorType::BBWP(
+                a.len(0, len)?,
+                a.len(1, lookback)?,
+                a.len(3, sma_len)?,
+                FixedPoint::new(a.float(4, std_n)?),
+                a.ma_type(2, ma_type)?,
+            )
+        }
+        "pivots" => {
+            a.max(1)?;
+            IndicatorType::DynamicPivot(a.len(0, DynamicPivots::default_args().len_res()?)?)
+        }
+        "pmar" => {
+            a.max(2)?;
+            let (len, ma_type) = PMAR::default_args().pmar_res()?;
+            IndicatorType::PMAR(a.len(0, len)?, a.ma_type(1, ma_type)?)
+        }
+        "pmarp" => {
+            a.max(4)?;
+            let (len, lookback, sma_len, ma_type) = PMARP::default_args().pmarp_res()?;
+            IndicatorType::PMARP(
+                a.len(0, len)?,
+                a.len(1, lookback)?,
+                a.len(3, sma_len)?,
+                a.ma_type(2, ma_type)?,
+            )
+        }
+        "stoch" => {
+            a.max(3)?;
+            let (k_len, k_smoothing, d_smoothing) = Stochastic::default_args().stochastic_res()?;
+            IndicatorType::Stochastic(
+                a.len(0, k_len)?,
+                a.len(1, k_smoothing)?,
+                a.len(2, d_smoothing)?,
+            )
+        }
+        "macd" => {
+            a.max(3)?;
+            let (fast, slow, signal) = MACD::default_args().macd_res()?;
+            IndicatorType::MACD(a.len(0, fast)?, a.len(1, slow)?, a.len(2, signal)?)
+        }
+        "obv" => {
+            a.max(0)?;
+            IndicatorType::OBV
+        }
+        "vwap" => {
+            a.max(1)?;
+            IndicatorType::VWAP(a.anchor(0, VWAP::default_args().vwap_res()?)?)
+        }
+        "volume_sma" => {
+            a.max(1)?;
+            IndicatorType::VolumeSMA(a.len(0, VolumeSMA::default_args().len_res()?)?)
+        }
+        "adx" => {
+            a.max(1)?;
+            IndicatorType::ADX(a.len(0, ADX::default_args().len_res()?)?)
+        }
+        "supertrend" => {
+            a.max(2)?;
+            let (len, multiplier) = Supertrend::default_args().supertrend_res()?;
+            IndicatorType::Supertrend(a.len(0, len)?, FixedPoint::new(a.float(1, multiplier)?))
+        }
+        "ichimoku" => {
+            a.max(3)?;
+            let (tenkan, kijun, senkou_b) = Ichimoku::default_args().ichimoku_res()?;
+            IndicatorType::Ichimoku(a.len(0, tenkan)?, a.len(1, kijun)?, a.len(2, senkou_b)?)
+        }
+        "keltner" => {
+            a.max(3)?;
+            let (ema_len, atr_len, multiplier) = KeltnerChannels::default_args().keltner_res()?;
+            IndicatorType::KeltnerChannels(
+                a.len(0, ema_len)?,
+                a.len(1, atr_len)?,
+                FixedPoint::new(a.float(2, multiplier)?),
+            )
+        }
+        "donchian" => {
+            a.max(1)?;
+            IndicatorType::DonchianChannels(a.len(0, DonchianChannels::default_args().len_res()?)?)
+        }
+        "squeeze" => {
+            a.max(3)?;
+            let (len, bb_std_n, kc_multiplier) = Squeeze::default_args().squeeze_res()?;
+            IndicatorType::Squeeze(
+                a.len(0, len)?,
+                FixedPoint::new(a.float(1, bb_std_n)?),
+                FixedPoint::new(a.float(2, kc_multiplier)?),
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(indicator_type))
+}
+
+/// Returns the fields which can be accessed on an indicator, e.g.
+/// `macd().signal`. The first field is used when none is given.
+pub fn fields(indicator_type: &IndicatorType) -> &'static [(&'static str, ValueKind)] {
+    use ValueKind::{Bool, Number};
+
+    match indicator_type {
+        IndicatorType::BollingerBands(..) => &[
+            ("basis", Number),
+            ("upper", Number),
+            ("lower", Number),
+            ("std", Number),
+        ],
+        IndicatorType::BBWP(..) => &[("value", Number), ("sma", Number)],
+        IndicatorType::DynamicPivot(_) => &[("high", Number), ("low", Number)],
+        IndicatorType::PMAR(..) | IndicatorType::PMARP(..) => &[("value", Number), ("ma", Number)],
+        IndicatorType::Stochastic(..) => &[("k", Number), ("d", Number)],
+        IndicatorType::MACD(..) => &[("line", Number), ("signal", Number), ("histogram", Number)],
+        IndicatorType::VWAP(_) => &[("value", Number), ("std_dev", Number)],
+        IndicatorType::VolumeSMA(_) => &[("value", Number), ("relative_volume", Number)],
+        IndicatorType::ADX(_) => &[("adx", Number), ("plus_di", Number), ("minus_di", Number)],
+        IndicatorType::Supertrend(..) => &[
+            ("value", Number),
+            ("is_uptrend", Bool),
+            ("upper_band", Number),
+            ("lower_band", Number),
+        ],
+        IndicatorType::Ichimoku(..) => &[
+            ("tenkan", Number),
+            ("kijun", Number),
+            ("senkou_a", Number),
+            ("senkou_b", Number),
+            ("chikou", Number),
+            ("cloud_top", Number),
+            ("cloud_bottom", Number),
+        ],
+        IndicatorType::KeltnerChannels(..) | IndicatorType::DonchianChannels(_) => {
+            &[("middle", Number), ("upper", Number), ("lower", Number)]
+        }
+        IndicatorType::Squeeze(..) => &[("momentum", Number), ("is_on", Bool)],
+        _ => &[("value", Number)],
+    }
+}
+
+/// Returns the value of a field of a populated indicator. Fields are
+/// validated when parsing, unknown fields resolve to the default field.
+pub fn field_value(indicator: &Indicator, field: &str) -> Option<Value> {
+    let number = match *indicator {
+        Indicator::SMA(sma) => sma?.value,
+        Indicator::EMA(ema) => ema?.value,
+        Indicator::VWMA(vwma) => vwma?.value,
+        Indicator::RSI(rsi) => rsi?.value,
+        Indicator::ATR(atr) => atr?.value,
+        Indicator::BBW(bbw) => bbw?.value,
+        Indicator::OBV(obv) => obv?.value,
+        Indicator::BollingerBands(bb) => {
+            let bb = bb?;
+            match field {
+                "upper" => bb.upper,
+                "lower" => bb.lower,
+                "std" => bb.std,
+                _ => bb.basis,
+            }
+        }
+        Indicator::BBWP(bbwp) => match field {
+            "sma" => bbwp?.sma?.value,
+            _ => bbwp?.value,
+        },
+        Indicator::DynamicPivot(pivots) => match field {
+            "low" => pivots?.low?,
+            _ => pivots?.high?,
+        },
+        Indicator::PMAR(pmar) => match field {
+            "ma" => pmar?.ma?,
+            _ => pmar?.value,
+        },
+        Indicator::PMARP(pmarp) => match field {
+            "ma" => pmarp?.ma?,
+            _ => pmarp?.value,
+        },
+        Indicator::Stochastic(stoch) => match field {
+            "d" => stoch?.d,
+            _ => stoch?.k,
+        },
+        Indicator::MACD(macd) => {
+            let macd = macd?;
+            match field {
+                "signal" => macd.signal,
+                "histogram" => macd.histogram,
+                _ => macd.line,
+            }
+        }
+        Indicator::VWAP(vwap) => match field {
+            "std_dev" => vwap?.std_dev,
+            _ => vwap?.value,
+        },
+        Indicator::VolumeSMA(volume_sma) => match field {
+            "relative_volume" => volume_sma?.relative_volume,
+            _ => volume_sma?.value,
+        },
+        Indicator::ADX(adx) => {
+            let adx = adx?;
+            match field {
+                "plus_di" => adx.plus_di,
+                "minus_di" => adx.minus_di,
+                _ => adx.adx,
+            }
+        }
+        Indicator::Supertrend(supertrend) => {
+            let supertrend = supertrend?;
+            match field {
+                "is_uptrend" => return Some(Value::Bool(supertrend.is_uptrend)),
+                "upper_band" => supertrend.upper_band,
+                "lower_band" => supertrend.lower_band,
+                _ => supertrend.value,
+            }
+        }
+        Indicator::Ichimoku(ichimoku) => {
+            let ichimoku = ichimoku?;
+            match field {
+                "kijun" => ichimoku.kijun,
+                "senkou_a" => ichimoku.senkou_a,
+                "senkou_b" => ichimoku.senkou_b,
+                "chikou" => ichimoku.chikou,
+                "cloud_top" => ichimoku.cloud_top(),
+                "cloud_bottom" => ichimoku.cloud_bottom(),
+                _ => ichimoku.tenkan,
+            }
+        }
+        Indicator::KeltnerChannels(kc) => {
+            let kc = kc?;
+            match field {
+                "upper" => kc.upper,
+                "lower" => kc.lower,
+                _ => kc.middle,
+            }
+        }
+        Indicator::DonchianChannels(dc) => {
+            let dc = dc?;
+            match field {
+                "upper" => dc.upper,
+                "lower" => dc.lower,
+                _ => dc.middle,
+            }
+        }
+        Indicator::Squeeze(squeeze) => {
+            let squeeze = squeeze?;
+            match field {
+                "is_on" => return Some(Value::Bool(squeeze.is_on)),
+                _ => squeeze.momentum,
+            }
+        }
+    };
+
+    Some(Value::Number(number))
+}
@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(f64),
+    Ident(String),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+}
+
+/// Token along with its character offset in the source, used for errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub pos: usize,
+}
+
+/// Splits an expression into tokens. The keywords `and`, `or` and `not` are
+/// case insensitive and interchangeable with `&&`, `||` and `!`.
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let pos = i;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let token = if c.is_ascii_digit() || (c == '.' && next_is_digit(&chars, i)) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+
+            let literal: String = chars[pos..i].iter().collect();
+            let number = literal
+                .parse()
+                .map_err(|_| anyhow!("Invalid number '{}' at position {}", literal, pos))?;
+
+            Token::Number(number)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            let ident: String = chars[pos..i].iter().collect();
+
+            match ident.to_lowercase().as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ => Token::Ident(ident),
+            }
+        } else {
+            let next = chars.get(i + 1).copied();
+            let (token, len) = match (c, next) {
+                ('<', Some('=')) => (Token::Le, 2),
+                ('>', Some('=')) => (Token::Ge, 2),
+                ('=', Some('=')) => (Token::Eq, 2),
+                ('!', Some('=')) => (Token::Ne, 2),
+                ('&', Some('&')) => (Token::And, 2),
+                ('|', Some('|')) => (Token::Or, 2),
+                ('<', _) => (Token::Lt, 1),
+                ('>', _) => (Token::Gt, 1),
+                ('!', _) => (Token::Not, 1),
+                ('(', _) => (Token::LParen, 1),
+                (')', _) => (Token::RParen, 1),
+                (',', _) => (Token::Comma, 1),
+                ('.', _) => (Token::Dot, 1),
+                ('+', _) => (Token::Plus, 1),
+                ('-', _) => (Token::Minus, 1),
+                ('*', _) => (Token::Star, 1),
+                ('/', _) => (Token::Slash, 1),
+                _ => return Err(anyhow!("Unexpected character '{}' at position {}", c, pos)),
+            };
+
+            i += len;
+            token
+        };
+
+        tokens.push(Spanned { token, pos });
+    }
+
+    Ok(tokens)
+}
+
+fn next_is_digit(chars: &[char], i: usize) -> bool {
+    chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, Token};
+
+    #[test]
+    fn tokenize_expression() {
+        let tokens: Vec<Token> = tokenize("rsi(14) <= 30.5 AND !x || .5")
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("rsi".to_string()),
+                Token::LParen,
+                Token::Number(14.0),
+                Token::RParen,
+                Token::Le,
+                Token::Number(30.5),
+                Token::And,
+                Token::Not,
+                Token::Ident("x".to_string()),
+                Token::Or,
+                Token::Number(0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_invalid() {
+        assert!(tokenize("rsi(14) # 30").is_err());
+        assert!(tokenize("1.2.3").is_err());
+    }
+}
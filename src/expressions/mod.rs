@@ -0,0 +1,4 @@
+pub mod expression;
+pub mod functions;
+pub mod lexer;
+pub mod parser;
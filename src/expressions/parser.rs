@@ -0,0 +1,372 @@
+use crate::expressions::{
+    expression::{BinaryOp, CandleField, Node, UnaryOp, ValueKind},
+    functions::{self, Arg},
+    lexer::{tokenize, Spanned, Token},
+};
+use anyhow::{anyhow, Result};
+
+/// Recursive descent parser turning tokens into a type checked `Node` tree.
+///
+/// Precedence from lowest to highest: `or`, `and`, `not`, comparisons,
+/// `+ -`, `* /`, unary minus, calls and parentheses.
+pub struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    source_len: usize,
+}
+
+impl Parser {
+    pub fn new(source: &str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            pos: 0,
+            source_len: source.len(),
+        })
+    }
+
+    pub fn parse(mut self) -> Result<Node> {
+        if self.tokens.is_empty() {
+            return Err(anyhow!("Empty expression"));
+        }
+
+        let node = self.parse_or()?;
+
+        if let Some(spanned) = self.tokens.get(self.pos) {
+            return Err(anyhow!(
+                "Unexpected {:?} at position {}",
+                spanned.token,
+                spanned.pos
+            ));
+        }
+
+        Ok(node)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|spanned| &spanned.token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.source_len, |spanned| spanned.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|spanned| spanned.token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        let pos = self.position();
+
+        match self.next() {
+            Some(next) if next == token => Ok(()),
+            Some(next) => Err(anyhow!(
+                "Expected {:?} at position {}, found {:?}",
+                token,
+                pos,
+                next
+            )),
+            None => Err(anyhow!("Expected {:?} at end of expression", token)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut node = self.parse_and()?;
+
+        while self.eat(&Token::Or) {
+            let rhs = self.parse_and()?;
+            node = binary(BinaryOp::Or, node, rhs)?;
+        }
+
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut node = self.parse_not()?;
+
+        while self.eat(&Token::And) {
+            let rhs = self.parse_not()?;
+            node = binary(BinaryOp::And, node, rhs)?;
+        }
+
+        Ok(node)
+    }
+
+    fn parse_not(&mut self) -> Result<Node> {
+        if self.eat(&Token::Not) {
+            let node = self.parse_not()?;
+            check_kind(&node, ValueKind::Bool, "not")?;
+
+            return Ok(Node::Unary(UnaryOp::Not, Box::new(node)));
+        }
+
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Node> {
+        let node = self.parse_sum()?;
+
+        let op = match self.peek() {
+            Some(Token::Lt) => BinaryOp::Lt,
+            Some(Token::Le) => BinaryOp::Le,
+            Some(Token::Gt) => BinaryOp::Gt,
+            Some(Token::Ge) => BinaryOp::Ge,
+            Some(Token::Eq) => BinaryOp::Eq,
+            Some(Token::Ne) => BinaryOp::Ne,
+            _ => return Ok(node),
+        };
+
+        self.pos += 1;
+        let rhs = self.parse_sum()?;
+
+        binary(op, node, rhs)
+    }
+
+    fn parse_sum(&mut self) -> Result<Node> {
+        let mut node = self.parse_product()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(node),
+            };
+
+            self.pos += 1;
+            let rhs = self.parse_product()?;
+            node = binary(op, node, rhs)?;
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Node> {
+        let mut node = self.parse_unary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                _ => return Ok(node),
+            };
+
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            node = binary(op, node, rhs)?;
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Node> {
+        if self.eat(&Token::Minus) {
+            let node = self.parse_unary()?;
+            check_kind(&node, ValueKind::Number, "-")?;
+
+            return Ok(Node::Unary(UnaryOp::Neg, Box::new(node)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Node> {
+        let pos = self.position();
+
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Number(n)),
+            Some(Token::LParen) => {
+                let node = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat(&Token::LParen) {
+                    return self.parse_call(&name, pos);
+                }
+
+                match name.to_lowercase().as_str() {
+                    "true" => Ok(Node::Bool(true)),
+                    "false" => Ok(Node::Bool(false)),
+                    _ => CandleField::from_name(&name)
+                        .map(Node::Candle)
+                        .ok_or(anyhow!("Unknown identifier '{}' at position {}", name, pos)),
+                }
+            }
+            Some(token) => Err(anyhow!("Unexpected {:?} at position {}", token, pos)),
+            None => Err(anyhow!("Unexpected end of expression")),
+        }
+    }
+
+    /// Parses a call after its opening parenthesis.
+    fn parse_call(&mut self, name: &str, pos: usize) -> Result<Node> {
+        let lower = name.to_lowercase();
+
+        let node = match lower.as_str() {
+            "crossover" | "crossunder" | "min" | "max" => {
+                let (a, b) = self.parse_two_numbers(&lower)?;
+
+                match lower.as_str() {
+                    "crossover" => Node::Crossover(Box::new(a), Box::new(b)),
+                    "crossunder" => Node::Crossunder(Box::new(a), Box::new(b)),
+                    "min" => Node::Min(Box::new(a), Box::new(b)),
+                    _ => Node::Max(Box::new(a), Box::new(b)),
+                }
+            }
+            "abs" => {
+                let node = self.parse_or()?;
+                check_kind(&node, ValueKind::Number, "abs()")?;
+                self.expect(Token::RParen)?;
+
+                Node::Abs(Box::new(node))
+            }
+            "prev" => {
+                let node = self.parse_or()?;
+
+                let n = if self.eat(&Token::Comma) {
+                    match self.next() {
+                        Some(Token::Number(n)) if n >= 1.0 && n.fract() == 0.0 => n as usize,
+                        _ => return Err(anyhow!("prev() expects a positive integer offset")),
+                    }
+                } else {
+                    1
+                };
+
+                self.expect(Token::RParen)?;
+
+                Node::Prev(Box::new(node), n)
+            }
+            _ => {
+                let args = self.parse_literal_args()?;
+                let indicator_type = functions::indicator_type(name, &args)?.ok_or(anyhow!(
+                    "Unknown function '{}' at position {}",
+                    name,
+                    pos
+                ))?;
+                let fields = functions::fields(&indicator_type);
+
+                let field = if self.eat(&Token::Dot) {
+                    let field = match self.next() {
+                        Some(Token::Ident(field)) => field.to_lowercase(),
+                        _ => return Err(anyhow!("Expected field name after '{}().'", name)),
+                    };
+
+                    fields
+                        .iter()
+                        .find(|(name, _)| *name == field)
+                        .map(|(name, _)| *name)
+                        .ok_or(anyhow!(
+                            "{}() has no field '{}', available fields are {:?}",
+                            name,
+                            field,
+                            fields.iter().map(|(name, _)| name).collect::<Vec<_>>()
+                        ))?
+                } else {
+                    fields[0].0
+                };
+
+                Node::Indicator {
+                    indicator_type,
+                    field,
+                }
+            }
+        };
+
+        Ok(node)
+    }
+
+    fn parse_two_numbers(&mut self, name: &str) -> Result<(Node, Node)> {
+        let a = self.parse_or()?;
+        self.expect(Token::Comma)?;
+        let b = self.parse_or()?;
+        self.expect(Token::RParen)?;
+
+        check_kind(&a, ValueKind::Number, name)?;
+        check_kind(&b, ValueKind::Number, name)?;
+
+        Ok((a, b))
+    }
+
+    /// Parses the literal arguments of an indicator call up to and including
+    /// the closing parenthesis.
+    fn parse_literal_args(&mut self) -> Result<Vec<Arg>> {
+        let mut args = vec![];
+
+        if self.eat(&Token::RParen) {
+            return Ok(args);
+        }
+
+        loop {
+            let pos = self.position();
+            let arg = match self.next() {
+                Some(Token::Number(n)) => Arg::Number(n),
+                Some(Token::Minus) => match self.next() {
+                    Some(Token::Number(n)) => Arg::Number(-n),
+                    _ => return Err(anyhow!("Expected number at position {}", pos + 1)),
+                },
+                Some(Token::Ident(ident)) => Arg::Ident(ident),
+                _ => {
+                    return Err(anyhow!(
+                        "Indicator arguments must be numbers or names, see position {}",
+                        pos
+                    ))
+                }
+            };
+
+            args.push(arg);
+
+            if self.eat(&Token::RParen) {
+                return Ok(args);
+            }
+
+            self.expect(Token::Comma)?;
+        }
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Node, rhs: Node) -> Result<Node> {
+    match op {
+        BinaryOp::And | BinaryOp::Or => {
+            check_kind(&lhs, ValueKind::Bool, "and/or")?;
+            check_kind(&rhs, ValueKind::Bool, "and/or")?;
+        }
+        BinaryOp::Eq | BinaryOp::Ne => {
+            if lhs.kind() != rhs.kind() {
+                return Err(anyhow!(
+                    "Unable to compare {} with {}",
+                    lhs.kind(),
+                    rhs.kind()
+                ));
+            }
+        }
+        _ => {
+            check_kind(&lhs, ValueKind::Number, "arithmetic and comparisons")?;
+            check_kind(&rhs, ValueKind::Number, "arithmetic and comparisons")?;
+        }
+    }
+
+    Ok(Node::Binary(op, Box::new(lhs), Box::new(rhs)))
+}
+
+fn check_kind(node: &Node, kind: ValueKind, context: &str) -> Result<()> {
+    if node.kind() != kind {
+        return Err(anyhow!(
+            "Expected {} operand for {}, got {}",
+            kind,
+            context,
+            node.kind()
+        ));
+    }
+
+    Ok(())
+}
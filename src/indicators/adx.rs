@@ -0,0 +1,346 @@
+use crate::{
+    indicators::{
+        atr::ATR, indicator::Indicator, indicator_args::IndicatorArgs,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Result};
+
+/// # Average Directional Index (ADX) and Directional Movement Index (DMI)
+///
+/// The directional indicators +DI and -DI measure the share of the true
+/// range made up by upwards and downwards movement. The ADX is the Wilder
+/// smoothed average of their normalised difference and measures the strength
+/// of a trend regardless of its direction. The smoothed sums are kept to
+/// allow rolling calculations on new candles.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct ADX {
+    pub adx: f64,
+    pub plus_di: f64,
+    pub minus_di: f64,
+    pub len: usize,
+    smoothed_tr: f64,
+    smoothed_plus_dm: f64,
+    smoothed_minus_dm: f64,
+}
+
+impl PopulatesCandles for ADX {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let indicator_type = IndicatorType::ADX(len);
+        let adxs = Self::calculate_series(&ts.candles, len);
+
+        for (i, adx) in adxs.into_iter().enumerate() {
+            ts.set_indicator(i, indicator_type, Indicator::ADX(adx));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let indicator_type = IndicatorType::ADX(len);
+        let candle_len = ts.candles.len();
+
+        if candle_len == 0 {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_adx());
+
+        let new_adx = match prev {
+            Some(prev) => Some(prev.next(&ts.candles[candle_len - 2], &ts.candles[candle_len - 1])),
+            // Not enough candles for a previous ADX, calculate from scratch
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::ADX(new_adx))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for ADX {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::LengthArg(14)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// The smoothing is seeded at the start of the segment, longer segments
+    /// give values closer to those of an ADX calculated over all available
+    /// data.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.len_opt()?;
+
+        if len == 0 || segment.len() < Self::needed_candles(len) {
+            return None;
+        }
+
+        *Self::calculate_series(segment, len).last()?
+    }
+}
+
+impl ADX {
+    /// Returns the ADX following this one given the previous and new candle.
+    pub fn next(&self, prev_candle: &Candle, candle: &Candle) -> Self {
+        let (tr, plus_dm, minus_dm) = Self::directional_movement(prev_candle, candle);
+        let mut next = Self::from_smoothed(
+            self.len,
+            wilder_sum(self.smoothed_tr, tr, self.len),
+            wilder_sum(self.smoothed_plus_dm, plus_dm, self.len),
+            wilder_sum(self.smoothed_minus_dm, minus_dm, self.len),
+        );
+
+        let f_len = self.len as f64;
+        next.adx = (self.adx * (f_len - 1.0) + next.dx()) / f_len;
+
+        next
+    }
+
+    /// Returns the directional index, the normalised difference of +DI and
+    /// -DI.
+    pub fn dx(&self) -> f64 {
+        let di_sum = self.plus_di + self.minus_di;
+
+        if di_sum == 0.0 {
+            return 0.0;
+        }
+
+        100.0 * (self.plus_di - self.minus_di).abs() / di_sum
+    }
+
+    fn needed_candles(len: usize) -> usize {
+        2 * len
+    }
+
+    /// Returns the true range and the positive and negative directional
+    /// movement of the candle.
+    fn directional_movement(prev_candle: &Candle, candle: &Candle) -> (f64, f64, f64) {
+        let up_move = candle.high - prev_candle.high;
+        let down_move = prev_candle.low - candle.low;
+
+        let plus_dm = if up_move > down_move && up_move > 0.0 {
+            up_move
+        } else {
+            0.0
+        };
+        let minus_dm = if down_move > up_move && down_move > 0.0 {
+            down_move
+        } else {
+            0.0
+        };
+
+        (
+            ATR::true_range(prev_candle.close, candle),
+            plus_dm,
+            minus_dm,
+        )
+    }
+
+    /// Returns an ADX with the directional indicators set from the smoothed
+    /// sums, the ADX itself is left for the caller to set.
+    fn from_smoothed(len: usize, tr: f64, plus_dm: f64, minus_dm: f64) -> Self {
+        let (plus_di, minus_di) = if tr == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (100.0 * plus_dm / tr, 100.0 * minus_dm / tr)
+        };
+
+        ADX {
+            adx: 0.0,
+            plus_di,
+            minus_di,
+            len,
+            smoothed_tr: tr,
+            smoothed_plus_dm: plus_dm,
+            smoothed_minus_dm: minus_dm,
+        }
+    }
+
+    /// Returns the ADX for every candle, None until enough candles are
+    /// available to average the first `len` directional indices.
+    fn calculate_series(candles: &[Candle], len: usize) -> Vec<Option<Self>> {
+        let mut sums = (0.0, 0.0, 0.0);
+        let mut dxs = Vec::new();
+        let mut prev: Option<ADX> = None;
+
+        (0..candles.len())
+            .map(|i| {
+                if i == 0 {
+                    return None;
+                }
+
+                if let Some(prev_adx) = prev {
+                    let adx = prev_adx.next(&candles[i - 1], &candles[i]);
+                    prev = Some(adx);
+                    return prev;
+                }
+
+                let (tr, plus_dm, minus_dm) =
+                    Self::directional_movement(&candles[i - 1], &candles[i]);
+
+                // Smoothing is seeded with the sum of the first len values
+                sums = if i <= len {
+                    (sums.0 + tr, sums.1 + plus_dm, sums.2 + minus_dm)
+                } else {
+                    (
+                        wilder_sum(sums.0, tr, len),
+                        wilder_sum(sums.1, plus_dm, len),
+                        wilder_sum(sums.2, minus_dm, len),
+                    )
+                };
+
+                if i < len {
+                    return None;
+                }
+
+                let mut adx = Self::from_smoothed(len, sums.0, sums.1, sums.2);
+                dxs.push(adx.dx());
+
+                if dxs.len() < len {
+                    return None;
+                }
+
+                adx.adx = dxs.iter().sum::<f64>() / len as f64;
+                prev = Some(adx);
+                prev
+            })
+            .collect()
+    }
+}
+
+/// Wilder smoothing of a running sum over `len` values.
+fn wilder_sum(prev_sum: f64, value: f64, len: usize) -> f64 {
+    prev_sum - prev_sum / len as f64 + value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            adx::ADX, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn adx_calculate() {
+        // Only upwards movement gives the maximum trend strength
+        let candles = Candle::dummy_data(40, "positive", 100.0);
+        let adx = ADX::calculate(&candles).unwrap();
+        assert_eq!(adx.plus_di, 100.0);
+        assert_eq!(adx.minus_di, 0.0);
+        assert_eq!(adx.adx, 100.0);
+    }
+
+    #[test]
+    fn adx_calculate_args() {
+        let candles = Candle::dummy_data(10, "negative", 1000.0);
+        let args = IndicatorArgs::LengthArg(5);
+        let adx = ADX::calculate_args(&candles, &args).unwrap();
+        assert_eq!(adx.plus_di, 0.0);
+        assert_eq!(adx.minus_di, 100.0);
+        assert_eq!(adx.adx, 100.0);
+    }
+
+    #[test]
+    fn adx_not_enough_candles() {
+        let candles = Candle::dummy_data(27, "positive", 100.0);
+        let adx = ADX::calculate(&candles);
+        assert!(adx.is_none());
+    }
+
+    #[test]
+    fn adx_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let adx = ADX::calculate(&candles);
+        assert!(adx.is_none());
+    }
+
+    #[test]
+    fn adx_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = ADX::default_args();
+        let adx = ADX::calculate_args(&candles, &args);
+        assert!(adx.is_none());
+    }
+
+    #[test]
+    fn adx_populate_candles() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = ADX::populate_candles(&mut ts);
+
+        for i in 0..ts.candles.len() {
+            let adx = ts.indicator(i, &IndicatorType::ADX(14)).unwrap().as_adx();
+            if i < 27 {
+                assert!(adx.is_none());
+            } else {
+                let adx = adx.unwrap();
+                assert!((0.0..=100.0).contains(&adx.adx));
+                assert!((0.0..=100.0).contains(&adx.plus_di));
+                assert!((0.0..=100.0).contains(&adx.minus_di));
+            }
+        }
+
+        let last_adx = ts
+            .view()
+            .last_indicator(&IndicatorType::ADX(14))
+            .unwrap()
+            .as_adx()
+            .unwrap();
+        assert_eq!(Some(last_adx), ADX::calculate(&ts.candles));
+    }
+
+    #[test]
+    fn adx_populate_last_candle() {
+        let mut candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = ADX::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_adx = ts
+            .view()
+            .last_indicator(&IndicatorType::ADX(14))
+            .unwrap()
+            .as_adx()
+            .unwrap();
+
+        assert_eq!(Some(last_adx), ADX::calculate(&ts.candles));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
+    models::{candle::Candle, candle_window::CandleView, timeseries::TimeSeries},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Copy, Clone)]
 pub struct ATR {
@@ -22,25 +23,10 @@ impl PopulatesCandles for ATR {
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let len = args.len_res()?;
         let indicator_type = IndicatorType::ATR(len);
+        ts.indicator_states.remove(&indicator_type);
 
-        let mut prev: Option<ATR> = None;
-
-        for i in 0..ts.candles.len() {
-            let atr = if i < len {
-                None
-            } else if i == len || prev.is_none() {
-                let start = i - len;
-                Self::calculate_args(&ts.candles[start..i + 1], &args)
-            } else {
-                let candles = (&ts.candles[i - 1], &ts.candles[i]);
-                let prev = prev.unwrap();
-                Self::calculate_rolling(candles, prev.value, len)
-            };
-
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::ATR(atr));
-            prev = atr;
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, len);
         }
 
         ts.indicators.insert(indicator_type);
@@ -54,49 +40,19 @@ impl PopulatesCandles for ATR {
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let len = args.len_res()?;
-        let indicator_type = IndicatorType::ATR(len);
-        let prev = Indicator::get_second_last(ts, &indicator_type)
-            .and_then(|indicator| indicator.as_atr());
-        let ctx_err = "Unable to get last candle.";
 
-        let candle_len = ts.candles.len();
-        if candle_len == 0 {
+        if ts.candles.is_empty() {
             return Err(anyhow!("Not enough candles to populate."));
         }
 
-        // Not enough candles to populate
-        if candle_len <= len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::ATR(None));
-
-            return Ok(());
-        };
-
-        // Calculate new and populate
-        let new_atr = if prev.is_none() {
-            let start = candle_len - len - 1;
-            let end = candle_len;
-            Self::calculate_args(&ts.candles[start..end], &args)
-        } else {
-            let candles = (&ts.candles[candle_len - 2], &ts.candles[candle_len - 1]);
-            Self::calculate_rolling(candles, prev.unwrap().value, len)
-        };
-
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::ATR(new_atr));
+        Self::populate_candle(ts, ts.candles.len(), len);
 
         Ok(())
     }
 }
 
 impl ATR {
-    fn calculate_rolling(
+    pub fn calculate_rolling(
         (prev_candle, curr_candle): (&Candle, &Candle),
         prev_atr: f64,
         len: usize,
@@ -108,7 +64,38 @@ impl ATR {
         Some(ATR { len, value: atr })
     }
 
-    fn true_range(prev: f64, curr: &Candle) -> f64 {
+    /// Populates the candle at `end - 1` from the Wilder smoothing of the true
+    /// ranges kept in the TimeSeries. The smoothing has no window, a missing
+    /// state is rebuilt from the first candle.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, len: usize) {
+        let indicator_type = IndicatorType::ATR(len);
+        let new_state = || IndicatorState::new().with_wilders(&[len]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            Self::next(state, view, i, len);
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, end, new_state, step);
+        let atr = Self::next(&mut state, ts.view(), end - 1, len);
+
+        state.put(ts, indicator_type, end);
+        ts.set_indicator(end - 1, indicator_type, Indicator::ATR(atr));
+    }
+
+    /// Updates the state with the true range of the candle at index `i` of
+    /// the view, returning the ATR once `len` true ranges have been added.
+    fn next(
+        state: &mut IndicatorState,
+        view: CandleView<'_>,
+        i: usize,
+        len: usize,
+    ) -> Option<Self> {
+        let prev_close = view[i.checked_sub(1)?].close;
+        let tr = Self::true_range(prev_close, &view[i]);
+
+        state.wilders[0].push(tr).map(|value| ATR { len, value })
+    }
+
+    pub fn true_range(prev: f64, curr: &Candle) -> f64 {
         let a = curr.high - curr.low;
         let b = (curr.high - prev).abs();
         let c = (curr.low - prev).abs();
@@ -165,6 +152,7 @@ mod tests {
             is_indicator::IsIndicator, populates_candles::PopulatesCandles,
         },
         models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
     };
 
     #[test]
@@ -200,8 +188,8 @@ mod tests {
         let len = ATR::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::ATR(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let atr = indicator.as_atr();
             if i < len {
                 assert!(atr.is_none());
@@ -210,10 +198,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_atr = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_atr = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_atr()
             .unwrap();
@@ -236,8 +223,8 @@ mod tests {
         let len = ATR::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::ATR(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let atr = indicator.as_atr();
             if i < len {
                 assert!(atr.is_none());
@@ -246,14 +233,52 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_atr = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_atr = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_atr()
             .unwrap();
 
         assert_eq!(last_atr.value, 10.0);
     }
+
+    #[test]
+    fn atr_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = IndicatorArgs::LengthArg(14);
+        let indicator_type = IndicatorType::ATR(14);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        ATR::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_atr());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_atr());
+            // Smoothing carries over the evicted candles
+            assert_eq!(value.map(|atr| atr.value), full_value.map(|atr| atr.value));
+        }
+    }
 }
@@ -1,12 +1,15 @@
 use crate::{
     indicators::{
         bollinger_bands::BollingerBands, indicator::Indicator, indicator_args::IndicatorArgs,
-        indicator_type::IndicatorType, is_indicator::IsIndicator,
-        populates_candles::PopulatesCandles,
+        indicator_columns::IndicatorColumns, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{
+        candle::Candle, candle_window::CandleView, fixed_point::FixedPoint, ma_type::MAType,
+        timeseries::TimeSeries,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 /// Bollinger Band Width
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -21,20 +24,14 @@ impl PopulatesCandles for BBW {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let indicator_type = IndicatorType::BBW(len);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bbw = if end < len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - len..end], &args)
-            };
-
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::BBW(bbw));
+        let (len, std_n, ma_type) = args.bb_res()?;
+        let indicator_type = IndicatorType::BBW(len, FixedPoint::new(std_n), ma_type);
+        ma_type.populate_rolling(ts, len)?;
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            let bbw = Self::populate_rolling(ts, end, &args, indicator_type)?;
+            ts.set_indicator(end - 1, indicator_type, Indicator::BBW(bbw));
         }
 
         ts.indicators.insert(indicator_type);
@@ -47,37 +44,25 @@ impl PopulatesCandles for BBW {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BBW(len);
+        let (len, std_n, ma_type) = args.bb_res()?;
+        let indicator_type = IndicatorType::BBW(len, FixedPoint::new(std_n), ma_type);
         let end = ts.candles.len();
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
-        } else if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBW(None));
-        } else {
-            let new_bbw = Self::calculate_args(&ts.candles[end - len..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBW(new_bbw));
         }
 
+        // Not enough candles results in None
+        let new_bbw = Self::populate_rolling(ts, end, &args, indicator_type)?;
+        ts.set_last_indicator(indicator_type, Indicator::BBW(new_bbw))?;
+
         Ok(())
     }
 }
 
 impl IsIndicator for BBW {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BollingerBandArgs(20, 2.0)
+        IndicatorArgs::BollingerBandArgs(20, 2.0, MAType::SMA)
     }
 
     /// Segment should have the same number of candles as the desired length of
@@ -88,7 +73,7 @@ impl IsIndicator for BBW {
     {
         let bb = BollingerBands::calculate(segment)?;
         Some(BBW {
-            value: (bb.upper - bb.lower) / bb.sma,
+            value: (bb.upper - bb.lower) / bb.basis,
             len: segment.len(),
         })
     }
@@ -97,12 +82,39 @@ impl IsIndicator for BBW {
     where
         Self: Sized,
     {
-        let bb = BollingerBands::calculate_args(segment, args)?;
+        let columns = IndicatorColumns::new(0, 0);
 
-        Some(BBW {
-            value: (bb.upper - bb.lower) / bb.sma,
-            len: segment.len(),
-        })
+        Self::calculate_view(CandleView::new(segment, &columns), args)
+    }
+}
+
+impl BBW {
+    /// Calculates the BBW of the last candles of the view, see
+    /// `BollingerBands::calculate_view`.
+    pub fn calculate_view(segment: CandleView<'_>, args: &IndicatorArgs) -> Option<Self> {
+        let bb = BollingerBands::calculate_view(segment, args)?;
+
+        Some(Self::from_bb(bb))
+    }
+
+    /// Returns the BBW of the candle at `end - 1` from the rolling state kept
+    /// under `indicator_type`, see `BollingerBands::populate_rolling`.
+    fn populate_rolling(
+        ts: &mut TimeSeries,
+        end: usize,
+        args: &IndicatorArgs,
+        indicator_type: IndicatorType,
+    ) -> Result<Option<Self>> {
+        let bb = BollingerBands::populate_rolling(ts, end, args, indicator_type)?;
+
+        Ok(bb.map(Self::from_bb))
+    }
+
+    fn from_bb(bb: BollingerBands) -> Self {
+        BBW {
+            value: (bb.upper - bb.lower) / bb.basis,
+            len: bb.len,
+        }
     }
 }
 
@@ -113,7 +125,11 @@ mod tests {
             bbw::BBW, indicator_type::IndicatorType, is_indicator::IsIndicator,
             populates_candles::PopulatesCandles,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::dummy_data::PRICE_CHANGES,
     };
 
     #[test]
@@ -161,11 +177,11 @@ mod tests {
 
         let _ = BBW::populate_candles(&mut ts);
 
-        let (len, _) = BBW::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BBW(len);
+        let (len, std_n, ma_type) = BBW::default_args().bb_opt().unwrap();
+        let indicator_type = IndicatorType::BBW(len, FixedPoint::new(std_n), ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bbw = indicator.as_bbw();
             if i < len - 1 {
                 assert!(bbw.is_none());
@@ -174,10 +190,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_bbw = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_bbw = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_bbw()
             .unwrap();
@@ -197,11 +212,11 @@ mod tests {
         let _ = BBW::populate_candles(&mut ts);
 
         let _ = ts.add_candle(&candle);
-        let (len, _) = BBW::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BBW(len);
+        let (len, std_n, ma_type) = BBW::default_args().bb_opt().unwrap();
+        let indicator_type = IndicatorType::BBW(len, FixedPoint::new(std_n), ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bbw = indicator.as_bbw();
             if i < len - 1 {
                 assert!(bbw.is_none());
@@ -210,14 +225,59 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_bbw = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_bbw = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_bbw()
             .unwrap();
 
         assert_eq!(last_bbw.value, 0.9280125149960182);
     }
+
+    #[test]
+    fn bbw_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = BBW::default_args();
+        let indicator_type = IndicatorType::BBW(20, FixedPoint::new(2.0), MAType::SMA);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        BBW::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_bbw());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_bbw());
+            assert_eq!(value, full_value);
+
+            let window = &candles[..offset + i + 1];
+            let expected = BBW::calculate_args(window, &args);
+            assert_eq!(value.is_some(), expected.is_some());
+
+            if let (Some(value), Some(expected)) = (value, expected) {
+                assert!((value.value - expected.value).abs() < 1e-12);
+            }
+        }
+    }
 }
@@ -1,10 +1,13 @@
 use crate::{
     indicators::{
         bbw::BBW, indicator::Indicator, indicator_args::IndicatorArgs,
-        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        indicator_state::IndicatorState, indicator_type::IndicatorType, is_indicator::IsIndicator,
         populates_candles::PopulatesCandles, sma::SMA,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
+    models::{
+        candle::Candle, candle_window::CandleView, fixed_point::FixedPoint, ma_type::MAType,
+        timeseries::TimeSeries,
+    },
     utils::math::sma,
 };
 use anyhow::{anyhow, Context, Result};
@@ -23,55 +26,20 @@ pub struct BBWP {
     pub sma: Option<SMA>,
 }
 
-// Default number of standard deviations for BBW calculation
-const BBW_STD_N: f64 = 1.0;
-
 impl PopulatesCandles for BBWP {
     fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
         Self::populate_candles_args(ts, Self::default_args())
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, sma_len) = args.bbwp_res()?;
-        let indicator_type = IndicatorType::BBWP(len, lookback);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bbwp = if end < len {
-                None
-            } else {
-                let start = if end > lookback + len {
-                    end - lookback - len
-                } else {
-                    0
-                };
-
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
-
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::BBWP(bbwp));
-
-            // Not enough candles to populate bbwp sma so return
-            if end < len + sma_len {
-                continue;
-            }
-
-            let mut bbwp = bbwp.context("Unable to calculate BBWP")?;
-            let sma_segment = &ts.candles[end - sma_len..end];
-            let sma = Self::bbwp_sma(sma_segment, &indicator_type);
-
-            if let Some(sma) = sma {
-                bbwp.sma = Some(SMA {
-                    len: sma_len,
-                    value: sma,
-                });
-
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::BBWP(Some(bbwp)));
-            }
+        let (len, lookback, sma_len, std_n, ma_type) = args.bbwp_res()?;
+        let indicator_type =
+            IndicatorType::BBWP(len, lookback, sma_len, FixedPoint::new(std_n), ma_type);
+        ma_type.populate_rolling(ts, len)?;
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, &args)?;
         }
 
         ts.indicators.insert(indicator_type);
@@ -84,69 +52,21 @@ impl PopulatesCandles for BBWP {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, sma_len) = args.bbwp_res()?;
-        let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BBWP(len, lookback);
         let end = ts.candles.len();
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
         }
 
-        // Not enough candles to populate
-        if end < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBWP(None));
-
-            return Ok(());
-        }
-
-        let start = if end > lookback + len {
-            end - lookback - len
-        } else {
-            0
-        };
-
-        let mut new_bbwp = Self::calculate_args(&ts.candles[start..end], &args)
-            .context("Unable to calculate BBWP")?;
-
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::BBWP(Some(new_bbwp)));
-
-        // Not enough candles to populate bbwp sma so return
-        if end < len + sma_len {
-            return Ok(());
-        }
-
-        let sma_segment = &ts.candles[end - sma_len..end];
-        let sma = Self::bbwp_sma(sma_segment, &indicator_type);
-
-        if let Some(sma) = sma {
-            new_bbwp.sma = Some(SMA {
-                len: sma_len,
-                value: sma,
-            });
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BBWP(Some(new_bbwp)));
-        }
-
-        Ok(())
+        Self::populate_candle(ts, end, &args)
     }
 }
 
 impl IsIndicator for BBWP {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BBWPArgs(13, 252, 5) // len, lookback, sma_len
+        // The percentile does not depend on the number of standard deviations
+        // as all widths are scaled equally
+        IndicatorArgs::BBWPArgs(13, 252, 5, 1.0, MAType::SMA) // len, lookback, sma_len, std_n, basis
     }
 
     /// Note: Due to api based on segment length, the segment needs "len"
@@ -170,7 +90,7 @@ impl IsIndicator for BBWP {
 impl BBWP {
     fn calculate_bbwp(segment: &[Candle], args: &IndicatorArgs) -> Option<Self> {
         let bbws = Self::get_bbws(segment, args).ok()?;
-        let (len, lookback, _) = args.bbwp_opt()?;
+        let (len, lookback, _, _, _) = args.bbwp_opt()?;
         let new_bbw = bbws.last()?.as_ref()?;
 
         let count = bbws
@@ -190,10 +110,77 @@ impl BBWP {
         })
     }
 
-    fn bbwp_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
+    /// Populates the candle at `end - 1`. The percentile is ranked against the
+    /// rolling window of previous BBWs kept in the TimeSeries so that only the
+    /// new BBW needs to be calculated.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, args: &IndicatorArgs) -> Result<()> {
+        let (len, lookback, sma_len, std_n, ma_type) = args.bbwp_res()?;
+        let indicator_type =
+            IndicatorType::BBWP(len, lookback, sma_len, FixedPoint::new(std_n), ma_type);
+        let bbw_at = |view: CandleView<'_>, i: usize| Self::bbw_at(view, i, len, std_n, ma_type);
+
+        let new_state = || IndicatorState::new().with_percentiles(&[lookback]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            state.percentiles[0].push(bbw_at(view, i))
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, lookback, new_state, step);
+        let new_bbw = bbw_at(ts.view(), end - 1);
+
+        let bbwp = new_bbw.map(|new_bbw| BBWP {
+            value: state.percentiles[0].rank(new_bbw),
+            len,
+            lookback,
+            sma: None,
+        });
+
+        state.percentiles[0].push(new_bbw);
+        state.put(ts, indicator_type, end);
+
+        ts.set_indicator(end - 1, indicator_type, Indicator::BBWP(bbwp));
+
+        // Not enough candles to populate bbwp sma so return
+        if end < len + sma_len {
+            return Ok(());
+        }
+
+        let mut bbwp = bbwp.context("Unable to calculate BBWP")?;
+        let sma_segment = ts.view().window(end - sma_len, end);
+        let sma = Self::bbwp_sma(sma_segment, &indicator_type);
+
+        if let Some(sma) = sma {
+            bbwp.sma = Some(SMA {
+                len: sma_len,
+                value: sma,
+            });
+
+            ts.set_indicator(end - 1, indicator_type, Indicator::BBWP(Some(bbwp)));
+        }
+
+        Ok(())
+    }
+
+    /// BBW value of the candle at index `i`.
+    fn bbw_at(
+        view: CandleView<'_>,
+        i: usize,
+        len: usize,
+        std_n: f64,
+        ma_type: MAType,
+    ) -> Option<f64> {
+        if i + 1 < len {
+            return None;
+        }
+
+        let args = IndicatorArgs::BollingerBandArgs(len, std_n, ma_type);
+        BBW::calculate_view(view.window(i + 1 - len, i + 1), &args).map(|bbw| bbw.value)
+    }
+
+    fn bbwp_sma(segment: CandleView<'_>, indicator_type: &IndicatorType) -> Option<f64> {
         let values: Vec<f64> = segment
+            .column(indicator_type)
             .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_bbwp())
+            .filter_map(|bbwp| bbwp?.as_bbwp())
             .map(|bbwp| bbwp.value)
             .collect();
 
@@ -209,16 +196,16 @@ impl BBWP {
         let mut temp_ts = TimeSeries::dummy();
         temp_ts.set_candles(&temp_segment);
 
-        let (len, _, _) = args.bbwp_res()?;
-        let bbw_args = IndicatorArgs::BollingerBandArgs(len, BBW_STD_N);
+        let (len, _, _, std_n, ma_type) = args.bbwp_res()?;
+        let bbw_args = IndicatorArgs::BollingerBandArgs(len, std_n, ma_type);
         BBW::populate_candles_args(&mut temp_ts, bbw_args)?;
 
-        let ind_type = IndicatorType::BBW(len);
+        let ind_type = IndicatorType::BBW(len, FixedPoint::new(std_n), ma_type);
 
         let bbws: Vec<Option<BBW>> = temp_ts
-            .candles
+            .indicator_column(&ind_type)
             .iter()
-            .map(|candle| candle.clone_indicator(&ind_type).unwrap().as_bbw())
+            .map(|bbw| bbw?.as_bbw())
             .collect();
 
         Ok(bbws)
@@ -232,7 +219,10 @@ mod tests {
             bbwp::BBWP, indicator_type::IndicatorType, is_indicator::IsIndicator,
             populates_candles::PopulatesCandles,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
+        },
         utils::data::dummy_data::PRICE_CHANGES,
     };
 
@@ -256,12 +246,16 @@ mod tests {
 
         let _ = BBWP::populate_candles(&mut ts);
 
-        let segment = &ts.candles[ts.candles.len() - 5..];
+        let segment = ts.view().window(ts.candles.len() - 5, ts.candles.len());
 
-        let (len, lookback, _) = BBWP::default_args().bbwp_opt().unwrap();
+        let (len, lookback, sma_len, std_n, ma_type) = BBWP::default_args().bbwp_opt().unwrap();
+        let std_n = FixedPoint::new(std_n);
         for (i, val) in FINAL_VALUES.iter().enumerate() {
-            let bbwp = segment[i]
-                .clone_indicator(&IndicatorType::BBWP(len, lookback))
+            let bbwp = segment
+                .indicator(
+                    i,
+                    &IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type),
+                )
                 .unwrap()
                 .as_bbwp()
                 .unwrap();
@@ -295,11 +289,12 @@ mod tests {
 
         let _ = BBWP::populate_candles(&mut ts);
 
-        let (len, lookback, _sma_len) = BBWP::default_args().bbwp_opt().unwrap();
-        let indicator_type = IndicatorType::BBWP(len, lookback);
+        let (len, lookback, sma_len, std_n, ma_type) = BBWP::default_args().bbwp_opt().unwrap();
+        let std_n = FixedPoint::new(std_n);
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bbwp = indicator.as_bbwp();
             if i < len - 1 {
                 assert!(bbwp.is_none());
@@ -308,12 +303,16 @@ mod tests {
             }
         }
 
-        let segment = &ts.candles[ts.candles.len() - 5..];
+        let segment = ts.view().window(ts.candles.len() - 5, ts.candles.len());
 
-        let (len, lookback, _) = BBWP::default_args().bbwp_opt().unwrap();
+        let (len, lookback, sma_len, std_n, ma_type) = BBWP::default_args().bbwp_opt().unwrap();
+        let std_n = FixedPoint::new(std_n);
         for (i, val) in FINAL_VALUES.iter().enumerate() {
-            let bbwp = segment[i]
-                .clone_indicator(&IndicatorType::BBWP(len, lookback))
+            let bbwp = segment
+                .indicator(
+                    i,
+                    &IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type),
+                )
                 .unwrap()
                 .as_bbwp()
                 .unwrap();
@@ -334,11 +333,12 @@ mod tests {
         let _ = BBWP::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let (len, lookback, _sma_len) = BBWP::default_args().bbwp_opt().unwrap();
-        let indicator_type = IndicatorType::BBWP(len, lookback);
+        let (len, lookback, sma_len, std_n, ma_type) = BBWP::default_args().bbwp_opt().unwrap();
+        let std_n = FixedPoint::new(std_n);
+        let indicator_type = IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bbwp = indicator.as_bbwp();
             if i < len - 1 {
                 assert!(bbwp.is_none());
@@ -347,14 +347,42 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_bbwp = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_bbwp = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_bbwp()
             .unwrap();
 
         assert_eq!(&last_bbwp.value, FINAL_VALUES.last().unwrap());
     }
+
+    #[test]
+    fn bbwp_populate_last_candle_matches_populate_candles() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let indicator_type = IndicatorType::BBWP(13, 100, 5, FixedPoint::new(1.0), MAType::SMA);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        full_ts.add_indicator(indicator_type).unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        for i in 0..ts.candles.len() {
+            let bbwp = ts.indicator(i, &indicator_type).unwrap().as_bbwp();
+            let full_bbwp = full_ts.indicator(i, &indicator_type).unwrap().as_bbwp();
+            assert_eq!(bbwp, full_bbwp);
+        }
+    }
 }
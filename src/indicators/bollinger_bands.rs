@@ -1,18 +1,22 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_columns::IndicatorColumns,
+        indicator_state::IndicatorState, indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
-    utils::math::{sma, std},
+    models::{
+        candle::Candle, candle_window::CandleView, fixed_point::FixedPoint, ma_type::MAType,
+        timeseries::TimeSeries,
+    },
+    utils::math::std,
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct BollingerBands {
     pub upper: f64,
     pub lower: f64,
-    pub sma: f64,
+    pub basis: f64,
     pub std: f64,
     pub len: usize,
 }
@@ -23,20 +27,14 @@ impl PopulatesCandles for BollingerBands {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let indicator_type = IndicatorType::BollingerBands(len);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let bb = if end < len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - len..end], &args)
-            };
-
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::BollingerBands(bb));
+        let (len, std_n, ma_type) = args.bb_res()?;
+        let indicator_type = IndicatorType::BollingerBands(len, FixedPoint::new(std_n), ma_type);
+        ma_type.populate_rolling(ts, len)?;
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            let bb = Self::populate_rolling(ts, end, &args, indicator_type)?;
+            ts.set_indicator(end - 1, indicator_type, Indicator::BollingerBands(bb));
         }
 
         ts.indicators.insert(indicator_type);
@@ -49,37 +47,25 @@ impl PopulatesCandles for BollingerBands {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, _) = args.bb_res()?;
-        let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let (len, std_n, ma_type) = args.bb_res()?;
+        let indicator_type = IndicatorType::BollingerBands(len, FixedPoint::new(std_n), ma_type);
         let end = ts.candles.len();
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
-        } else if end < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BollingerBands(None));
-        } else {
-            let new_bb = Self::calculate_args(&ts.candles[end - len..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::BollingerBands(new_bb));
         }
 
+        // Not enough candles results in None
+        let new_bb = Self::populate_rolling(ts, end, &args, indicator_type)?;
+        ts.set_last_indicator(indicator_type, Indicator::BollingerBands(new_bb))?;
+
         Ok(())
     }
 }
 
 impl IsIndicator for BollingerBands {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::BollingerBandArgs(20, 2.0)
+        IndicatorArgs::BollingerBandArgs(20, 2.0, MAType::SMA)
     }
 
     /// Segment should have the same number of candles as the desired length of
@@ -92,46 +78,104 @@ impl IsIndicator for BollingerBands {
             return None;
         }
 
-        let (_, std_n) = Self::default_args().bb_opt()?;
-        let args = IndicatorArgs::BollingerBandArgs(segment.len(), std_n);
+        let (_, std_n, ma_type) = Self::default_args().bb_opt()?;
+        let args = IndicatorArgs::BollingerBandArgs(segment.len(), std_n, ma_type);
+        let columns = IndicatorColumns::new(0, 0);
 
-        Self::calculate_bb(segment, &args)
+        Self::calculate_bb(CandleView::new(segment, &columns), &args)
     }
 
     fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
     where
         Self: Sized,
     {
-        let (arg_len, _) = args.bb_opt()?;
-        let candle_len = segment.len();
+        let columns = IndicatorColumns::new(0, 0);
 
-        if candle_len < arg_len {
+        Self::calculate_view(CandleView::new(segment, &columns), args)
+    }
+}
+
+impl BollingerBands {
+    /// Calculates the BollingerBands of the last candles of the view. EMA
+    /// bases are read from the indicators of the view, so they are None
+    /// unless the EMA has been populated.
+    pub fn calculate_view(segment: CandleView<'_>, args: &IndicatorArgs) -> Option<Self> {
+        let (arg_len, _, _) = args.bb_opt()?;
+
+        if segment.len() < arg_len {
             return None;
         }
 
-        Self::calculate_bb(&segment[candle_len - arg_len..candle_len], args)
+        Self::calculate_bb(segment.tail(arg_len), args)
     }
-}
 
-impl BollingerBands {
-    fn calculate_bb(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
-    where
-        Self: Sized,
-    {
-        let (_, std_n) = args.bb_opt()?;
+    /// Returns the BollingerBands of the candle at `end - 1`. The running sums
+    /// of the closes (and of the volumes for VWMA bases) are kept in the
+    /// TimeSeries under `indicator_type` so that only the new candle needs to
+    /// be added. EMA bases are read from the populated EMA.
+    pub fn populate_rolling(
+        ts: &mut TimeSeries,
+        end: usize,
+        args: &IndicatorArgs,
+        indicator_type: IndicatorType,
+    ) -> Result<Option<Self>> {
+        let (len, std_n, ma_type) = args.bb_res()?;
+        let new_state = || IndicatorState::new().with_sums(&[len, len, len]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            let candle = &view[i];
+            state.sums[0].push(candle.close);
+            state.sums[1].push(candle.close * candle.volume);
+            state.sums[2].push(candle.volume);
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, len, new_state, step);
+        step(&mut state, ts.view(), end - 1);
+
+        let basis = match ma_type {
+            _ if !state.sums[0].is_full() => None,
+            MAType::SMA => Some(state.sums[0].mean()),
+            MAType::EMA => ts
+                .indicator(end - 1, &ma_type.indicator_type(len))
+                .and_then(|ema| ema.as_ema())
+                .map(|ema| ema.value),
+            MAType::VWMA => {
+                (!state.sums[2].is_zero()).then(|| state.sums[1].mean() / state.sums[2].mean())
+            }
+        };
+
+        let bb = basis.map(|basis| {
+            let std = state.sums[0].std_from(basis);
+
+            BollingerBands {
+                upper: basis + std_n * std,
+                lower: basis - std_n * std,
+                std,
+                basis,
+                len,
+            }
+        });
+
+        state.put(ts, indicator_type, end);
+
+        Ok(bb)
+    }
+
+    fn calculate_bb(segment: CandleView<'_>, args: &IndicatorArgs) -> Option<Self> {
+        let (_, std_n, ma_type) = args.bb_opt()?;
         let values: Vec<f64> = segment.iter().map(|c| c.close).collect();
 
-        let sma = sma(&values);
-        let std = std(&values, sma);
+        // Deviations are measured from the basis, which is the mean for SMAs
+        let basis = ma_type.calculate(segment, segment.len())?;
+        let std = std(&values, basis);
 
-        let upper = sma + std_n * std;
-        let lower = sma - std_n * std;
+        let upper = basis + std_n * std;
+        let lower = basis - std_n * std;
 
         Some(BollingerBands {
             upper,
             lower,
             std,
-            sma,
+            basis,
             len: segment.len(),
         })
     }
@@ -141,10 +185,15 @@ impl BollingerBands {
 mod tests {
     use crate::{
         indicators::{
-            bollinger_bands::BollingerBands, indicator_type::IndicatorType,
-            is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+            bollinger_bands::BollingerBands, indicator_args::IndicatorArgs,
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder,
         },
-        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
     };
 
     #[test]
@@ -186,11 +235,11 @@ mod tests {
 
         let _ = BollingerBands::populate_candles(&mut ts);
 
-        let (len, _) = BollingerBands::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let (len, std_n, ma_type) = BollingerBands::default_args().bb_opt().unwrap();
+        let indicator_type = IndicatorType::BollingerBands(len, FixedPoint::new(std_n), ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bb = indicator.as_bollinger_bands();
             if i < len - 1 {
                 assert!(bb.is_none());
@@ -199,10 +248,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_bb = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_bb = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_bollinger_bands()
             .unwrap();
@@ -223,11 +271,11 @@ mod tests {
 
         let _ = ts.add_candle(&candle);
 
-        let (len, _) = BollingerBands::default_args().bb_opt().unwrap();
-        let indicator_type = IndicatorType::BollingerBands(len);
+        let (len, std_n, ma_type) = BollingerBands::default_args().bb_opt().unwrap();
+        let indicator_type = IndicatorType::BollingerBands(len, FixedPoint::new(std_n), ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let bb = indicator.as_bollinger_bands();
             if i < len - 1 {
                 assert!(bb.is_none());
@@ -236,14 +284,110 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_bb = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_bb = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_bollinger_bands()
             .unwrap();
 
         assert_eq!(last_bb.upper, 373.3215956619923);
     }
+
+    #[test]
+    fn bb_populate_multiple_configurations() {
+        let candles = Candle::dummy_data(25, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = BollingerBands::populate_candles_args(
+            &mut ts,
+            IndicatorArgs::BollingerBandArgs(20, 2.0, MAType::SMA),
+        );
+        let _ = BollingerBands::populate_candles_args(
+            &mut ts,
+            IndicatorArgs::BollingerBandArgs(20, 2.5, MAType::SMA),
+        );
+        let _ = BollingerBands::populate_candles_args(
+            &mut ts,
+            IndicatorArgs::BollingerBandArgs(20, 2.0, MAType::EMA),
+        );
+
+        let bb = |std_n: f64, ma_type: MAType| {
+            ts.view()
+                .last_indicator(&IndicatorType::BollingerBands(
+                    20,
+                    FixedPoint::new(std_n),
+                    ma_type,
+                ))
+                .unwrap()
+                .as_bollinger_bands()
+                .unwrap()
+        };
+
+        let narrow = bb(2.0, MAType::SMA);
+        let wide = bb(2.5, MAType::SMA);
+        assert_eq!(narrow.basis, wide.basis);
+        assert_eq!(wide.upper, wide.basis + 2.5 * narrow.std);
+
+        // EMA basis is read from the EMA populated alongside the bands
+        let ema = ts
+            .view()
+            .last_indicator(&IndicatorType::EMA(20))
+            .unwrap()
+            .as_ema()
+            .unwrap();
+        assert_eq!(bb(2.0, MAType::EMA).basis, ema.value);
+    }
+
+    #[test]
+    fn bb_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = BollingerBands::default_args();
+        let indicator_type = IndicatorType::BollingerBands(20, FixedPoint::new(2.0), MAType::SMA);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        BollingerBands::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_bollinger_bands());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_bollinger_bands());
+            assert_eq!(value, full_value);
+
+            let window = &candles[..offset + i + 1];
+            let expected = BollingerBands::calculate_args(window, &args);
+            assert_eq!(value.is_some(), expected.is_some());
+
+            if let (Some(value), Some(expected)) = (value, expected) {
+                assert!((value.basis - expected.basis).abs() < 1e-9);
+                assert!((value.upper - expected.upper).abs() < 1e-9);
+                assert!((value.lower - expected.lower).abs() < 1e-9);
+            }
+        }
+    }
 }
@@ -0,0 +1,265 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, candle_window::CandleView, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Result};
+
+/// # Donchian Channels
+///
+/// The highest high and lowest low of the n most recent candles, including
+/// the current one, and their midpoint.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct DonchianChannels {
+    pub upper: f64,
+    pub lower: f64,
+    pub middle: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for DonchianChannels {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let indicator_type = IndicatorType::DonchianChannels(len);
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, len);
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        Self::populate_candle(ts, ts.candles.len(), len);
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for DonchianChannels {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::LengthArg(20)
+    }
+
+    /// Segment should be the same length as the Donchian Channels wanted.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if segment.is_empty() {
+            return None;
+        }
+
+        let (upper, lower) = segment
+            .iter()
+            .fold((f64::MIN, f64::MAX), |(upper, lower), candle| {
+                (upper.max(candle.high), lower.min(candle.low))
+            });
+
+        Some(DonchianChannels {
+            upper,
+            lower,
+            middle: (upper + lower) / 2.0,
+            len: segment.len(),
+        })
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.len_opt()?;
+        let candle_len = segment.len();
+
+        if len == 0 || candle_len < len {
+            return None;
+        }
+
+        Self::calculate(&segment[candle_len - len..candle_len])
+    }
+}
+
+impl DonchianChannels {
+    /// Populates the candle at `end - 1` from the highest high and lowest low
+    /// of the previous candles kept in the TimeSeries. Not enough candles
+    /// results in None.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, len: usize) {
+        let indicator_type = IndicatorType::DonchianChannels(len);
+        let new_state = || IndicatorState::new().with_extremes(&[len]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            state.extremes[0].push(view[i].high, view[i].low)
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, len, new_state, step);
+        step(&mut state, ts.view(), end - 1);
+
+        let dc = state.extremes[0]
+            .high_low()
+            .map(|(upper, lower)| DonchianChannels {
+                upper,
+                lower,
+                middle: (upper + lower) / 2.0,
+                len,
+            });
+
+        state.put(ts, indicator_type, end);
+        ts.set_indicator(end - 1, indicator_type, Indicator::DonchianChannels(dc));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            donchian_channels::DonchianChannels, indicator_args::IndicatorArgs,
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn donchian_channels_calculate() {
+        let candles = Candle::dummy_data(5, "alternating", 100.0);
+        let dc = DonchianChannels::calculate(&candles).unwrap();
+        assert_eq!(dc.upper, 110.0);
+        assert_eq!(dc.lower, 100.0);
+        assert_eq!(dc.middle, 105.0);
+    }
+
+    #[test]
+    fn donchian_channels_calculate_args() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let args = IndicatorArgs::LengthArg(4);
+        let dc = DonchianChannels::calculate_args(&candles, &args).unwrap();
+        assert_eq!(dc.upper, 200.0);
+        assert_eq!(dc.lower, 170.0);
+        assert_eq!(dc.len, 4);
+    }
+
+    #[test]
+    fn donchian_channels_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let dc = DonchianChannels::calculate(&candles);
+        assert!(dc.is_none());
+    }
+
+    #[test]
+    fn donchian_channels_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = DonchianChannels::default_args();
+        let dc = DonchianChannels::calculate_args(&candles, &args);
+        assert!(dc.is_none());
+    }
+
+    #[test]
+    fn donchian_channels_populate_candles() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = DonchianChannels::populate_candles_args(&mut ts, IndicatorArgs::LengthArg(4));
+
+        for i in 0..ts.candles.len() {
+            let dc = ts
+                .indicator(i, &IndicatorType::DonchianChannels(4))
+                .unwrap()
+                .as_donchian_channels();
+            if i < 3 {
+                assert!(dc.is_none());
+            } else {
+                assert_eq!(dc.unwrap().upper, ts.candles[i].close);
+            }
+        }
+    }
+
+    #[test]
+    fn donchian_channels_populate_last_candle() {
+        let mut candles = Candle::dummy_data(10, "positive", 100.0);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = DonchianChannels::populate_candles_args(&mut ts, IndicatorArgs::LengthArg(4));
+        let _ = ts.add_candle(&candle);
+
+        let last_dc = ts
+            .view()
+            .last_indicator(&IndicatorType::DonchianChannels(4))
+            .unwrap()
+            .as_donchian_channels()
+            .unwrap();
+
+        assert_eq!(last_dc.upper, 200.0);
+        assert_eq!(last_dc.lower, 170.0);
+    }
+
+    #[test]
+    fn donchian_channels_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = IndicatorArgs::LengthArg(20);
+        let indicator_type = IndicatorType::DonchianChannels(20);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        DonchianChannels::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_donchian_channels());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_donchian_channels());
+            assert_eq!(value, full_value);
+
+            // Extremes are exact, matching a scan of the window
+            let window = &candles[..offset + i + 1];
+            assert_eq!(value, DonchianChannels::calculate_args(window, &args));
+        }
+    }
+}
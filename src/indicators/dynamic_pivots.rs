@@ -5,7 +5,7 @@ use crate::{
     },
     models::{candle::Candle, timeseries::TimeSeries},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 /// #DynamicPivot indicator
 ///
@@ -29,20 +29,19 @@ impl PopulatesCandles for DynamicPivots {
 
         for i in 0..ts.candles.len() {
             let end = i + 1;
-            let pivot = if end < min_len {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - min_len..end], &args)
-            };
-
             // Since the dynamic pivots are populated for the "len/2"-nth
             // (by default 15+1 = 16) candle we need extra handling to select
             // the correct index when populating.
             let j = if end <= len { i } else { i - len };
 
-            ts.candles[j]
-                .indicators
-                .insert(indicator_type, Indicator::DynamicPivot(pivot));
+            let pivot = if end < min_len {
+                None
+            } else {
+                let prev = Self::get_prev(ts, &indicator_type, j);
+                Self::calculate_with_prev(&ts.candles[end - min_len..end], prev)
+            };
+
+            ts.set_indicator(j, indicator_type, Indicator::DynamicPivot(pivot));
         }
 
         ts.indicators.insert(indicator_type);
@@ -51,37 +50,31 @@ impl PopulatesCandles for DynamicPivots {
     }
 
     fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
-        Self::populate_candles_args(ts, Self::default_args())
+        Self::populate_last_candle_args(ts, Self::default_args())
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let len = args.len_res()?;
-        let ctx_err = "Unable to get last candle";
         let indicator_type = IndicatorType::DynamicPivot(len);
-        let candle_len = ts.candles.len();
+        let end = ts.candles.len();
         let min_len = 2 * len + 1;
-        if candle_len == 0 {
+
+        if end == 0 {
             return Err(anyhow!("No candle to populate"));
         }
 
-        if candle_len < len {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::DynamicPivot(None));
+        // Same index selection as when populating all candles, see
+        // populate_candles_args
+        let j = if end <= len { end - 1 } else { end - 1 - len };
 
-            return Ok(());
-        }
-
-        let new_pivot = Self::calculate_args(&ts.candles[candle_len - min_len..candle_len], &args);
+        let pivot = if end < min_len {
+            None
+        } else {
+            let prev = Self::get_prev(ts, &indicator_type, j);
+            Self::calculate_with_prev(&ts.candles[end - min_len..end], prev)
+        };
 
-        ts.candles
-            .get_mut(candle_len - (len + 1))
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::DynamicPivot(new_pivot));
+        ts.set_indicator(j, indicator_type, Indicator::DynamicPivot(pivot));
 
         Ok(())
     }
@@ -96,6 +89,36 @@ impl IsIndicator for DynamicPivots {
     where
         Self: Sized,
     {
+        Self::calculate_with_prev(segment, None)
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.len_opt()?;
+        let segment_len = segment.len();
+
+        if segment_len < 2 * len + 1 {
+            return None;
+        }
+
+        Self::calculate(&segment[segment_len - 2 * len - 1..segment_len])
+    }
+}
+
+impl DynamicPivots {
+    pub fn new_empty(len: usize) -> Self {
+        Self {
+            len,
+            high: None,
+            low: None,
+        }
+    }
+
+    /// Calculates the pivots of the middle candle of `segment`, carrying over
+    /// the pivots of the previous candle where none are found.
+    fn calculate_with_prev(segment: &[Candle], prev: Option<DynamicPivots>) -> Option<Self> {
         // Unable to calculate for even number of Candles
         if segment.len() % 2 == 0 {
             return None;
@@ -108,10 +131,6 @@ impl IsIndicator for DynamicPivots {
         let is_low = segment.iter().all(|c| c.low >= candle.low);
 
         let mut pivots = DynamicPivots::new_empty(len);
-        let prev = segment[len - 1]
-            .indicators
-            .get(&IndicatorType::DynamicPivot(len))
-            .and_then(|p| p.as_dynamic_pivots());
 
         pivots.high = if is_high {
             Some(candle.high)
@@ -128,27 +147,62 @@ impl IsIndicator for DynamicPivots {
         Some(pivots)
     }
 
-    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
-    where
-        Self: Sized,
-    {
-        let len = args.len_opt()?;
-        let segment_len = segment.len();
-
-        if segment_len < 2 * len + 1 {
-            return None;
-        }
-
-        Self::calculate(&segment[segment_len - 2 * len - 1..segment_len])
+    /// Returns the pivots of the candle preceding the candle at index `j`.
+    fn get_prev(
+        ts: &TimeSeries,
+        indicator_type: &IndicatorType,
+        j: usize,
+    ) -> Option<DynamicPivots> {
+        ts.indicator(j.checked_sub(1)?, indicator_type)?
+            .as_dynamic_pivots()
     }
 }
 
-impl DynamicPivots {
-    pub fn new_empty(len: usize) -> Self {
-        Self {
-            len,
-            high: None,
-            low: None,
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            dynamic_pivots::DynamicPivots, indicator_type::IndicatorType,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn dynamic_pivots_populate_last_candle() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let indicator_type = IndicatorType::DynamicPivot(15);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        DynamicPivots::populate_candles(&mut full_ts).unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        for i in 0..ts.candles.len() {
+            let pivots = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_dynamic_pivots());
+            let full_pivots = full_ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_dynamic_pivots());
+
+            assert_eq!(
+                pivots.map(|p| (p.high, p.low)),
+                full_pivots.map(|p| (p.high, p.low))
+            );
         }
     }
 }
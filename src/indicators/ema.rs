@@ -6,7 +6,7 @@ use crate::{
     models::{candle::Candle, timeseries::TimeSeries},
     utils::math::{ema_rolling, sma},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct EMA {
@@ -38,9 +38,7 @@ impl PopulatesCandles for EMA {
                 Self::calculate_rolling(prev, current, len)
             };
 
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::EMA(ema));
+            ts.set_indicator(i, indicator_type, Indicator::EMA(ema));
             prev_ema = ema;
         }
 
@@ -57,7 +55,6 @@ impl PopulatesCandles for EMA {
         let len = args.len_res()?;
         let indicator_type = IndicatorType::EMA(len);
         let end = ts.candles.len();
-        let ctx_err = "Unable to get last candle";
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
@@ -65,11 +62,7 @@ impl PopulatesCandles for EMA {
 
         // Not enough candles to get new EMA
         if end < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::EMA(None));
+            ts.set_last_indicator(indicator_type, Indicator::EMA(None))?;
 
             return Ok(());
         }
@@ -88,11 +81,7 @@ impl PopulatesCandles for EMA {
             Self::calculate_rolling(prev.value, current, len)
         };
 
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::EMA(new_ema));
+        ts.set_last_indicator(indicator_type, Indicator::EMA(new_ema))?;
 
         Ok(())
     }
@@ -207,8 +196,8 @@ mod tests {
         let len = EMA::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::EMA(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let ema = indicator.as_ema();
             if i < len {
                 assert!(ema.is_none());
@@ -217,10 +206,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_sma = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_sma = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_ema()
             .unwrap();
@@ -244,8 +232,8 @@ mod tests {
         let len = EMA::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::EMA(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let ema = indicator.as_ema();
             if i < len {
                 assert!(ema.is_none());
@@ -254,10 +242,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_ema = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_ema = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_ema()
             .unwrap();
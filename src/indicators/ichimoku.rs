@@ -0,0 +1,378 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, candle_window::CandleView, timeseries::TimeSeries},
+    utils::rolling::RollingExtremes,
+};
+use anyhow::{anyhow, Result};
+
+/// # Ichimoku Cloud
+///
+/// Tenkan-sen, kijun-sen and senkou span B are midpoints of the highest high
+/// and lowest low over their respective lengths, senkou span A is the
+/// midpoint of the tenkan-sen and kijun-sen. The senkou spans are plotted
+/// kijun-length candles ahead and the chikou span (the close) kijun-length
+/// candles behind.
+///
+/// The senkou spans are the values calculated at this candle, to be plotted
+/// ahead. The cloud at this candle, i.e. the senkou spans calculated
+/// kijun-length candles ago, is found in `cloud_a` and `cloud_b`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Ichimoku {
+    pub tenkan: f64,
+    pub kijun: f64,
+    pub senkou_a: f64,
+    pub senkou_b: f64,
+    pub chikou: f64,
+    pub cloud_a: f64,
+    pub cloud_b: f64,
+}
+
+impl PopulatesCandles for Ichimoku {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (tenkan_len, kijun_len, senkou_b_len) = args.ichimoku_res()?;
+        let indicator_type = IndicatorType::Ichimoku(tenkan_len, kijun_len, senkou_b_len);
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, &args)?;
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        Self::populate_candle(ts, ts.candles.len(), &args)
+    }
+}
+
+impl IsIndicator for Ichimoku {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::IchimokuArgs(9, 26, 52)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Segment should be at least senkou B length + kijun length long to
+    /// include the cloud at the last candle.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (tenkan_len, kijun_len, senkou_b_len) = args.ichimoku_opt()?;
+        let end = segment.len();
+
+        if end < Self::needed_candles(kijun_len, senkou_b_len) {
+            return None;
+        }
+
+        let (tenkan, kijun, senkou_a, senkou_b) =
+            Self::spans(&segment[..end], tenkan_len, kijun_len, senkou_b_len)?;
+        let (_, _, cloud_a, cloud_b) = Self::spans(
+            &segment[..end - kijun_len],
+            tenkan_len,
+            kijun_len,
+            senkou_b_len,
+        )?;
+
+        Some(Ichimoku {
+            tenkan,
+            kijun,
+            senkou_a,
+            senkou_b,
+            chikou: segment.last()?.close,
+            cloud_a,
+            cloud_b,
+        })
+    }
+}
+
+impl Ichimoku {
+    #[allow(dead_code)]
+    pub fn cloud_top(&self) -> f64 {
+        self.cloud_a.max(self.cloud_b)
+    }
+
+    #[allow(dead_code)]
+    pub fn cloud_bottom(&self) -> f64 {
+        self.cloud_a.min(self.cloud_b)
+    }
+
+    fn needed_candles(kijun_len: usize, senkou_b_len: usize) -> usize {
+        senkou_b_len + kijun_len
+    }
+
+    /// Populates the candle at `end - 1`. The highs and lows of the candles
+    /// over each length are kept in the TimeSeries, along with those of the
+    /// candles kijun-length candles back for the cloud, so that only the new
+    /// candle needs to be added. Not enough candles results in None.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, args: &IndicatorArgs) -> Result<()> {
+        let (tenkan_len, kijun_len, senkou_b_len) = args.ichimoku_res()?;
+        let indicator_type = IndicatorType::Ichimoku(tenkan_len, kijun_len, senkou_b_len);
+        let needed_candles = Self::needed_candles(kijun_len, senkou_b_len);
+        let lens = [tenkan_len, kijun_len, senkou_b_len];
+        let new_state = || IndicatorState::new().with_extremes(&[lens, lens].concat());
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            Self::next(state, view, i, kijun_len);
+        };
+
+        let mut state =
+            IndicatorState::take(ts, &indicator_type, end, needed_candles, new_state, step);
+        let ichimoku = Self::next(&mut state, ts.view(), end - 1, kijun_len);
+
+        state.put(ts, indicator_type, end);
+        ts.set_indicator(end - 1, indicator_type, Indicator::Ichimoku(ichimoku));
+
+        Ok(())
+    }
+
+    /// Updates the state with the candle at index `i` of the view, returning
+    /// the Ichimoku once the spans of the cloud are available.
+    fn next(
+        state: &mut IndicatorState,
+        view: CandleView<'_>,
+        i: usize,
+        kijun_len: usize,
+    ) -> Option<Self> {
+        let (current, cloud) = state.extremes.split_at_mut(3);
+
+        for extremes in current.iter_mut() {
+            extremes.push(view[i].high, view[i].low);
+        }
+
+        if let Some(candle) = i.checked_sub(kijun_len).map(|j| &view[j]) {
+            for extremes in cloud.iter_mut() {
+                extremes.push(candle.high, candle.low);
+            }
+        }
+
+        let (tenkan, kijun, senkou_a, senkou_b) = Self::rolling_spans(current)?;
+        let (_, _, cloud_a, cloud_b) = Self::rolling_spans(cloud)?;
+
+        Some(Ichimoku {
+            tenkan,
+            kijun,
+            senkou_a,
+            senkou_b,
+            chikou: view[i].close,
+            cloud_a,
+            cloud_b,
+        })
+    }
+
+    /// Returns the spans from the extremes of the tenkan, kijun and senkou B
+    /// lengths, see `spans`.
+    fn rolling_spans(extremes: &[RollingExtremes]) -> Option<(f64, f64, f64, f64)> {
+        let midpoint =
+            |extremes: &RollingExtremes| extremes.high_low().map(|(high, low)| (high + low) / 2.0);
+        let tenkan = midpoint(&extremes[0])?;
+        let kijun = midpoint(&extremes[1])?;
+        let senkou_b = midpoint(&extremes[2])?;
+
+        Some((tenkan, kijun, (tenkan + kijun) / 2.0, senkou_b))
+    }
+
+    /// Returns the tenkan-sen, kijun-sen, senkou span A and senkou span B
+    /// calculated at the last candle of the segment.
+    fn spans(
+        segment: &[Candle],
+        tenkan_len: usize,
+        kijun_len: usize,
+        senkou_b_len: usize,
+    ) -> Option<(f64, f64, f64, f64)> {
+        let tenkan = midpoint(segment, tenkan_len)?;
+        let kijun = midpoint(segment, kijun_len)?;
+        let senkou_b = midpoint(segment, senkou_b_len)?;
+
+        Some((tenkan, kijun, (tenkan + kijun) / 2.0, senkou_b))
+    }
+}
+
+/// Returns the midpoint of the highest high and lowest low of the last `len`
+/// candles.
+fn midpoint(segment: &[Candle], len: usize) -> Option<f64> {
+    let end = segment.len();
+
+    if len == 0 || end < len {
+        return None;
+    }
+
+    let (high, low) = segment[end - len..end]
+        .iter()
+        .fold((f64::MIN, f64::MAX), |(high, low), candle| {
+            (high.max(candle.high), low.min(candle.low))
+        });
+
+    Some((high + low) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            ichimoku::Ichimoku, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn ichimoku_calculate() {
+        let candles = Candle::dummy_data(100, "positive", 100.0);
+        let ichimoku = Ichimoku::calculate(&candles).unwrap();
+        assert_eq!(ichimoku.tenkan, 1060.0);
+        assert_eq!(ichimoku.kijun, 975.0);
+        assert_eq!(ichimoku.senkou_a, 1017.5);
+        assert_eq!(ichimoku.senkou_b, 845.0);
+        assert_eq!(ichimoku.chikou, 1100.0);
+        assert_eq!(ichimoku.cloud_a, 757.5);
+        assert_eq!(ichimoku.cloud_b, 585.0);
+        assert_eq!(ichimoku.cloud_top(), 757.5);
+        assert_eq!(ichimoku.cloud_bottom(), 585.0);
+    }
+
+    #[test]
+    fn ichimoku_calculate_args() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let args = IndicatorArgs::IchimokuArgs(2, 3, 5);
+        let ichimoku = Ichimoku::calculate_args(&candles, &args).unwrap();
+        assert_eq!(ichimoku.tenkan, 195.0);
+        assert_eq!(ichimoku.kijun, 190.0);
+        assert_eq!(ichimoku.senkou_b, 180.0);
+        assert_eq!(ichimoku.cloud_b, 150.0);
+    }
+
+    #[test]
+    fn ichimoku_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let ichimoku = Ichimoku::calculate(&candles);
+        assert!(ichimoku.is_none());
+    }
+
+    #[test]
+    fn ichimoku_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = Ichimoku::default_args();
+        let ichimoku = Ichimoku::calculate_args(&candles, &args);
+        assert!(ichimoku.is_none());
+    }
+
+    #[test]
+    fn ichimoku_populate_candles() {
+        let candles = Candle::dummy_data(100, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = Ichimoku::populate_candles(&mut ts);
+        let indicator_type = IndicatorType::Ichimoku(9, 26, 52);
+
+        for i in 0..ts.candles.len() {
+            let ichimoku = ts.indicator(i, &indicator_type).unwrap().as_ichimoku();
+            if i < 77 {
+                assert!(ichimoku.is_none());
+            } else {
+                assert!(ichimoku.is_some());
+            }
+        }
+
+        let last_ichimoku = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_ichimoku()
+            .unwrap();
+        assert_eq!(last_ichimoku.kijun, 975.0);
+    }
+
+    #[test]
+    fn ichimoku_populate_last_candle() {
+        let mut candles = Candle::dummy_data(100, "positive", 100.0);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = Ichimoku::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_ichimoku = ts
+            .view()
+            .last_indicator(&IndicatorType::Ichimoku(9, 26, 52))
+            .unwrap()
+            .as_ichimoku()
+            .unwrap();
+
+        assert_eq!(last_ichimoku.senkou_b, 845.0);
+        assert_eq!(last_ichimoku.cloud_a, 757.5);
+    }
+
+    #[test]
+    fn ichimoku_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = IndicatorArgs::IchimokuArgs(9, 26, 52);
+        let indicator_type = IndicatorType::Ichimoku(9, 26, 52);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        Ichimoku::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_ichimoku());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_ichimoku());
+            assert_eq!(value, full_value);
+
+            // Extremes are exact, matching a scan of the window
+            let window = &candles[..offset + i + 1];
+            assert_eq!(value, Ichimoku::calculate_args(window, &args));
+        }
+    }
+}
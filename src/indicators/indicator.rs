@@ -1,14 +1,17 @@
 use crate::{
     indicators::{
-        atr::ATR, bbw::BBW, bbwp::BBWP, bollinger_bands::BollingerBands,
-        dynamic_pivots::DynamicPivots, ema::EMA, indicator_type::IndicatorType, pmar::PMAR,
-        pmarp::PMARP, rsi::RSI, sma::SMA, stochastic::Stochastic,
+        adx::ADX, atr::ATR, bbw::BBW, bbwp::BBWP, bollinger_bands::BollingerBands,
+        donchian_channels::DonchianChannels, dynamic_pivots::DynamicPivots, ema::EMA,
+        ichimoku::Ichimoku, indicator_type::IndicatorType, keltner_channels::KeltnerChannels,
+        macd::MACD, obv::OBV, pmar::PMAR, pmarp::PMARP, rsi::RSI, sma::SMA, squeeze::Squeeze,
+        stochastic::Stochastic, supertrend::Supertrend, volume_sma::VolumeSMA, vwap::VWAP,
+        vwma::VWMA,
     },
     models::timeseries::TimeSeries,
 };
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub enum Indicator {
     SMA(Option<SMA>),
     EMA(Option<EMA>),
@@ -21,6 +24,17 @@ pub enum Indicator {
     PMAR(Option<PMAR>),
     PMARP(Option<PMARP>),
     Stochastic(Option<Stochastic>),
+    MACD(Option<MACD>),
+    VWMA(Option<VWMA>),
+    OBV(Option<OBV>),
+    VWAP(Option<VWAP>),
+    VolumeSMA(Option<VolumeSMA>),
+    ADX(Option<ADX>),
+    Supertrend(Option<Supertrend>),
+    Ichimoku(Option<Ichimoku>),
+    KeltnerChannels(Option<KeltnerChannels>),
+    DonchianChannels(Option<DonchianChannels>),
+    Squeeze(Option<Squeeze>),
 }
 
 impl Indicator {
@@ -35,19 +49,7 @@ impl Indicator {
         indicator_type: &IndicatorType,
         i: usize,
     ) -> Option<Indicator> {
-        let candles_len = ts.candles.len();
-
-        if candles_len < i {
-            return None;
-        }
-
-        let prev = ts
-            .candles
-            .get(candles_len - i)
-            .and_then(|candle| candle.indicators.get(&indicator_type))
-            .and_then(|indicator| Some(indicator.clone()));
-
-        prev.clone()
+        ts.indicator_column(indicator_type).nth_last(i).copied()
     }
 
     /// Returns the second last indicator of the given type for the given TimeSeries.
@@ -149,4 +151,114 @@ impl Indicator {
             None
         }
     }
+
+    #[allow(dead_code)]
+    pub fn as_macd(&self) -> Option<MACD> {
+        if let Indicator::MACD(macd) = self {
+            *macd
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_vwma(&self) -> Option<VWMA> {
+        if let Indicator::VWMA(vwma) = self {
+            *vwma
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_obv(&self) -> Option<OBV> {
+        if let Indicator::OBV(obv) = self {
+            *obv
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_vwap(&self) -> Option<VWAP> {
+        if let Indicator::VWAP(vwap) = self {
+            *vwap
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_volume_sma(&self) -> Option<VolumeSMA> {
+        if let Indicator::VolumeSMA(volume_sma) = self {
+            *volume_sma
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_adx(&self) -> Option<ADX> {
+        if let Indicator::ADX(adx) = self {
+            *adx
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_supertrend(&self) -> Option<Supertrend> {
+        if let Indicator::Supertrend(supertrend) = self {
+            *supertrend
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_ichimoku(&self) -> Option<Ichimoku> {
+        if let Indicator::Ichimoku(ichimoku) = self {
+            *ichimoku
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_keltner_channels(&self) -> Option<KeltnerChannels> {
+        if let Indicator::KeltnerChannels(kc) = self {
+            *kc
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_donchian_channels(&self) -> Option<DonchianChannels> {
+        if let Indicator::DonchianChannels(dc) = self {
+            *dc
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_squeeze(&self) -> Option<Squeeze> {
+        if let Indicator::Squeeze(squeeze) = self {
+            *squeeze
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of the indicator if it is a moving average.
+    #[allow(dead_code)]
+    pub fn as_ma_value(&self) -> Option<f64> {
+        match self {
+            Indicator::SMA(sma) => sma.map(|sma| sma.value),
+            Indicator::EMA(ema) => ema.map(|ema| ema.value),
+            Indicator::VWMA(vwma) => vwma.map(|vwma| vwma.value),
+            _ => None,
+        }
+    }
 }
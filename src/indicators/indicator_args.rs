@@ -1,16 +1,23 @@
-use crate::models::ma_type::MAType;
+use crate::models::{ma_type::MAType, vwap_anchor::VWAPAnchor};
 use anyhow::{anyhow, Result};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub enum IndicatorArgs {
     LengthArg(usize),
-    BollingerBandArgs(usize, f64),    // length, n-standard deviations
-    BBWPArgs(usize, usize, usize),    // bbwp-length, lookback, sma-length
-    LengthLookbackArgs(usize, usize), // Length, lookback
-    PMARArgs(usize, MAType),          // Length, moving average type
-    PMARPArgs(usize, usize, MAType),  // Length, lookback, moving average type
-    StochasticArgs(usize, usize, usize), // K length, K smoothing, D Smoothing
+    BollingerBandArgs(usize, f64, MAType), // length, n-standard deviations, basis type
+    BBWPArgs(usize, usize, usize, f64, MAType), // bbwp-length, lookback, sma-length, n-standard deviations, basis type
+    LengthLookbackArgs(usize, usize),           // Length, lookback
+    PMARArgs(usize, MAType),                    // Length, moving average type
+    PMARPArgs(usize, usize, usize, MAType),     // Length, lookback, sma length, moving average type
+    StochasticArgs(usize, usize, usize),        // K length, K smoothing, D Smoothing
+    MACDArgs(usize, usize, usize),              // Fast length, slow length, signal length
+    VWAPArgs(VWAPAnchor),                       // Anchor period
+    SupertrendArgs(usize, f64),                 // ATR length, ATR multiplier
+    IchimokuArgs(usize, usize, usize),          // Tenkan length, kijun length, senkou B length
+    KeltnerArgs(usize, usize, f64),             // EMA length, ATR length, ATR multiplier
+    SqueezeArgs(usize, f64, f64), // Length, BB n-standard deviations, KC ATR multiplier
+    NoArgs,
 }
 
 const ERR_MSG: &str = "Invalid indicator arguments.";
@@ -31,31 +38,31 @@ impl IndicatorArgs {
         }
     }
 
-    pub fn bb_opt(&self) -> Option<(usize, f64)> {
+    pub fn bb_opt(&self) -> Option<(usize, f64, MAType)> {
         match self {
-            IndicatorArgs::BollingerBandArgs(n, m) => Some((*n, *m)),
+            IndicatorArgs::BollingerBandArgs(n, m, t) => Some((*n, *m, *t)),
             _ => return None,
         }
     }
 
-    pub fn bb_res(&self) -> Result<(usize, f64)> {
+    pub fn bb_res(&self) -> Result<(usize, f64, MAType)> {
         match self {
-            IndicatorArgs::BollingerBandArgs(n, m) => Ok((*n, *m)),
+            IndicatorArgs::BollingerBandArgs(n, m, t) => Ok((*n, *m, *t)),
             _ => return Err(anyhow!(ERR_MSG)),
         }
     }
 
     #[allow(dead_code)]
-    pub fn bbwp_opt(&self) -> Option<(usize, usize, usize)> {
+    pub fn bbwp_opt(&self) -> Option<(usize, usize, usize, f64, MAType)> {
         match self {
-            IndicatorArgs::BBWPArgs(a, b, c) => Some((*a, *b, *c)),
+            IndicatorArgs::BBWPArgs(a, b, c, d, e) => Some((*a, *b, *c, *d, *e)),
             _ => return None,
         }
     }
 
-    pub fn bbwp_res(&self) -> Result<(usize, usize, usize)> {
+    pub fn bbwp_res(&self) -> Result<(usize, usize, usize, f64, MAType)> {
         match self {
-            IndicatorArgs::BBWPArgs(a, b, c) => Ok((*a, *b, *c)),
+            IndicatorArgs::BBWPArgs(a, b, c, d, e) => Ok((*a, *b, *c, *d, *e)),
             _ => return Err(anyhow!(ERR_MSG)),
         }
     }
@@ -93,17 +100,17 @@ impl IndicatorArgs {
     }
 
     #[allow(dead_code)]
-    pub fn pmarp_opt(&self) -> Option<(usize, usize, MAType)> {
+    pub fn pmarp_opt(&self) -> Option<(usize, usize, usize, MAType)> {
         match self {
-            IndicatorArgs::PMARPArgs(a, b, c) => Some((*a, *b, *c)),
+            IndicatorArgs::PMARPArgs(a, b, c, d) => Some((*a, *b, *c, *d)),
             _ => return None,
         }
     }
 
     #[allow(dead_code)]
-    pub fn pmarp_res(&self) -> Result<(usize, usize, MAType)> {
+    pub fn pmarp_res(&self) -> Result<(usize, usize, usize, MAType)> {
         match self {
-            IndicatorArgs::PMARPArgs(a, b, c) => Ok((*a, *b, *c)),
+            IndicatorArgs::PMARPArgs(a, b, c, d) => Ok((*a, *b, *c, *d)),
             _ => return Err(anyhow!(ERR_MSG)),
         }
     }
@@ -123,4 +130,98 @@ impl IndicatorArgs {
             _ => return None,
         }
     }
+
+    #[allow(dead_code)]
+    pub fn macd_res(&self) -> Result<(usize, usize, usize)> {
+        match self {
+            IndicatorArgs::MACDArgs(a, b, c) => Ok((*a, *b, *c)),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn macd_opt(&self) -> Option<(usize, usize, usize)> {
+        match self {
+            IndicatorArgs::MACDArgs(a, b, c) => Some((*a, *b, *c)),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn vwap_res(&self) -> Result<VWAPAnchor> {
+        match self {
+            IndicatorArgs::VWAPArgs(anchor) => Ok(*anchor),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn vwap_opt(&self) -> Option<VWAPAnchor> {
+        match self {
+            IndicatorArgs::VWAPArgs(anchor) => Some(*anchor),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn supertrend_res(&self) -> Result<(usize, f64)> {
+        match self {
+            IndicatorArgs::SupertrendArgs(a, b) => Ok((*a, *b)),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn supertrend_opt(&self) -> Option<(usize, f64)> {
+        match self {
+            IndicatorArgs::SupertrendArgs(a, b) => Some((*a, *b)),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn ichimoku_res(&self) -> Result<(usize, usize, usize)> {
+        match self {
+            IndicatorArgs::IchimokuArgs(a, b, c) => Ok((*a, *b, *c)),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn ichimoku_opt(&self) -> Option<(usize, usize, usize)> {
+        match self {
+            IndicatorArgs::IchimokuArgs(a, b, c) => Some((*a, *b, *c)),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn keltner_res(&self) -> Result<(usize, usize, f64)> {
+        match self {
+            IndicatorArgs::KeltnerArgs(a, b, c) => Ok((*a, *b, *c)),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn keltner_opt(&self) -> Option<(usize, usize, f64)> {
+        match self {
+            IndicatorArgs::KeltnerArgs(a, b, c) => Some((*a, *b, *c)),
+            _ => None,
+        }
+    }
+
+    pub fn squeeze_res(&self) -> Result<(usize, f64, f64)> {
+        match self {
+            IndicatorArgs::SqueezeArgs(a, b, c) => Ok((*a, *b, *c)),
+            _ => Err(anyhow!(ERR_MSG)),
+        }
+    }
+
+    pub fn squeeze_opt(&self) -> Option<(usize, f64, f64)> {
+        match self {
+            IndicatorArgs::SqueezeArgs(a, b, c) => Some((*a, *b, *c)),
+            _ => None,
+        }
+    }
 }
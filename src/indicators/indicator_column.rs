@@ -0,0 +1,102 @@
+use crate::indicators::indicator::Indicator;
+
+/// Borrowed view of a single indicator across a window of candles. Lookups
+/// return references into the indicator column so nothing is cloned.
+#[derive(Debug, Copy, Clone)]
+pub struct IndicatorColumn<'a> {
+    values: &'a [Option<Indicator>],
+    len: usize,
+}
+
+impl<'a> IndicatorColumn<'a> {
+    /// Creates a view of `len` candles, `values` being the column of the
+    /// indicator for those candles or empty if it has not been populated.
+    pub fn new(values: &'a [Option<Indicator>], len: usize) -> Self {
+        Self { values, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the indicator of the candle at index `i`.
+    pub fn get(&self, i: usize) -> Option<&'a Indicator> {
+        self.values.get(i)?.as_ref()
+    }
+
+    /// Returns the nth last indicator, 1 is last, 2 is second last, etc.
+    pub fn nth_last(&self, n: usize) -> Option<&'a Indicator> {
+        let i = self.len().checked_sub(n)?;
+        self.get(i)
+    }
+
+    #[allow(dead_code)]
+    pub fn last(&self) -> Option<&'a Indicator> {
+        self.nth_last(1)
+    }
+
+    /// Narrows the view to the last `len` candles.
+    #[allow(dead_code)]
+    pub fn tail(&self, len: usize) -> Self {
+        let start = self.len().saturating_sub(len);
+        let values = &self.values[start.min(self.values.len())..];
+
+        Self::new(values, self.len - start)
+    }
+
+    /// Iterates the indicator of each candle in the view, candles missing the
+    /// indicator yield None.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a Indicator>> + 'a {
+        let values = self.values;
+
+        (0..self.len).map(move |i| values.get(i).and_then(|value| value.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator,
+            populates_candles::PopulatesCandles, sma::SMA,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+    };
+
+    #[test]
+    fn indicator_column_lookups() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        SMA::populate_candles(&mut ts).unwrap();
+
+        let indicator_type = IndicatorType::SMA(8);
+        let column = ts.indicator_column(&indicator_type);
+
+        assert_eq!(column.len(), 10);
+        assert!(column.get(0).unwrap().as_sma().is_none());
+        assert_eq!(
+            column.last().unwrap().as_sma(),
+            SMA::calculate(&ts.candles[2..])
+        );
+        assert_eq!(
+            column.nth_last(2).unwrap().as_sma(),
+            SMA::calculate(&ts.candles[1..9])
+        );
+        assert!(column.nth_last(11).is_none());
+
+        let tail = column.tail(3);
+        assert_eq!(tail.len(), 3);
+        assert_eq!(tail.iter().flatten().filter_map(|i| i.as_sma()).count(), 3);
+        assert!(ts.indicator_column(&IndicatorType::SMA(3)).last().is_none());
+    }
+}
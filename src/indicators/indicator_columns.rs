@@ -0,0 +1,199 @@
+use crate::{
+    indicators::{indicator::Indicator, indicator_type::IndicatorType},
+    utils::ring_buffer::RingBuffer,
+};
+use std::collections::HashMap;
+
+/// Indicators of a single candle, used where a candle is passed on by itself
+/// (e.g. the setup candle or a candle still forming).
+pub type IndicatorRow = HashMap<IndicatorType, Indicator>;
+
+/// One column of values per indicator, aligned to the candles they belong to.
+/// Every column holds a value slot for each of the `len` candles, candles the
+/// indicator has not been populated for hold None. Columns are ring buffers
+/// of the same capacity as the candles, so they evict along with them.
+#[derive(Debug, Clone)]
+pub struct IndicatorColumns {
+    capacity: usize,
+    len: usize,
+    columns: HashMap<IndicatorType, RingBuffer<Option<Indicator>>>,
+}
+
+impl IndicatorColumns {
+    pub fn new(capacity: usize, len: usize) -> Self {
+        Self {
+            capacity,
+            len,
+            columns: HashMap::new(),
+        }
+    }
+
+    /// Number of candles the columns hold a value slot for.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.len = self.len.min(capacity.max(1));
+
+        for column in self.columns.values_mut() {
+            column.set_capacity(capacity);
+        }
+    }
+
+    /// Adds an empty slot to every column for a new candle.
+    pub fn push_row(&mut self) {
+        self.len = (self.len + 1).min(self.capacity.max(1));
+
+        for column in self.columns.values_mut() {
+            column.push(None);
+        }
+    }
+
+    /// Adds a slot to every column for a new candle with the given
+    /// indicators.
+    pub fn push_row_with(&mut self, row: &IndicatorRow) {
+        self.push_row();
+
+        for (indicator_type, indicator) in row {
+            self.set(*indicator_type, self.len - 1, *indicator);
+        }
+    }
+
+    /// Removes the slots of the last candle.
+    pub fn pop_row(&mut self) {
+        self.len = self.len.saturating_sub(1);
+
+        for column in self.columns.values_mut() {
+            column.pop();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.columns.clear();
+    }
+
+    pub fn contains(&self, indicator_type: &IndicatorType) -> bool {
+        self.columns.contains_key(indicator_type)
+    }
+
+    /// Returns the values of the given indicator, oldest first.
+    pub fn column(&self, indicator_type: &IndicatorType) -> Option<&[Option<Indicator>]> {
+        self.columns.get(indicator_type).map(|column| &column[..])
+    }
+
+    /// Returns the indicator of the candle at index `i`.
+    pub fn get(&self, indicator_type: &IndicatorType, i: usize) -> Option<&Indicator> {
+        self.columns.get(indicator_type)?.get(i)?.as_ref()
+    }
+
+    /// Sets the indicator of the candle at index `i`, adding the column if
+    /// it is missing. Indices past the candles are ignored.
+    pub fn set(&mut self, indicator_type: IndicatorType, i: usize, indicator: Indicator) {
+        if i >= self.len {
+            return;
+        }
+
+        let (capacity, len) = (self.capacity, self.len);
+        let column = self
+            .columns
+            .entry(indicator_type)
+            .or_insert_with(|| RingBuffer::from_vec(vec![None; len], capacity));
+
+        column[i] = Some(indicator);
+    }
+
+    /// Takes the column of the given indicator out.
+    pub fn remove(&mut self, indicator_type: &IndicatorType) -> Option<Vec<Option<Indicator>>> {
+        self.columns
+            .remove(indicator_type)
+            .map(|column| column.to_vec())
+    }
+
+    /// Inserts a column of values for the last candles, missing slots of the
+    /// first candles are filled with None.
+    pub fn insert(&mut self, indicator_type: IndicatorType, values: Vec<Option<Indicator>>) {
+        let mut column = vec![None; self.len.saturating_sub(values.len())];
+        column.extend(values);
+
+        self.columns
+            .insert(indicator_type, RingBuffer::from_vec(column, self.capacity));
+    }
+
+    /// Returns the indicators of the candle at index `i`.
+    pub fn row(&self, i: usize) -> IndicatorRow {
+        self.columns
+            .iter()
+            .filter_map(|(indicator_type, column)| Some((*indicator_type, (*column.get(i)?)?)))
+            .collect()
+    }
+
+    /// Copies the slots of the candles in `start..end` into new columns.
+    pub fn slice(&self, start: usize, end: usize) -> Self {
+        let len = end.saturating_sub(start);
+
+        Self {
+            capacity: len,
+            len,
+            columns: self
+                .columns
+                .iter()
+                .map(|(indicator_type, column)| {
+                    let values = column[start..end].to_vec();
+                    (*indicator_type, RingBuffer::from_vec(values, len))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndicatorColumns;
+    use crate::indicators::{indicator::Indicator, indicator_type::IndicatorType, sma::SMA};
+
+    fn sma(value: f64) -> Indicator {
+        Indicator::SMA(Some(SMA { value, len: 2 }))
+    }
+
+    #[test]
+    fn indicator_columns_stay_aligned() {
+        let indicator_type = IndicatorType::SMA(2);
+        let mut columns = IndicatorColumns::new(3, 0);
+
+        for i in 0..5 {
+            columns.push_row();
+            columns.set(indicator_type, columns.len() - 1, sma(i as f64));
+        }
+
+        let values: Vec<f64> = columns
+            .column(&indicator_type)
+            .unwrap()
+            .iter()
+            .map(|value| value.unwrap().as_sma().unwrap().value)
+            .collect();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(values, vec![2.0, 3.0, 4.0]);
+
+        // Columns added later hold a slot for every candle
+        columns.set(IndicatorType::SMA(3), 0, sma(1.0));
+        assert_eq!(columns.column(&IndicatorType::SMA(3)).unwrap().len(), 3);
+        assert!(columns.get(&IndicatorType::SMA(3), 2).is_none());
+
+        let slice = columns.slice(1, 3);
+        assert_eq!(slice.len(), 2);
+        assert_eq!(slice.row(1).len(), 1);
+        assert_eq!(slice.row(0).len(), 1);
+
+        columns.pop_row();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns.column(&indicator_type).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{candle_window::CandleView, timeseries::TimeSeries},
+    utils::rolling::{RollingExtremes, RollingPercentile, RollingSum, WilderSmoothing},
+};
+use chrono::{DateTime, Utc};
+
+/// Rolling state kept by a TimeSeries for indicators which would otherwise
+/// have to recompute their whole window for every new candle, so that each
+/// new candle is an O(1) update (amortised for the extremes).
+///
+/// - Percentile windows rank a value against the lookback (PMARP, BBWP).
+/// - Running sums give means and standard deviations (Bollinger Bands, BBW,
+///   VWMA and the smoothing of the Stochastic).
+/// - Monotonic deques give the highest high and lowest low (Donchian
+///   Channels, Stochastic, Ichimoku).
+/// - Wilder smoothing averages a series recursively (ATR).
+///
+/// Indicators with a recursive form carrying their state in their value (EMA,
+/// RSI, ADX, MACD, ...) read the previous candle's value instead.
+#[derive(Debug, Clone, Default)]
+pub struct IndicatorState {
+    /// Timestamp of the last candle the state has been updated with.
+    pub timestamp: Option<DateTime<Utc>>,
+    pub percentiles: Vec<RollingPercentile>,
+    pub sums: Vec<RollingSum>,
+    pub extremes: Vec<RollingExtremes>,
+    pub wilders: Vec<WilderSmoothing>,
+}
+
+impl IndicatorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_percentiles(mut self, lookbacks: &[usize]) -> Self {
+        self.percentiles = lookbacks
+            .iter()
+            .map(|&lookback| RollingPercentile::new(lookback))
+            .collect();
+        self
+    }
+
+    pub fn with_sums(mut self, lens: &[usize]) -> Self {
+        self.sums = lens.iter().map(|&len| RollingSum::new(len)).collect();
+        self
+    }
+
+    pub fn with_extremes(mut self, lens: &[usize]) -> Self {
+        self.extremes = lens.iter().map(|&len| RollingExtremes::new(len)).collect();
+        self
+    }
+
+    pub fn with_wilders(mut self, lens: &[usize]) -> Self {
+        self.wilders = lens.iter().map(|&len| WilderSmoothing::new(len)).collect();
+        self
+    }
+
+    /// Takes the state of the indicator out of the TimeSeries, updated up to
+    /// the candle preceding the candle at `end - 1`. If the state is missing
+    /// or out of sync (e.g. candles have been replaced) a state from `new` is
+    /// rebuilt by calling `step` for each of the `warmup` preceding candles.
+    /// `step` updates the state with the candle at the given index.
+    pub fn take<N, S>(
+        ts: &mut TimeSeries,
+        indicator_type: &IndicatorType,
+        end: usize,
+        warmup: usize,
+        new: N,
+        step: S,
+    ) -> Self
+    where
+        N: Fn() -> Self,
+        S: Fn(&mut Self, CandleView<'_>, usize),
+    {
+        let prev_timestamp = end
+            .checked_sub(2)
+            .and_then(|i| ts.candles.get(i))
+            .map(|candle| candle.timestamp);
+
+        match ts.indicator_states.remove(indicator_type) {
+            Some(state) if state.timestamp.is_some() && state.timestamp == prev_timestamp => state,
+            _ => {
+                let mut state = new();
+                let prev_end = end.saturating_sub(1);
+
+                for i in prev_end.saturating_sub(warmup)..prev_end {
+                    step(&mut state, ts.view(), i);
+                }
+
+                state.timestamp = prev_timestamp;
+                state
+            }
+        }
+    }
+
+    /// Puts the state back into the TimeSeries once updated with the candle
+    /// at `end - 1`.
+    pub fn put(mut self, ts: &mut TimeSeries, indicator_type: IndicatorType, end: usize) {
+        self.timestamp = end
+            .checked_sub(1)
+            .and_then(|i| ts.candles.get(i))
+            .map(|candle| candle.timestamp);
+
+        ts.indicator_states.insert(indicator_type, self);
+    }
+}
@@ -1,20 +1,34 @@
 use crate::{
     indicators::{
+        adx::ADX,
         atr::ATR,
         bbw::BBW,
         bbwp::BBWP,
         bollinger_bands::BollingerBands,
+        donchian_channels::DonchianChannels,
         dynamic_pivots::DynamicPivots,
         ema::EMA,
+        ichimoku::Ichimoku,
         indicator_args::IndicatorArgs,
+        keltner_channels::KeltnerChannels,
+        macd::MACD,
+        obv::OBV,
         pmar::PMAR,
         pmarp::PMARP,
         populates_candles::{PopulatesCandles, PopulatesCandlesWithSelf},
         rsi::RSI,
         sma::SMA,
+        squeeze::Squeeze,
         stochastic::Stochastic,
+        supertrend::Supertrend,
+        volume_sma::VolumeSMA,
+        vwap::VWAP,
+        vwma::VWMA,
+    },
+    models::{
+        fixed_point::FixedPoint, ma_type::MAType, timeseries::TimeSeries,
+        traits::has_min_length::HasMinLength, vwap_anchor::VWAPAnchor,
     },
-    models::{ma_type::MAType, timeseries::TimeSeries, traits::has_min_length::HasMinLength},
 };
 use anyhow::Result;
 use serde::Serialize;
@@ -26,13 +40,24 @@ pub enum IndicatorType {
     EMA(usize),
     RSI(usize),
     ATR(usize),
-    BollingerBands(usize),
-    BBW(usize),
-    BBWP(usize, usize), // length, lookback
+    BollingerBands(usize, FixedPoint, MAType), // length, n-standard deviations, basis type
+    BBW(usize, FixedPoint, MAType),            // length, n-standard deviations, basis type
+    BBWP(usize, usize, usize, FixedPoint, MAType), // length, lookback, sma length, n-standard deviations, basis type
     DynamicPivot(usize),
-    PMAR(usize, MAType),             // length, moving average type
-    PMARP(usize, usize, MAType),     // length, lookback, moving average type
-    Stochastic(usize, usize, usize), // K-lenght, K-smoothing, D-smoothing
+    PMAR(usize, MAType),                // length, moving average type
+    PMARP(usize, usize, usize, MAType), // length, lookback, sma length, moving average type
+    Stochastic(usize, usize, usize),    // K-lenght, K-smoothing, D-smoothing
+    MACD(usize, usize, usize),          // Fast length, slow length, signal length
+    VWMA(usize),
+    OBV,
+    VWAP(VWAPAnchor), // Anchor period
+    VolumeSMA(usize),
+    ADX(usize),
+    Supertrend(usize, FixedPoint), // ATR length, ATR multiplier
+    Ichimoku(usize, usize, usize), // Tenkan length, kijun length, senkou B length
+    KeltnerChannels(usize, usize, FixedPoint), // EMA length, ATR length, ATR multiplier
+    DonchianChannels(usize),
+    Squeeze(usize, FixedPoint, FixedPoint), // length, BB n-standard deviations, KC ATR multiplier
 }
 
 impl PopulatesCandlesWithSelf for IndicatorType {
@@ -42,16 +67,17 @@ impl PopulatesCandlesWithSelf for IndicatorType {
                 let args = IndicatorArgs::LengthArg(*n);
                 ATR::populate_candles_args(ts, args)
             }
-            IndicatorType::BBW(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
+            IndicatorType::BBW(len, std_n, ma_type) => {
+                let args = IndicatorArgs::BollingerBandArgs(*len, std_n.as_f64(), *ma_type);
                 BBW::populate_candles_args(ts, args)
             }
-            IndicatorType::BBWP(len, lookback) => {
-                let args = IndicatorArgs::BBWPArgs(*len, *lookback, 5);
+            IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type) => {
+                let args =
+                    IndicatorArgs::BBWPArgs(*len, *lookback, *sma_len, std_n.as_f64(), *ma_type);
                 BBWP::populate_candles_args(ts, args)
             }
-            IndicatorType::BollingerBands(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
+            IndicatorType::BollingerBands(len, std_n, ma_type) => {
+                let args = IndicatorArgs::BollingerBandArgs(*len, std_n.as_f64(), *ma_type);
                 BollingerBands::populate_candles_args(ts, args)
             }
             IndicatorType::DynamicPivot(len) => {
@@ -74,14 +100,56 @@ impl PopulatesCandlesWithSelf for IndicatorType {
                 let args = IndicatorArgs::PMARArgs(*len, *ma_type);
                 PMAR::populate_candles_args(ts, args)
             }
-            IndicatorType::PMARP(len, lookback, ma_type) => {
-                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *ma_type);
+            IndicatorType::PMARP(len, lookback, sma_len, ma_type) => {
+                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *sma_len, *ma_type);
                 PMARP::populate_candles_args(ts, args)
             }
             IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
                 let args = IndicatorArgs::StochasticArgs(*k_len, *k_smoothing, *d_smoothing);
                 Stochastic::populate_candles_args(ts, args)
             }
+            IndicatorType::MACD(fast_len, slow_len, signal_len) => {
+                let args = IndicatorArgs::MACDArgs(*fast_len, *slow_len, *signal_len);
+                MACD::populate_candles_args(ts, args)
+            }
+            IndicatorType::VWMA(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                VWMA::populate_candles_args(ts, args)
+            }
+            IndicatorType::OBV => OBV::populate_candles_args(ts, IndicatorArgs::NoArgs),
+            IndicatorType::VWAP(anchor) => {
+                let args = IndicatorArgs::VWAPArgs(*anchor);
+                VWAP::populate_candles_args(ts, args)
+            }
+            IndicatorType::VolumeSMA(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                VolumeSMA::populate_candles_args(ts, args)
+            }
+            IndicatorType::ADX(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                ADX::populate_candles_args(ts, args)
+            }
+            IndicatorType::Supertrend(len, multiplier) => {
+                let args = IndicatorArgs::SupertrendArgs(*len, multiplier.as_f64());
+                Supertrend::populate_candles_args(ts, args)
+            }
+            IndicatorType::Ichimoku(tenkan_len, kijun_len, senkou_b_len) => {
+                let args = IndicatorArgs::IchimokuArgs(*tenkan_len, *kijun_len, *senkou_b_len);
+                Ichimoku::populate_candles_args(ts, args)
+            }
+            IndicatorType::KeltnerChannels(ema_len, atr_len, multiplier) => {
+                let args = IndicatorArgs::KeltnerArgs(*ema_len, *atr_len, multiplier.as_f64());
+                KeltnerChannels::populate_candles_args(ts, args)
+            }
+            IndicatorType::DonchianChannels(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                DonchianChannels::populate_candles_args(ts, args)
+            }
+            IndicatorType::Squeeze(len, bb_std_n, kc_multiplier) => {
+                let args =
+                    IndicatorArgs::SqueezeArgs(*len, bb_std_n.as_f64(), kc_multiplier.as_f64());
+                Squeeze::populate_candles_args(ts, args)
+            }
         }
     }
 
@@ -91,16 +159,17 @@ impl PopulatesCandlesWithSelf for IndicatorType {
                 let args = IndicatorArgs::LengthArg(*len);
                 ATR::populate_last_candle_args(ts, args)
             }
-            IndicatorType::BBW(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
+            IndicatorType::BBW(len, std_n, ma_type) => {
+                let args = IndicatorArgs::BollingerBandArgs(*len, std_n.as_f64(), *ma_type);
                 BBW::populate_last_candle_args(ts, args)
             }
-            IndicatorType::BBWP(len, lookback) => {
-                let args = IndicatorArgs::BBWPArgs(*len, *lookback, 5);
+            IndicatorType::BBWP(len, lookback, sma_len, std_n, ma_type) => {
+                let args =
+                    IndicatorArgs::BBWPArgs(*len, *lookback, *sma_len, std_n.as_f64(), *ma_type);
                 BBWP::populate_last_candle_args(ts, args)
             }
-            IndicatorType::BollingerBands(len) => {
-                let args = IndicatorArgs::BollingerBandArgs(*len, 2.0);
+            IndicatorType::BollingerBands(len, std_n, ma_type) => {
+                let args = IndicatorArgs::BollingerBandArgs(*len, std_n.as_f64(), *ma_type);
                 BollingerBands::populate_last_candle_args(ts, args)
             }
             IndicatorType::DynamicPivot(len) => {
@@ -123,14 +192,56 @@ impl PopulatesCandlesWithSelf for IndicatorType {
                 let args = IndicatorArgs::PMARArgs(*len, *ma_type);
                 PMAR::populate_last_candle_args(ts, args)
             }
-            IndicatorType::PMARP(len, lookback, ma_type) => {
-                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *ma_type);
+            IndicatorType::PMARP(len, lookback, sma_len, ma_type) => {
+                let args = IndicatorArgs::PMARPArgs(*len, *lookback, *sma_len, *ma_type);
                 PMARP::populate_last_candle_args(ts, args)
             }
             IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
                 let args = IndicatorArgs::StochasticArgs(*k_len, *k_smoothing, *d_smoothing);
                 Stochastic::populate_last_candle_args(ts, args)
             }
+            IndicatorType::MACD(fast_len, slow_len, signal_len) => {
+                let args = IndicatorArgs::MACDArgs(*fast_len, *slow_len, *signal_len);
+                MACD::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::VWMA(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                VWMA::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::OBV => OBV::populate_last_candle_args(ts, IndicatorArgs::NoArgs),
+            IndicatorType::VWAP(anchor) => {
+                let args = IndicatorArgs::VWAPArgs(*anchor);
+                VWAP::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::VolumeSMA(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                VolumeSMA::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::ADX(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                ADX::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::Supertrend(len, multiplier) => {
+                let args = IndicatorArgs::SupertrendArgs(*len, multiplier.as_f64());
+                Supertrend::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::Ichimoku(tenkan_len, kijun_len, senkou_b_len) => {
+                let args = IndicatorArgs::IchimokuArgs(*tenkan_len, *kijun_len, *senkou_b_len);
+                Ichimoku::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::KeltnerChannels(ema_len, atr_len, multiplier) => {
+                let args = IndicatorArgs::KeltnerArgs(*ema_len, *atr_len, multiplier.as_f64());
+                KeltnerChannels::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::DonchianChannels(len) => {
+                let args = IndicatorArgs::LengthArg(*len);
+                DonchianChannels::populate_last_candle_args(ts, args)
+            }
+            IndicatorType::Squeeze(len, bb_std_n, kc_multiplier) => {
+                let args =
+                    IndicatorArgs::SqueezeArgs(*len, bb_std_n.as_f64(), kc_multiplier.as_f64());
+                Squeeze::populate_last_candle_args(ts, args)
+            }
         }
     }
 }
@@ -142,15 +253,26 @@ impl HasMinLength for IndicatorType {
             IndicatorType::EMA(n) => n + 1,
             IndicatorType::RSI(n) => n + 1,
             IndicatorType::ATR(n) => n + 1,
-            IndicatorType::BollingerBands(n) => n + 1,
-            IndicatorType::BBW(n) => n + 1,
-            IndicatorType::BBWP(_, n) => n + 1,
+            IndicatorType::BollingerBands(n, _, _) => n + 1,
+            IndicatorType::BBW(n, _, _) => n + 1,
+            IndicatorType::BBWP(_, n, _, _, _) => n + 1,
             IndicatorType::DynamicPivot(n) => n + 1,
             IndicatorType::PMAR(n, _) => n + 1,
-            IndicatorType::PMARP(_, n, _) => n + 1,
+            IndicatorType::PMARP(_, n, _, _) => n + 1,
             IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing) => {
                 k_len + k_smoothing + d_smoothing
             }
+            IndicatorType::MACD(_, slow_len, signal_len) => slow_len + signal_len + 1,
+            IndicatorType::VWMA(n) => n + 1,
+            IndicatorType::OBV => 2,
+            IndicatorType::VWAP(_) => 1,
+            IndicatorType::VolumeSMA(n) => n + 1,
+            IndicatorType::ADX(n) => 2 * n + 1,
+            IndicatorType::Supertrend(n, _) => n + 1,
+            IndicatorType::Ichimoku(_, kijun_len, senkou_b_len) => senkou_b_len + kijun_len + 1,
+            IndicatorType::KeltnerChannels(ema_len, atr_len, _) => ema_len.max(atr_len) + 1,
+            IndicatorType::DonchianChannels(n) => n + 1,
+            IndicatorType::Squeeze(n, _, _) => 2 * n + 1,
         }
     }
 }
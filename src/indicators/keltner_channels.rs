@@ -0,0 +1,280 @@
+use crate::{
+    indicators::{
+        atr::ATR, ema::EMA, indicator::Indicator, indicator_args::IndicatorArgs,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, fixed_point::FixedPoint, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Result};
+
+/// # Keltner Channels
+///
+/// Bands placed a multiple of the ATR above and below an EMA of the close.
+/// The EMA and ATR are kept to allow rolling calculations on new candles.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct KeltnerChannels {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+    pub atr: f64,
+}
+
+impl PopulatesCandles for KeltnerChannels {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (ema_len, atr_len, multiplier) = args.keltner_res()?;
+        let indicator_type =
+            IndicatorType::KeltnerChannels(ema_len, atr_len, FixedPoint::new(multiplier));
+        let kcs = Self::calculate_series(&ts.candles, ema_len, atr_len, multiplier);
+
+        for (i, kc) in kcs.into_iter().enumerate() {
+            ts.set_indicator(i, indicator_type, Indicator::KeltnerChannels(kc));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (ema_len, atr_len, multiplier) = args.keltner_res()?;
+        let indicator_type =
+            IndicatorType::KeltnerChannels(ema_len, atr_len, FixedPoint::new(multiplier));
+        let candle_len = ts.candles.len();
+
+        if candle_len == 0 {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_keltner_channels());
+
+        let new_kc = match prev {
+            Some(prev) => Some(prev.next(
+                (&ts.candles[candle_len - 2], &ts.candles[candle_len - 1]),
+                ema_len,
+                atr_len,
+                multiplier,
+            )),
+            // Not enough candles for previous channels, calculate from scratch
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::KeltnerChannels(new_kc))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for KeltnerChannels {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::KeltnerArgs(20, 10, 2.0)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// The EMA and ATR are seeded at the start of the segment, longer
+    /// segments give values closer to those of channels calculated over all
+    /// available data.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (ema_len, atr_len, multiplier) = args.keltner_opt()?;
+
+        if segment.len() <= ema_len.max(atr_len) {
+            return None;
+        }
+
+        *Self::calculate_series(segment, ema_len, atr_len, multiplier).last()?
+    }
+}
+
+impl KeltnerChannels {
+    /// Returns the channels following these given the previous and new
+    /// candle.
+    pub fn next(
+        &self,
+        candles: (&Candle, &Candle),
+        ema_len: usize,
+        atr_len: usize,
+        multiplier: f64,
+    ) -> Self {
+        let (_, candle) = candles;
+        let ema = EMA::calculate_rolling(self.middle, candle.close, ema_len)
+            .map_or(self.middle, |ema| ema.value);
+        let atr =
+            ATR::calculate_rolling(candles, self.atr, atr_len).map_or(self.atr, |atr| atr.value);
+
+        Self::new(ema, atr, multiplier)
+    }
+
+    fn new(ema: f64, atr: f64, multiplier: f64) -> Self {
+        KeltnerChannels {
+            upper: ema + multiplier * atr,
+            middle: ema,
+            lower: ema - multiplier * atr,
+            atr,
+        }
+    }
+
+    /// Returns the channels for every candle, None until enough candles are
+    /// available to calculate both the EMA and ATR.
+    fn calculate_series(
+        candles: &[Candle],
+        ema_len: usize,
+        atr_len: usize,
+        multiplier: f64,
+    ) -> Vec<Option<Self>> {
+        let mut ema: Option<f64> = None;
+        let mut atr: Option<f64> = None;
+
+        (0..candles.len())
+            .map(|i| {
+                ema = match ema {
+                    Some(prev) => EMA::calculate_rolling(prev, candles[i].close, ema_len),
+                    None if i >= ema_len => EMA::calculate(&candles[i - ema_len..i + 1]),
+                    None => None,
+                }
+                .map(|ema| ema.value);
+
+                atr = match atr {
+                    Some(prev) => {
+                        ATR::calculate_rolling((&candles[i - 1], &candles[i]), prev, atr_len)
+                    }
+                    None if i >= atr_len && atr_len > 0 => {
+                        ATR::calculate(&candles[i - atr_len..i + 1])
+                    }
+                    None => None,
+                }
+                .map(|atr| atr.value);
+
+                Some(Self::new(ema?, atr?, multiplier))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, keltner_channels::KeltnerChannels,
+            populates_candles::PopulatesCandles,
+        },
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::{data::dummy_data::PRICE_CHANGES, math::ema_rolling},
+    };
+
+    #[test]
+    fn keltner_channels_calculate() {
+        let candles = Candle::dummy_data(30, "positive", 100.0);
+        let kc = KeltnerChannels::calculate(&candles).unwrap();
+        assert_eq!(kc.atr, 10.0);
+        assert_eq!(kc.upper - kc.middle, 20.0);
+        assert_eq!(kc.middle - kc.lower, 20.0);
+    }
+
+    #[test]
+    fn keltner_channels_calculate_args() {
+        let candles = Candle::dummy_data(6, "positive", 100.0);
+        let args = IndicatorArgs::KeltnerArgs(5, 5, 1.5);
+        let kc = KeltnerChannels::calculate_args(&candles, &args).unwrap();
+        let ema = ema_rolling(130.0, 160.0, 5.0);
+        assert_eq!(kc.middle, ema);
+        assert_eq!(kc.upper, ema + 15.0);
+        assert_eq!(kc.lower, ema - 15.0);
+    }
+
+    #[test]
+    fn keltner_channels_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let kc = KeltnerChannels::calculate(&candles);
+        assert!(kc.is_none());
+    }
+
+    #[test]
+    fn keltner_channels_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = KeltnerChannels::default_args();
+        let kc = KeltnerChannels::calculate_args(&candles, &args);
+        assert!(kc.is_none());
+    }
+
+    #[test]
+    fn keltner_channels_populate_candles() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = KeltnerChannels::populate_candles(&mut ts);
+        let indicator_type = IndicatorType::KeltnerChannels(20, 10, FixedPoint::new(2.0));
+
+        for i in 0..ts.candles.len() {
+            let kc = ts
+                .indicator(i, &indicator_type)
+                .unwrap()
+                .as_keltner_channels();
+            if i < 20 {
+                assert!(kc.is_none());
+            } else {
+                assert!(kc.is_some());
+            }
+        }
+
+        let last_kc = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_keltner_channels()
+            .unwrap();
+        assert_eq!(Some(last_kc), KeltnerChannels::calculate(&ts.candles));
+    }
+
+    #[test]
+    fn keltner_channels_populate_last_candle() {
+        let mut candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = KeltnerChannels::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_kc = ts
+            .view()
+            .last_indicator(&IndicatorType::KeltnerChannels(
+                20,
+                10,
+                FixedPoint::new(2.0),
+            ))
+            .unwrap()
+            .as_keltner_channels()
+            .unwrap();
+
+        assert_eq!(Some(last_kc), KeltnerChannels::calculate(&ts.candles));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+    utils::math::{ema_rolling, sma},
+};
+use anyhow::{anyhow, Context, Result};
+
+/// Moving Average Convergence Divergence. The fast and slow EMAs are kept to
+/// allow rolling calculations on new candles.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MACD {
+    pub line: f64,
+    pub signal: f64,
+    pub histogram: f64,
+    pub fast_ema: f64,
+    pub slow_ema: f64,
+}
+
+impl PopulatesCandles for MACD {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (fast_len, slow_len, signal_len) = args.macd_res()?;
+        let indicator_type = IndicatorType::MACD(fast_len, slow_len, signal_len);
+        let closes: Vec<f64> = ts.candles.iter().map(|candle| candle.close).collect();
+        let macds = Self::calculate_series(&closes, fast_len, slow_len, signal_len);
+
+        for (i, macd) in macds.into_iter().enumerate() {
+            ts.set_indicator(i, indicator_type, Indicator::MACD(macd));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (fast_len, slow_len, signal_len) = args.macd_res()?;
+        let indicator_type = IndicatorType::MACD(fast_len, slow_len, signal_len);
+        let ctx_err = "Unable to get last candle";
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_macd());
+
+        let new_macd = match prev {
+            Some(prev) => {
+                let close = ts.candles.last().context(ctx_err)?.close;
+                Some(prev.next(close, fast_len, slow_len, signal_len))
+            }
+            // Not enough candles for a previous MACD, calculate from scratch
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::MACD(new_macd))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for MACD {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::MACDArgs(12, 26, 9)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// The EMAs are seeded at the start of the segment, longer segments give
+    /// values closer to those of a MACD calculated over all available data.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (fast_len, slow_len, signal_len) = args.macd_opt()?;
+
+        if segment.len() < Self::needed_candles(slow_len, signal_len) {
+            return None;
+        }
+
+        let closes: Vec<f64> = segment.iter().map(|candle| candle.close).collect();
+
+        *Self::calculate_series(&closes, fast_len, slow_len, signal_len).last()?
+    }
+}
+
+impl MACD {
+    /// Returns the MACD following this one given the close of the new candle.
+    pub fn next(&self, close: f64, fast_len: usize, slow_len: usize, signal_len: usize) -> Self {
+        let fast_ema = ema_rolling(self.fast_ema, close, fast_len as f64);
+        let slow_ema = ema_rolling(self.slow_ema, close, slow_len as f64);
+        let line = fast_ema - slow_ema;
+        let signal = ema_rolling(self.signal, line, signal_len as f64);
+
+        MACD {
+            line,
+            signal,
+            histogram: line - signal,
+            fast_ema,
+            slow_ema,
+        }
+    }
+
+    fn needed_candles(slow_len: usize, signal_len: usize) -> usize {
+        slow_len + signal_len + 1
+    }
+
+    /// Returns the MACD for every close, None until enough closes are
+    /// available to calculate the signal line.
+    fn calculate_series(
+        closes: &[f64],
+        fast_len: usize,
+        slow_len: usize,
+        signal_len: usize,
+    ) -> Vec<Option<Self>> {
+        let mut fast_ema = None;
+        let mut slow_ema = None;
+        let mut signal = None;
+        let mut lines = Vec::new();
+
+        (1..=closes.len())
+            .map(|end| {
+                fast_ema = next_ema(fast_ema, &closes[..end], fast_len);
+                slow_ema = next_ema(slow_ema, &closes[..end], slow_len);
+
+                let line = fast_ema? - slow_ema?;
+                lines.push(line);
+                signal = next_ema(signal, &lines, signal_len);
+
+                Some(MACD {
+                    line,
+                    signal: signal?,
+                    histogram: line - signal?,
+                    fast_ema: fast_ema?,
+                    slow_ema: slow_ema?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Returns the EMA of the last value in `values` given the previous EMA, the
+/// first EMA is seeded by the SMA of the preceding `len` values.
+fn next_ema(prev: Option<f64>, values: &[f64], len: usize) -> Option<f64> {
+    let end = values.len();
+    let value = *values.last()?;
+
+    match prev {
+        Some(prev) => Some(ema_rolling(prev, value, len as f64)),
+        None if end > len => {
+            let initial_value = sma(&values[end - 1 - len..end - 1]);
+            Some(ema_rolling(initial_value, value, len as f64))
+        }
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, macd::MACD,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    const FINAL_VALUES: &[(f64, f64, f64)] = &[
+        (-3.4893603624502703, -5.60201268964976, 2.1126523271994895),
+        (-3.1232479055270232, -5.106259732825213, 1.9830118272981894),
+        (-3.1058252352249838, -4.706172833305167, 1.600347598080183),
+        (-3.5386490093731027, -4.472668068518754, 0.9340190591456512),
+        (-3.160413402117456, -4.210217135238494, 1.0498037331210384),
+    ];
+
+    #[test]
+    fn macd_calculate() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let macd = MACD::calculate(&candles).unwrap();
+
+        let (line, signal, histogram) = FINAL_VALUES.last().unwrap();
+        assert_eq!(macd.line, *line);
+        assert_eq!(macd.signal, *signal);
+        assert_eq!(macd.histogram, *histogram);
+    }
+
+    #[test]
+    fn macd_no_candles() {
+        let candles = Vec::new();
+        let macd = MACD::calculate(&candles);
+        assert!(macd.is_none());
+    }
+
+    #[test]
+    fn macd_no_candles_args() {
+        let candles = Vec::new();
+        let args = MACD::default_args();
+        let macd = MACD::calculate_args(&candles, &args);
+        assert!(macd.is_none());
+    }
+
+    #[test]
+    fn macd_populate_candles() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = MACD::populate_candles(&mut ts);
+
+        let (fast_len, slow_len, signal_len) = MACD::default_args().macd_opt().unwrap();
+        let indicator_type = IndicatorType::MACD(fast_len, slow_len, signal_len);
+
+        for i in 0..ts.candles.len() {
+            let macd = ts.indicator(i, &indicator_type).unwrap().as_macd();
+            if i < MACD::needed_candles(slow_len, signal_len) - 1 {
+                assert!(macd.is_none());
+            } else {
+                assert!(macd.is_some());
+            }
+        }
+
+        let segment = ts.view().window(ts.candles.len() - 5, ts.candles.len());
+
+        for (i, (line, signal, histogram)) in FINAL_VALUES.iter().enumerate() {
+            let macd = segment
+                .indicator(i, &indicator_type)
+                .unwrap()
+                .as_macd()
+                .unwrap();
+            assert_eq!(*line, macd.line);
+            assert_eq!(*signal, macd.signal);
+            assert_eq!(*histogram, macd.histogram);
+        }
+    }
+
+    #[test]
+    fn macd_populate_last_candle() {
+        let mut candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = MACD::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let (fast_len, slow_len, signal_len) = MACD::default_args().macd_opt().unwrap();
+        let indicator_type = IndicatorType::MACD(fast_len, slow_len, signal_len);
+
+        let last_macd = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_macd()
+            .unwrap();
+
+        let (line, signal, histogram) = FINAL_VALUES.last().unwrap();
+        assert_eq!(last_macd.line, *line);
+        assert_eq!(last_macd.signal, *signal);
+        assert_eq!(last_macd.histogram, *histogram);
+    }
+
+    #[test]
+    fn macd_populate_last_candle_from_start() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .build();
+        ts.indicators.insert(IndicatorType::MACD(12, 26, 9));
+
+        for candle in candles.iter() {
+            let _ = ts.add_candle(candle);
+        }
+
+        let last_macd = ts
+            .view()
+            .last_indicator(&IndicatorType::MACD(12, 26, 9))
+            .unwrap()
+            .as_macd()
+            .unwrap();
+
+        let (line, _, _) = FINAL_VALUES.last().unwrap();
+        assert_eq!(last_macd.line, *line);
+    }
+}
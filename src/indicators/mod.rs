@@ -1,16 +1,30 @@
+pub mod adx;
 pub mod atr;
 pub mod bbw;
 pub mod bbwp;
 pub mod bollinger_bands;
+pub mod donchian_channels;
 pub mod dynamic_pivots;
 pub mod ema;
+pub mod ichimoku;
 pub mod indicator;
 pub mod indicator_args;
+pub mod indicator_column;
+pub mod indicator_columns;
+pub mod indicator_state;
 pub mod indicator_type;
 pub mod is_indicator;
+pub mod keltner_channels;
+pub mod macd;
+pub mod obv;
 pub mod pmar;
 pub mod pmarp;
 pub mod populates_candles;
 pub mod rsi;
 pub mod sma;
+pub mod squeeze;
 pub mod stochastic;
+pub mod supertrend;
+pub mod volume_sma;
+pub mod vwap;
+pub mod vwma;
@@ -0,0 +1,204 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Result};
+
+/// # On-Balance Volume (OBV)
+///
+/// Running total of volume where the volume of a candle is added if it closed
+/// higher than the previous candle and subtracted if it closed lower. The
+/// value starts at zero on the first candle available.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct OBV {
+    pub value: f64,
+}
+
+impl PopulatesCandles for OBV {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, _args: IndicatorArgs) -> Result<()> {
+        let indicator_type = IndicatorType::OBV;
+        let mut prev: Option<(OBV, f64)> = None;
+
+        for i in 0..ts.candles.len() {
+            let candle = &ts.candles[i];
+            let obv = match prev {
+                Some((prev_obv, prev_close)) => prev_obv.next(prev_close, candle),
+                None => OBV { value: 0.0 },
+            };
+
+            prev = Some((obv, candle.close));
+            ts.set_indicator(i, indicator_type, Indicator::OBV(Some(obv)));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let indicator_type = IndicatorType::OBV;
+        let len = ts.candles.len();
+
+        if len == 0 {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_obv());
+
+        let new_obv = match prev {
+            Some(prev) => {
+                let prev_close = ts.candles[len - 2].close;
+                Some(prev.next(prev_close, &ts.candles[len - 1]))
+            }
+            // No previous OBV, calculate from the start of the series
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::OBV(new_obv))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for OBV {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::NoArgs
+    }
+
+    /// Returns the OBV of the last candle, accumulated from the first candle
+    /// of the segment.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let first = segment.first()?;
+
+        let (obv, _) = segment[1..].iter().fold(
+            (OBV { value: 0.0 }, first.close),
+            |(obv, prev_close), candle| (obv.next(prev_close, candle), candle.close),
+        );
+
+        Some(obv)
+    }
+
+    fn calculate_args(segment: &[Candle], _args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate(segment)
+    }
+}
+
+impl OBV {
+    /// Returns the OBV following this one given the previous close and the
+    /// new candle.
+    pub fn next(&self, prev_close: f64, candle: &Candle) -> Self {
+        let value = if candle.close > prev_close {
+            self.value + candle.volume
+        } else if candle.close < prev_close {
+            self.value - candle.volume
+        } else {
+            self.value
+        };
+
+        OBV { value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_type::IndicatorType, is_indicator::IsIndicator, obv::OBV,
+            populates_candles::PopulatesCandles,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+    };
+
+    #[test]
+    fn obv_calculate() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let obv = OBV::calculate(&candles);
+        assert!(obv.is_some());
+        assert_eq!(obv.unwrap().value, 9000.0);
+    }
+
+    #[test]
+    fn obv_calculate_alternating() {
+        // Closes alternate up and down, each move with equal volume
+        let candles = Candle::dummy_data(4, "alternating", 100.0);
+        let obv = OBV::calculate(&candles);
+        assert_eq!(obv.unwrap().value, -1000.0);
+    }
+
+    #[test]
+    fn obv_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let obv = OBV::calculate(&candles);
+        assert!(obv.is_none());
+    }
+
+    #[test]
+    fn obv_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = OBV::default_args();
+        let obv = OBV::calculate_args(&candles, &args);
+        assert!(obv.is_none());
+    }
+
+    #[test]
+    fn obv_populate_candles() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = OBV::populate_candles(&mut ts);
+
+        for i in 0..ts.candles.len() {
+            let obv = ts
+                .indicator(i, &IndicatorType::OBV)
+                .unwrap()
+                .as_obv()
+                .unwrap();
+            assert_eq!(obv.value, i as f64 * 1000.0);
+        }
+    }
+
+    #[test]
+    fn obv_populate_last_candle() {
+        let mut candles = Candle::dummy_data(10, "positive", 100.0);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = OBV::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_obv = ts
+            .view()
+            .last_indicator(&IndicatorType::OBV)
+            .unwrap()
+            .as_obv()
+            .unwrap();
+
+        assert_eq!(last_obv.value, 9000.0);
+    }
+}
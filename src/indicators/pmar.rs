@@ -1,10 +1,11 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_columns::IndicatorColumns,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
-    models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
-    utils::math::{sma, vwma},
+    models::{candle::Candle, candle_window::CandleView, ma_type::MAType, timeseries::TimeSeries},
+    utils::math::sma,
 };
 use anyhow::{anyhow, Context, Result};
 
@@ -37,19 +38,17 @@ impl PopulatesCandles for PMAR {
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let (len, ma_type) = args.pmar_res()?;
         let indicator_type = IndicatorType::PMAR(len, ma_type);
+        ma_type.populate_rolling(ts, len)?;
 
         for i in 0..ts.candles.len() {
             let end = i + 1;
             let pmar = if end < len {
                 None
             } else {
-                let start = end - len;
-                Self::calculate_args(&ts.candles[start..end], &args)
+                Self::calculate_view(ts.view().window(end - len, end), &args)
             };
 
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::PMAR(pmar));
+            ts.set_indicator(i, indicator_type, Indicator::PMAR(pmar));
 
             // If ma type is EMA one extra candle is needed to proceed with
             // calculations (EMA always needs one extra previous value as
@@ -63,14 +62,12 @@ impl PopulatesCandles for PMAR {
             }
 
             let mut pmar = pmar.context("Unable to calculate PMAR")?;
-            let sma = Self::pmar_sma(&ts.candles[end - len..end], &indicator_type);
+            let sma = Self::pmar_sma(ts.view().window(end - len, end), &indicator_type);
 
             if let Some(sma) = sma {
                 pmar.ma = Some(sma);
 
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::PMAR(Some(pmar)));
+                ts.set_indicator(i, indicator_type, Indicator::PMAR(Some(pmar)));
             }
         }
 
@@ -86,7 +83,6 @@ impl PopulatesCandles for PMAR {
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let (len, ma_type) = args.pmar_res()?;
         let end = ts.candles.len();
-        let ctx_err = "Failed to get last candle";
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
         if end == 0 {
@@ -95,36 +91,23 @@ impl PopulatesCandles for PMAR {
 
         if end < len {
             // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMAR(None));
+            ts.set_last_indicator(indicator_type, Indicator::PMAR(None))?;
 
             return Ok(());
         }
 
-        let new_pmar = Self::calculate_args(&ts.candles[end - len..end], &args);
+        let new_pmar = Self::calculate_view(ts.view().tail(len), &args);
 
         // Insert pmar without moving average
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::PMAR(new_pmar));
+        ts.set_last_indicator(indicator_type, Indicator::PMAR(new_pmar))?;
 
         // Attempt to calculate and insert pmar moving average
-        let sma_segment = &ts.candles[end - len..end];
-        let sma = Self::pmar_sma(sma_segment, &indicator_type);
-
-        ts.candles
-            .last_mut()
-            .and_then(|candle| candle.indicators.get_mut(&indicator_type))
-            .and_then(|indicator| match indicator {
-                Indicator::PMAR(pmar) => pmar.as_mut(),
-                _ => None,
-            })
-            .map(|pmar| pmar.ma = sma);
+        let sma = Self::pmar_sma(ts.view().tail(len), &indicator_type);
+
+        if let Some(mut pmar) = new_pmar {
+            pmar.ma = sma;
+            ts.set_last_indicator(indicator_type, Indicator::PMAR(Some(pmar)))?;
+        }
 
         Ok(())
     }
@@ -142,27 +125,35 @@ impl IsIndicator for PMAR {
     {
         let (_, ma_type) = Self::default_args().pmar_opt()?;
         let args = IndicatorArgs::PMARArgs(segment.len(), ma_type);
+        let columns = IndicatorColumns::new(0, 0);
 
-        Self::calculate_pmar(segment, &args)
+        Self::calculate_pmar(CandleView::new(segment, &columns), &args)
     }
 
     fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
     where
         Self: Sized,
     {
+        let columns = IndicatorColumns::new(0, 0);
+
+        Self::calculate_view(CandleView::new(segment, &columns), args)
+    }
+}
+
+impl PMAR {
+    /// Calculates the PMAR of the last candles of the view. EMA based PMARs
+    /// read the EMA from the indicators of the view.
+    pub fn calculate_view(segment: CandleView<'_>, args: &IndicatorArgs) -> Option<Self> {
         let (len, _) = args.pmar_opt()?;
-        let candle_len = segment.len();
 
-        if candle_len < len {
+        if segment.len() < len {
             return None;
         }
 
-        Self::calculate_pmar(&segment[candle_len - len..candle_len], args)
+        Self::calculate_pmar(segment.tail(len), args)
     }
-}
 
-impl PMAR {
-    fn calculate_pmar(segment: &[Candle], args: &IndicatorArgs) -> Option<Self> {
+    fn calculate_pmar(segment: CandleView<'_>, args: &IndicatorArgs) -> Option<Self> {
         let (len, ma_type) = args.pmar_opt()?;
         let segment_len = segment.len();
 
@@ -175,34 +166,13 @@ impl PMAR {
         }
 
         // Reset in case longer segment than args has been passed in
-        let segment = &segment[segment_len - len..segment_len];
+        let segment = segment.tail(len);
 
-        let pmar = match ma_type {
-            MAType::SMA => {
-                let values: Vec<f64> = segment.iter().map(|c| c.close).collect();
-
-                segment.last()?.close / sma(&values)
-            }
-            MAType::EMA => {
-                // REVISIT: This calculation depends on the EMA being populated
-                // in the candles. This is due to the rolling nature of EMAs.
-                // To properly calculate it here would require a large amount
-                // of iterations when populating further indicators (PMARP) and
-                // so this shortcut is taken. Revisit at some later point to
-                // rewrite the entire indicator system.
-                let candle = segment.last()?;
-                let ema = candle
-                    .indicators
-                    .get(&IndicatorType::EMA(len))
-                    .expect("Unable to find EMA indicator when calculating PMAR.")
-                    .as_ema()?;
-                candle.close / ema.value
-            }
-            MAType::VWMA => {
-                let values: Vec<(f64, f64)> = segment.iter().map(|c| (c.close, c.volume)).collect();
-                segment.last()?.close / vwma(&values)
-            }
-        };
+        // REVISIT: EMA based PMARs depend on the EMA being populated in the
+        // candles due to the rolling nature of EMAs. To properly calculate it
+        // here would require a large amount of iterations when populating
+        // further indicators (PMARP) and so this shortcut is taken.
+        let pmar = segment.last()?.close / ma_type.calculate(segment, len)?;
 
         Some(PMAR::new(pmar, segment_len))
     }
@@ -215,10 +185,11 @@ impl PMAR {
         }
     }
 
-    fn pmar_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
+    fn pmar_sma(segment: CandleView<'_>, indicator_type: &IndicatorType) -> Option<f64> {
         let values: Vec<f64> = segment
+            .column(indicator_type)
             .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_pmar())
+            .filter_map(|pmar| pmar?.as_pmar())
             .map(|pmar| pmar.value)
             .collect();
 
@@ -302,8 +273,8 @@ mod tests {
         let (len, ma_type) = PMAR::default_args().pmar_opt().unwrap();
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let pmar = indicator.as_pmar();
             if i < len - 1 {
                 assert!(pmar.is_none());
@@ -312,10 +283,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_pmar = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_pmar = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_pmar()
             .unwrap();
@@ -338,8 +308,8 @@ mod tests {
         let (len, ma_type) = PMAR::default_args().pmar_opt().unwrap();
         let indicator_type = IndicatorType::PMAR(len, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let pmar = indicator.as_pmar();
             if i < len - 1 {
                 assert!(pmar.is_none());
@@ -348,14 +318,51 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_pmar = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_pmar = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_pmar()
             .unwrap();
 
         assert_eq!(last_pmar.value, FINAL_VALUE);
     }
+
+    #[test]
+    fn pmar_vwma_constant_volume() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let sma_pmar = PMAR::calculate_args(&candles, &IndicatorArgs::PMARArgs(8, MAType::SMA));
+        let vwma_pmar = PMAR::calculate_args(&candles, &IndicatorArgs::PMARArgs(8, MAType::VWMA));
+
+        assert_eq!(sma_pmar.unwrap().value, vwma_pmar.unwrap().value);
+    }
+
+    #[test]
+    fn pmar_populate_candles_ema() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        // EMA is populated by PMAR as it can't be calculated from a segment
+        ts.add_indicator(IndicatorType::PMAR(20, MAType::EMA))
+            .unwrap();
+
+        let ema = ts
+            .view()
+            .last_indicator(&IndicatorType::EMA(20))
+            .unwrap()
+            .as_ema()
+            .unwrap();
+        let pmar = ts
+            .view()
+            .last_indicator(&IndicatorType::PMAR(20, MAType::EMA))
+            .unwrap()
+            .as_pmar()
+            .unwrap();
+
+        assert_eq!(pmar.value, ts.candles.last().unwrap().close / ema.value);
+    }
 }
@@ -1,9 +1,10 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, pmar::PMAR, populates_candles::PopulatesCandles,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator, pmar::PMAR,
+        populates_candles::PopulatesCandles,
     },
-    models::{candle::Candle, ma_type::MAType, timeseries::TimeSeries},
+    models::{candle::Candle, candle_window::CandleView, ma_type::MAType, timeseries::TimeSeries},
     utils::math::sma,
 };
 use anyhow::{anyhow, Context, Result};
@@ -28,44 +29,13 @@ impl PopulatesCandles for PMARP {
     }
 
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, ma_type) = args.pmarp_res()?;
-        let sma_len = len; // TODO: Change argument type so this is provided
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let (len, lookback, sma_len, ma_type) = args.pmarp_res()?;
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
+        ma_type.populate_rolling(ts, len)?;
+        ts.indicator_states.remove(&indicator_type);
 
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let pmarp = if end < len {
-                None
-            } else {
-                let start = if end > lookback + len {
-                    end - lookback - len
-                } else {
-                    0
-                };
-
-                Self::calculate_args(&ts.candles[start..end], &args)
-            };
-
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::PMARP(pmarp));
-
-            // Not enough candles to populate pmarp sma so return
-            if end < len + sma_len {
-                continue;
-            }
-
-            let mut pmarp = pmarp.context("Unable to calculate PMARP")?;
-            let sma_segment = &ts.candles[end - sma_len..end];
-            let sma = Self::pmarp_sma(sma_segment, &indicator_type);
-
-            if let Some(sma) = sma {
-                pmarp.ma = Some(sma);
-
-                ts.candles[i]
-                    .indicators
-                    .insert(indicator_type, Indicator::PMARP(Some(pmarp)));
-            }
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, &args)?;
         }
 
         ts.indicators.insert(indicator_type);
@@ -78,67 +48,19 @@ impl PopulatesCandles for PMARP {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (len, lookback, ma_type) = args.pmarp_res()?;
-        let sma_len = len; // TODO: Change argument type so this is provided
-        let ctx_err = "Unable to get last candle";
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
         let end = ts.candles.len();
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
         }
 
-        // Not enough candles to populate
-        if end < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMARP(None));
-
-            return Ok(());
-        }
-
-        let start = if end > lookback + len {
-            end - lookback - len
-        } else {
-            0
-        };
-
-        let mut new_pmarp = Self::calculate_args(&ts.candles[start..end], &args)
-            .context("Unable to calculate PMARP")?;
-
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::PMARP(Some(new_pmarp)));
-
-        // Not enough candles to populate pmarp sma so return
-        if end < len + sma_len {
-            return Ok(());
-        }
-
-        let sma_segment = &ts.candles[end - sma_len..end];
-        let sma = Self::pmarp_sma(sma_segment, &indicator_type);
-
-        if let Some(sma) = sma {
-            new_pmarp.ma = Some(sma);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::PMARP(Some(new_pmarp)));
-        }
-
-        Ok(())
+        Self::populate_candle(ts, end, &args)
     }
 }
 
 impl IsIndicator for PMARP {
     fn default_args() -> IndicatorArgs {
-        IndicatorArgs::PMARPArgs(20, 350, MAType::VWMA)
+        IndicatorArgs::PMARPArgs(20, 350, 20, MAType::VWMA) // len, lookback, sma_len, ma_type
     }
 
     fn calculate(segment: &[Candle]) -> Option<Self>
@@ -154,7 +76,7 @@ impl IsIndicator for PMARP {
     {
         let mut pmars = Self::get_pmars(segment, args).ok()?;
         let new_pmar = pmars.pop()??;
-        let (len, lookback, _) = args.pmarp_opt()?;
+        let (len, lookback, _, _) = args.pmarp_opt()?;
 
         let count = pmars
             .iter()
@@ -183,10 +105,67 @@ impl PMARP {
         }
     }
 
-    fn pmarp_sma(segment: &[Candle], indicator_type: &IndicatorType) -> Option<f64> {
+    /// Populates the candle at `end - 1`. The percentile is ranked against the
+    /// rolling window of previous PMARs kept in the TimeSeries so that only
+    /// the new PMAR needs to be calculated.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, args: &IndicatorArgs) -> Result<()> {
+        let (len, lookback, sma_len, ma_type) = args.pmarp_res()?;
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
+        let pmar_at = |view: CandleView<'_>, i: usize| Self::pmar_at(view, i, len, ma_type);
+
+        let new_state = || IndicatorState::new().with_percentiles(&[lookback]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            state.percentiles[0].push(pmar_at(view, i))
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, lookback, new_state, step);
+        let new_pmar = pmar_at(ts.view(), end - 1);
+
+        let pmarp = new_pmar.map(|new_pmar| PMARP {
+            value: state.percentiles[0].rank(new_pmar),
+            len,
+            ma: None,
+            lookback,
+        });
+
+        state.percentiles[0].push(new_pmar);
+        state.put(ts, indicator_type, end);
+
+        ts.set_indicator(end - 1, indicator_type, Indicator::PMARP(pmarp));
+
+        // Not enough candles to populate pmarp sma so return
+        if end < len + sma_len {
+            return Ok(());
+        }
+
+        let mut pmarp = pmarp.context("Unable to calculate PMARP")?;
+        let sma_segment = ts.view().window(end - sma_len, end);
+        let sma = Self::pmarp_sma(sma_segment, &indicator_type);
+
+        if let Some(sma) = sma {
+            pmarp.ma = Some(sma);
+
+            ts.set_indicator(end - 1, indicator_type, Indicator::PMARP(Some(pmarp)));
+        }
+
+        Ok(())
+    }
+
+    /// PMAR value of the candle at index `i`.
+    fn pmar_at(view: CandleView<'_>, i: usize, len: usize, ma_type: MAType) -> Option<f64> {
+        if i + 1 < len {
+            return None;
+        }
+
+        let args = IndicatorArgs::PMARArgs(len, ma_type);
+        PMAR::calculate_view(view.window(i + 1 - len, i + 1), &args).map(|pmar| pmar.value)
+    }
+
+    fn pmarp_sma(segment: CandleView<'_>, indicator_type: &IndicatorType) -> Option<f64> {
         let values: Vec<f64> = segment
+            .column(indicator_type)
             .iter()
-            .filter_map(|c| c.clone_indicator(indicator_type).ok()?.as_pmarp())
+            .filter_map(|pmarp| pmarp?.as_pmarp())
             .map(|pmarp| pmarp.value)
             .collect();
 
@@ -202,16 +181,16 @@ impl PMARP {
         let mut temp_ts = TimeSeries::dummy();
         temp_ts.set_candles(&temp_segment);
 
-        let (len, _, ma_type) = args.pmarp_res()?;
+        let (len, _, _, ma_type) = args.pmarp_res()?;
         let pmar_args = IndicatorArgs::PMARArgs(len, ma_type);
         PMAR::populate_candles_args(&mut temp_ts, pmar_args)?;
 
         let ind_type = IndicatorType::PMAR(len, ma_type);
 
         let pmars: Vec<Option<PMAR>> = temp_ts
-            .candles
+            .indicator_column(&ind_type)
             .iter()
-            .map(|candle| candle.clone_indicator(&ind_type).unwrap().as_pmar())
+            .map(|pmar| pmar?.as_pmar())
             .collect();
 
         Ok(pmars)
@@ -247,8 +226,8 @@ mod tests {
     #[test]
     fn pmarp_calculate_args_vwma() {
         let candles = Candle::dummy_from_increments(&PRICE_CHANGES);
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
-        let args = IndicatorArgs::PMARPArgs(len, lookback, MAType::VWMA);
+        let (len, lookback, sma_len, _) = PMARP::default_args().pmarp_opt().unwrap();
+        let args = IndicatorArgs::PMARPArgs(len, lookback, sma_len, MAType::VWMA);
         let pmarp = PMARP::calculate_args(&candles, &args);
         assert!(pmarp.is_some());
 
@@ -259,8 +238,8 @@ mod tests {
     #[test]
     fn pmarp_calculate_args_sma() {
         let candles = Candle::dummy_from_increments(&PRICE_CHANGES);
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
-        let args = IndicatorArgs::PMARPArgs(len, lookback, MAType::SMA);
+        let (len, lookback, sma_len, _) = PMARP::default_args().pmarp_opt().unwrap();
+        let args = IndicatorArgs::PMARPArgs(len, lookback, sma_len, MAType::SMA);
         let pmarp = PMARP::calculate_args(&candles, &args);
         assert!(pmarp.is_some());
 
@@ -315,11 +294,11 @@ mod tests {
 
         let _ = PMARP::populate_candles(&mut ts);
 
-        let (len, lookback, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let (len, lookback, sma_len, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let pmarp = indicator.as_pmarp();
             if i < len - 1 {
                 assert!(pmarp.is_none());
@@ -328,10 +307,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_pmarp = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_pmarp = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_pmarp()
             .unwrap();
@@ -348,15 +326,15 @@ mod tests {
             .candles(candles)
             .build();
 
-        let (len, lookback, _) = PMARP::default_args().pmarp_opt().unwrap();
+        let (len, lookback, sma_len, _) = PMARP::default_args().pmarp_opt().unwrap();
         let ma_type = MAType::VWMA;
-        let args = IndicatorArgs::PMARPArgs(len, lookback, ma_type);
+        let args = IndicatorArgs::PMARPArgs(len, lookback, sma_len, ma_type);
         let _ = PMARP::populate_candles_args(&mut ts, args);
 
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let pmarp = indicator.as_pmarp();
             if i < len - 1 {
                 assert!(pmarp.is_none());
@@ -365,10 +343,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_pmarp = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_pmarp = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_pmarp()
             .unwrap();
@@ -390,11 +367,11 @@ mod tests {
         let _ = PMARP::populate_candles(&mut ts);
         let _ = ts.add_candle(&candle);
 
-        let (len, lookback, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
-        let indicator_type = IndicatorType::PMARP(len, lookback, ma_type);
+        let (len, lookback, sma_len, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let pmarp = indicator.as_pmarp();
             if i < len - 1 {
                 assert!(pmarp.is_none());
@@ -403,10 +380,68 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_pmarp = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_pmarp = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_pmarp()
+            .unwrap();
+
+        assert_eq!(last_pmarp.value, FINAL_VALUE);
+    }
+
+    #[test]
+    fn pmarp_populate_last_candle_matches_populate_candles() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let args = IndicatorArgs::PMARPArgs(20, 100, 10, MAType::EMA);
+        let indicator_type = IndicatorType::PMARP(20, 100, 10, MAType::EMA);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        PMARP::populate_candles_args(&mut full_ts, args).unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles[..50].to_vec())
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles[50..].iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        for i in 0..ts.candles.len() {
+            let pmarp = ts.indicator(i, &indicator_type).unwrap().as_pmarp();
+            let full_pmarp = full_ts.indicator(i, &indicator_type).unwrap().as_pmarp();
+            assert_eq!(pmarp, full_pmarp);
+        }
+    }
+
+    #[test]
+    fn pmarp_populate_last_candle_after_set_candles() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let (len, lookback, sma_len, ma_type) = PMARP::default_args().pmarp_opt().unwrap();
+        let indicator_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles[..100].to_vec())
+            .build();
+        PMARP::populate_candles(&mut ts).unwrap();
+
+        // Replacing the candles invalidates the rolling state which then
+        // needs to be rebuilt from the candles
+        ts.set_candles(&candles);
+        PMARP::populate_last_candle(&mut ts).unwrap();
+
+        let last_pmarp = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_pmarp()
             .unwrap();
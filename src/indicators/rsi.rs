@@ -5,7 +5,7 @@ use crate::{
     },
     models::{candle::Candle, timeseries::TimeSeries},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use serde::Serialize;
 
 #[derive(Debug, Copy, Clone, Serialize, PartialEq, PartialOrd)]
@@ -39,9 +39,7 @@ impl PopulatesCandles for RSI {
                 Self::calculate_rolling(candles, prev, len)
             };
 
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::RSI(rsi));
+            ts.set_indicator(i, indicator_type, Indicator::RSI(rsi));
             prev = rsi;
         }
 
@@ -57,7 +55,6 @@ impl PopulatesCandles for RSI {
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let len = args.len_res()?;
         let indicator_type = IndicatorType::RSI(len);
-        let ctx_err = "Unable to get last candle";
         let candle_len = ts.candles.len();
 
         if candle_len == 0 {
@@ -66,11 +63,7 @@ impl PopulatesCandles for RSI {
 
         // Not enough candles to populate
         if candle_len < len {
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::RSI(None));
+            ts.set_last_indicator(indicator_type, Indicator::RSI(None))?;
 
             return Ok(());
         };
@@ -87,11 +80,7 @@ impl PopulatesCandles for RSI {
             Self::calculate_rolling(candles, prev_rsi.unwrap(), len)
         };
 
-        ts.candles
-            .last_mut()
-            .context(ctx_err)?
-            .indicators
-            .insert(indicator_type, Indicator::RSI(new_rsi));
+        ts.set_last_indicator(indicator_type, Indicator::RSI(new_rsi))?;
 
         Ok(())
     }
@@ -262,8 +251,8 @@ mod tests {
         let len = RSI::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::RSI(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let rsi = indicator.as_rsi();
             if i < len {
                 assert!(rsi.is_none());
@@ -272,10 +261,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_sma = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_sma = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_rsi()
             .unwrap();
@@ -300,8 +288,8 @@ mod tests {
         let len = RSI::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::RSI(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let rsi = indicator.as_rsi();
             if i < len {
                 assert!(rsi.is_none());
@@ -310,10 +298,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_rsi = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_rsi = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_rsi()
             .unwrap();
@@ -4,9 +4,9 @@ use crate::{
         is_indicator::IsIndicator, populates_candles::PopulatesCandles,
     },
     models::{candle::Candle, timeseries::TimeSeries},
-    utils::math::sma,
+    utils::math::{sma, sma_rolling},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct SMA {
@@ -32,9 +32,7 @@ impl PopulatesCandles for SMA {
                 Self::calculate_args(&ts.candles[start..end], &args)
             };
 
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::SMA(sma));
+            ts.set_indicator(i, indicator_type, Indicator::SMA(sma));
         }
 
         ts.indicators.insert(indicator_type);
@@ -49,26 +47,31 @@ impl PopulatesCandles for SMA {
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let len = args.len_res()?;
         let end = ts.candles.len();
-        let ctx_err = "Failed to get last candle";
         let indicator_type = IndicatorType::SMA(len);
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
         } else if end < len {
             // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::SMA(None));
+            ts.set_last_indicator(indicator_type, Indicator::SMA(None))?;
         } else {
-            let new_sma = Self::calculate_args(&ts.candles[end - len..end], &args);
+            let prev = Indicator::get_second_last(ts, &indicator_type)
+                .and_then(|indicator| indicator.as_sma());
+
+            let new_sma = match prev {
+                Some(prev) if end > len => {
+                    let close_in = ts.candles[end - 1].close;
+                    let close_out = ts.candles[end - 1 - len].close;
+
+                    Some(SMA {
+                        value: sma_rolling(close_in, close_out, prev.value, len as f64),
+                        len,
+                    })
+                }
+                _ => Self::calculate_args(&ts.candles[end - len..end], &args),
+            };
 
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::SMA(new_sma));
+            ts.set_last_indicator(indicator_type, Indicator::SMA(new_sma))?;
         }
 
         Ok(())
@@ -175,8 +178,8 @@ mod tests {
         let len = SMA::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::SMA(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let sma = indicator.as_sma();
             if i < len - 1 {
                 assert!(sma.is_none());
@@ -185,10 +188,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_sma = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_sma = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_sma()
             .unwrap();
@@ -226,8 +228,8 @@ mod tests {
         let len = SMA::default_args().len_opt().unwrap();
         let indicator_type = IndicatorType::SMA(len);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let sma = indicator.as_sma();
             if i < len - 1 {
                 assert!(sma.is_none());
@@ -236,10 +238,9 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_sma = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_sma = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_sma()
             .unwrap();
@@ -0,0 +1,296 @@
+use crate::{
+    indicators::{
+        atr::ATR, bollinger_bands::BollingerBands, donchian_channels::DonchianChannels,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, fixed_point::FixedPoint, ma_type::MAType, timeseries::TimeSeries},
+    utils::math::sma,
+};
+use anyhow::{anyhow, Result};
+
+/// # Squeeze
+///
+/// TTM-style squeeze where the squeeze is on while the Bollinger Bands sit
+/// inside the Keltner Channels, i.e. volatility is unusually low. The Keltner
+/// Channels use an SMA basis and a simple average of the true range. The
+/// momentum is the linear regression value of the close relative to the mean
+/// of the Donchian midpoint and the SMA, its sign indicates the likely
+/// direction of the breakout once the squeeze releases. The standard
+/// deviations of the Bollinger Bands and the ATR multiplier of the Keltner
+/// Channels are configurable, 2 and 1.5 by default.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Squeeze {
+    pub is_on: bool,
+    pub momentum: f64,
+    pub bb_upper: f64,
+    pub bb_lower: f64,
+    pub kc_upper: f64,
+    pub kc_lower: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for Squeeze {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, _, _) = args.squeeze_res()?;
+        let indicator_type = Self::indicator_type(&args)?;
+        let needed_len = Self::needed_candles(len);
+
+        for i in 0..ts.candles.len() {
+            let end = i + 1;
+            let squeeze = if end < needed_len {
+                None
+            } else {
+                Self::calculate_args(&ts.candles[end - needed_len..end], &args)
+            };
+
+            ts.set_indicator(i, indicator_type, Indicator::Squeeze(squeeze));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let indicator_type = Self::indicator_type(&args)?;
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        // Not enough candles results in None
+        let new_squeeze = Self::calculate_args(&ts.candles, &args);
+
+        ts.set_last_indicator(indicator_type, Indicator::Squeeze(new_squeeze))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for Squeeze {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::SqueezeArgs(20, 2.0, 1.5) // len, bb_std_n, kc_multiplier
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Segment should be at least twice the length of the squeeze, the
+    /// momentum is a regression over `len` values each needing `len` candles.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (len, bb_std_n, kc_multiplier) = args.squeeze_opt()?;
+        let end = segment.len();
+
+        if len < 2 || end < Self::needed_candles(len) {
+            return None;
+        }
+
+        let window = &segment[end - len..end];
+        let bb = BollingerBands::calculate_args(
+            window,
+            &IndicatorArgs::BollingerBandArgs(len, bb_std_n, MAType::SMA),
+        )?;
+
+        let closes: Vec<f64> = window.iter().map(|candle| candle.close).collect();
+        let true_ranges: Vec<f64> = (end - len..end)
+            .map(|i| ATR::true_range(segment[i - 1].close, &segment[i]))
+            .collect();
+        let kc_basis = sma(&closes);
+        let kc_range = sma(&true_ranges);
+
+        let deltas = (end - len..end)
+            .map(|i| Self::delta(&segment[i + 1 - len..i + 1]))
+            .collect::<Option<Vec<f64>>>()?;
+
+        let kc_upper = kc_basis + kc_multiplier * kc_range;
+        let kc_lower = kc_basis - kc_multiplier * kc_range;
+
+        Some(Squeeze {
+            is_on: bb.lower > kc_lower && bb.upper < kc_upper,
+            momentum: linreg(&deltas),
+            bb_upper: bb.upper,
+            bb_lower: bb.lower,
+            kc_upper,
+            kc_lower,
+            len,
+        })
+    }
+}
+
+impl Squeeze {
+    fn needed_candles(len: usize) -> usize {
+        2 * len
+    }
+
+    fn indicator_type(args: &IndicatorArgs) -> Result<IndicatorType> {
+        let (len, bb_std_n, kc_multiplier) = args.squeeze_res()?;
+
+        Ok(IndicatorType::Squeeze(
+            len,
+            FixedPoint::new(bb_std_n),
+            FixedPoint::new(kc_multiplier),
+        ))
+    }
+
+    /// Returns the close of the last candle relative to the mean of the
+    /// Donchian midpoint and the SMA of the window.
+    fn delta(window: &[Candle]) -> Option<f64> {
+        let dc = DonchianChannels::calculate(window)?;
+        let closes: Vec<f64> = window.iter().map(|candle| candle.close).collect();
+
+        Some(window.last()?.close - (dc.middle + sma(&closes)) / 2.0)
+    }
+}
+
+/// Returns the value at the last point of the least squares line fitted
+/// through the values.
+fn linreg(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = sma(values);
+
+    let (cov, var) = values
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = x as f64 - x_mean;
+            (cov + dx * (y - y_mean), var + dx * dx)
+        });
+
+    let slope = if var == 0.0 { 0.0 } else { cov / var };
+
+    y_mean + slope * (n - 1.0 - x_mean)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, squeeze::Squeeze,
+        },
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    fn default_type() -> IndicatorType {
+        IndicatorType::Squeeze(20, FixedPoint::new(2.0), FixedPoint::new(1.5))
+    }
+
+    #[test]
+    fn squeeze_calculate() {
+        // A steady trend has a wide standard deviation compared to its range
+        let candles = Candle::dummy_data(40, "positive", 100.0);
+        let squeeze = Squeeze::calculate(&candles).unwrap();
+        assert!(!squeeze.is_on);
+        assert!(squeeze.momentum > 0.0);
+    }
+
+    #[test]
+    fn squeeze_calculate_args() {
+        // Closes alternating around a mean with large ranges squeeze the bands
+        let mut candles = Candle::dummy_data(10, "alternating", 100.0);
+        for candle in candles.iter_mut() {
+            candle.high += 20.0;
+            candle.low -= 20.0;
+        }
+
+        let args = IndicatorArgs::SqueezeArgs(4, 2.0, 1.5);
+        let squeeze = Squeeze::calculate_args(&candles, &args).unwrap();
+        assert!(squeeze.is_on);
+        assert!(squeeze.bb_upper < squeeze.kc_upper);
+        assert!(squeeze.bb_lower > squeeze.kc_lower);
+
+        // Narrower channels release the squeeze
+        let args = IndicatorArgs::SqueezeArgs(4, 2.0, 0.1);
+        let narrow = Squeeze::calculate_args(&candles, &args).unwrap();
+        assert!(!narrow.is_on);
+        assert_eq!(narrow.bb_upper, squeeze.bb_upper);
+    }
+
+    #[test]
+    fn squeeze_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let squeeze = Squeeze::calculate(&candles);
+        assert!(squeeze.is_none());
+    }
+
+    #[test]
+    fn squeeze_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = Squeeze::default_args();
+        let squeeze = Squeeze::calculate_args(&candles, &args);
+        assert!(squeeze.is_none());
+    }
+
+    #[test]
+    fn squeeze_populate_candles() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = Squeeze::populate_candles(&mut ts);
+
+        for i in 0..ts.candles.len() {
+            let squeeze = ts.indicator(i, &default_type()).unwrap().as_squeeze();
+            if i < 39 {
+                assert!(squeeze.is_none());
+            } else {
+                assert!(squeeze.is_some());
+            }
+        }
+
+        let last_squeeze = ts
+            .view()
+            .last_indicator(&default_type())
+            .unwrap()
+            .as_squeeze()
+            .unwrap();
+        assert_eq!(Some(last_squeeze), Squeeze::calculate(&ts.candles));
+    }
+
+    #[test]
+    fn squeeze_populate_last_candle() {
+        let mut candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = Squeeze::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_squeeze = ts
+            .view()
+            .last_indicator(&default_type())
+            .unwrap()
+            .as_squeeze()
+            .unwrap();
+
+        assert_eq!(Some(last_squeeze), Squeeze::calculate(&ts.candles));
+    }
+}
@@ -1,11 +1,12 @@
 use crate::{
     indicators::{
-        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
     },
-    models::{candle::Candle, timeseries::TimeSeries},
+    models::{candle::Candle, candle_window::CandleView, timeseries::TimeSeries},
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct Stochastic {
@@ -28,19 +29,10 @@ impl PopulatesCandles for Stochastic {
     fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
         let (k_len, k_smoothing, d_smoothing) = args.stochastic_res()?;
         let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
-        let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
-
-        for i in 0..ts.candles.len() {
-            let end = i + 1;
-            let stoch = if end < needed_candles {
-                None
-            } else {
-                Self::calculate_args(&ts.candles[end - needed_candles..end], &args)
-            };
+        ts.indicator_states.remove(&indicator_type);
 
-            ts.candles[i]
-                .indicators
-                .insert(indicator_type, Indicator::Stochastic(stoch));
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, &args)?;
         }
 
         ts.indicators.insert(indicator_type);
@@ -53,32 +45,13 @@ impl PopulatesCandles for Stochastic {
     }
 
     fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
-        let (k_len, k_smoothing, d_smoothing) = args.stochastic_res()?;
         let end = ts.candles.len();
-        let ctx_err = "Failed to get last candle";
-        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
-        let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
 
         if end == 0 {
             return Err(anyhow!("No candle to populate"));
-        } else if end < needed_candles {
-            // Not enough candles to populate
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::Stochastic(None));
-        } else {
-            let new_stoch = Self::calculate_args(&ts.candles[end - needed_candles..end], &args);
-
-            ts.candles
-                .last_mut()
-                .context(ctx_err)?
-                .indicators
-                .insert(indicator_type, Indicator::Stochastic(new_stoch));
         }
 
-        Ok(())
+        Self::populate_candle(ts, end, &args)
     }
 }
 
@@ -152,6 +125,63 @@ impl Stochastic {
     fn needed_candles(k_len: usize, k_smoothing: usize, d_smoothing: usize) -> usize {
         k_len + k_smoothing + d_smoothing - 2
     }
+
+    /// Populates the candle at `end - 1`. The highs and lows of the last
+    /// `k_len` candles and the %K values being smoothed are kept in the
+    /// TimeSeries so that only the new candle needs to be added.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, args: &IndicatorArgs) -> Result<()> {
+        let (k_len, k_smoothing, d_smoothing) = args.stochastic_res()?;
+        let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
+        let needed_candles = Self::needed_candles(k_len, k_smoothing, d_smoothing);
+        let new_state = || {
+            IndicatorState::new()
+                .with_extremes(&[k_len])
+                .with_sums(&[k_smoothing, d_smoothing])
+        };
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            Self::next(state, &view[i]);
+        };
+
+        let mut state =
+            IndicatorState::take(ts, &indicator_type, end, needed_candles, new_state, step);
+        let stoch = Self::next(&mut state, &ts.candles[end - 1]);
+
+        state.put(ts, indicator_type, end);
+        ts.set_indicator(end - 1, indicator_type, Indicator::Stochastic(stoch));
+
+        Ok(())
+    }
+
+    /// Updates the state with the candle, returning the Stochastic once
+    /// enough candles have been added to smooth %K and %D.
+    fn next(state: &mut IndicatorState, candle: &Candle) -> Option<Self> {
+        state.extremes[0].push(candle.high, candle.low);
+        let (high, low) = state.extremes[0].high_low()?;
+
+        let k = if high == low {
+            0.5
+        } else {
+            (candle.close - low) / (high - low)
+        };
+
+        state.sums[0].push(k);
+
+        if !state.sums[0].is_full() {
+            return None;
+        }
+
+        let k = state.sums[0].mean();
+        state.sums[1].push(k);
+
+        if !state.sums[1].is_full() {
+            return None;
+        }
+
+        Some(Self {
+            k,
+            d: state.sums[1].mean(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -186,17 +216,20 @@ mod tests {
         let args = Stochastic::krown_args();
         let _ = Stochastic::populate_candles_args(&mut ts, args);
 
-        let segment = &ts.candles[ts.candles.len() - 5..];
+        let segment = ts.view().window(ts.candles.len() - 5, ts.candles.len());
 
         let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
         for (i, (k_val, d_val)) in FINAL_VALUES.iter().enumerate() {
-            let stochastic = segment[i]
-                .clone_indicator(&IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing))
+            let stochastic = segment
+                .indicator(
+                    i,
+                    &IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing),
+                )
                 .unwrap()
                 .as_stochastic()
                 .unwrap();
-            assert_eq!(*k_val, stochastic.k);
-            assert_eq!(*d_val, stochastic.d);
+            assert!((k_val - stochastic.k).abs() < 1e-9);
+            assert!((d_val - stochastic.d).abs() < 1e-9);
         }
     }
 
@@ -230,8 +263,8 @@ mod tests {
         let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
         let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let stochastic = indicator.as_stochastic();
             if i < Stochastic::needed_candles(k_len, k_smoothing, d_smoothing) - 1 {
                 assert!(stochastic.is_none());
@@ -240,16 +273,19 @@ mod tests {
             }
         }
 
-        let segment = &ts.candles[ts.candles.len() - 5..];
+        let segment = ts.view().window(ts.candles.len() - 5, ts.candles.len());
 
         for (i, (k_val, d_val)) in FINAL_VALUES.iter().enumerate() {
-            let stoch = segment[i]
-                .clone_indicator(&IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing))
+            let stoch = segment
+                .indicator(
+                    i,
+                    &IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing),
+                )
                 .unwrap()
                 .as_stochastic()
                 .unwrap();
-            assert_eq!(*k_val, stoch.k);
-            assert_eq!(*d_val, stoch.d);
+            assert!((k_val - stoch.k).abs() < 1e-9);
+            assert!((d_val - stoch.d).abs() < 1e-9);
         }
     }
 
@@ -270,8 +306,8 @@ mod tests {
         let (k_len, k_smoothing, d_smoothing) = args.stochastic_opt().unwrap();
         let indicator_type = IndicatorType::Stochastic(k_len, k_smoothing, d_smoothing);
 
-        for (i, candle) in ts.candles.iter().enumerate() {
-            let indicator = candle.indicators.get(&indicator_type).unwrap();
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
             let stochastic = indicator.as_stochastic();
             if i < Stochastic::needed_candles(k_len, k_smoothing, d_smoothing) - 1 {
                 assert!(stochastic.is_none());
@@ -280,16 +316,62 @@ mod tests {
             }
         }
 
-        let last_candle = ts.candles.last().unwrap();
-        let last_stoch = last_candle
-            .indicators
-            .get(&indicator_type)
+        let last_stoch = ts
+            .view()
+            .last_indicator(&indicator_type)
             .unwrap()
             .as_stochastic()
             .unwrap();
 
         let (k_val, d_val) = FINAL_VALUES.last().unwrap();
-        assert_eq!(&last_stoch.k, k_val);
-        assert_eq!(&last_stoch.d, d_val);
+        assert!((last_stoch.k - k_val).abs() < 1e-9);
+        assert!((last_stoch.d - d_val).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stochastic_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = Stochastic::krown_args();
+        let indicator_type = IndicatorType::Stochastic(14, 3, 6);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        Stochastic::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_stochastic());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_stochastic());
+            assert_eq!(value, full_value);
+
+            let window = &candles[..offset + i + 1];
+            let expected = Stochastic::calculate_args(window, &args);
+            assert_eq!(value.is_some(), expected.is_some());
+
+            if let (Some(value), Some(expected)) = (value, expected) {
+                assert!((value.k - expected.k).abs() < 1e-9);
+                assert!((value.d - expected.d).abs() < 1e-9);
+            }
+        }
     }
 }
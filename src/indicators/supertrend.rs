@@ -0,0 +1,290 @@
+use crate::{
+    indicators::{
+        atr::ATR, indicator::Indicator, indicator_args::IndicatorArgs,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, fixed_point::FixedPoint, timeseries::TimeSeries},
+};
+use anyhow::{anyhow, Result};
+
+/// # Supertrend
+///
+/// Trailing line placed a multiple of the ATR below the candle midpoint in
+/// uptrends and above it in downtrends. The trend flips when a candle closes
+/// on the other side of the line. The bands only move in the direction of the
+/// trend, which is why the previous Supertrend is needed for the next one.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Supertrend {
+    pub value: f64,
+    pub is_uptrend: bool,
+    pub upper_band: f64,
+    pub lower_band: f64,
+    pub atr: f64,
+}
+
+impl PopulatesCandles for Supertrend {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, multiplier) = args.supertrend_res()?;
+        let indicator_type = IndicatorType::Supertrend(len, FixedPoint::new(multiplier));
+        let supertrends = Self::calculate_series(&ts.candles, len, multiplier);
+
+        for (i, supertrend) in supertrends.into_iter().enumerate() {
+            ts.set_indicator(i, indicator_type, Indicator::Supertrend(supertrend));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let (len, multiplier) = args.supertrend_res()?;
+        let indicator_type = IndicatorType::Supertrend(len, FixedPoint::new(multiplier));
+        let candle_len = ts.candles.len();
+
+        if candle_len == 0 {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_supertrend());
+
+        let new_supertrend = match prev {
+            Some(prev) => Some(prev.next(
+                &ts.candles[candle_len - 2],
+                &ts.candles[candle_len - 1],
+                len,
+                multiplier,
+            )),
+            // Not enough candles for a previous Supertrend, calculate from scratch
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::Supertrend(new_supertrend))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for Supertrend {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::SupertrendArgs(10, 3.0)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// The trend is seeded at the start of the segment, longer segments give
+    /// values closer to those of a Supertrend calculated over all available
+    /// data.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (len, multiplier) = args.supertrend_opt()?;
+
+        if len == 0 || segment.len() <= len {
+            return None;
+        }
+
+        *Self::calculate_series(segment, len, multiplier).last()?
+    }
+}
+
+impl Supertrend {
+    /// Returns the Supertrend following this one given the previous and new
+    /// candle.
+    pub fn next(&self, prev_candle: &Candle, candle: &Candle, len: usize, multiplier: f64) -> Self {
+        let atr = ATR::calculate_rolling((prev_candle, candle), self.atr, len)
+            .map_or(self.atr, |atr| atr.value);
+        let (basic_upper, basic_lower) = Self::basic_bands(candle, atr, multiplier);
+
+        // Bands may only tighten unless the previous close broke through them
+        let upper_band = if basic_upper < self.upper_band || prev_candle.close > self.upper_band {
+            basic_upper
+        } else {
+            self.upper_band
+        };
+        let lower_band = if basic_lower > self.lower_band || prev_candle.close < self.lower_band {
+            basic_lower
+        } else {
+            self.lower_band
+        };
+
+        let is_uptrend = if self.is_uptrend {
+            candle.close >= lower_band
+        } else {
+            candle.close > upper_band
+        };
+
+        Self::new(is_uptrend, upper_band, lower_band, atr)
+    }
+
+    fn new(is_uptrend: bool, upper_band: f64, lower_band: f64, atr: f64) -> Self {
+        Supertrend {
+            value: if is_uptrend { lower_band } else { upper_band },
+            is_uptrend,
+            upper_band,
+            lower_band,
+            atr,
+        }
+    }
+
+    fn basic_bands(candle: &Candle, atr: f64, multiplier: f64) -> (f64, f64) {
+        let mid = (candle.high + candle.low) / 2.0;
+        let offset = multiplier * atr;
+
+        (mid + offset, mid - offset)
+    }
+
+    /// Returns the Supertrend for every candle, None until enough candles are
+    /// available to calculate the ATR.
+    fn calculate_series(candles: &[Candle], len: usize, multiplier: f64) -> Vec<Option<Self>> {
+        let mut prev: Option<Supertrend> = None;
+
+        (0..candles.len())
+            .map(|i| {
+                if i < len {
+                    return None;
+                }
+
+                let supertrend = match prev {
+                    Some(prev) => prev.next(&candles[i - 1], &candles[i], len, multiplier),
+                    None => {
+                        let atr = ATR::calculate(&candles[i - len..i + 1])?.value;
+                        let candle = &candles[i];
+                        let (upper_band, lower_band) = Self::basic_bands(candle, atr, multiplier);
+                        let is_uptrend = candle.close >= (candle.high + candle.low) / 2.0;
+
+                        Self::new(is_uptrend, upper_band, lower_band, atr)
+                    }
+                };
+
+                prev = Some(supertrend);
+                prev
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, supertrend::Supertrend,
+        },
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval,
+            timeseries_builder::TimeSeriesBuilder,
+        },
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn supertrend_calculate() {
+        let candles = Candle::dummy_data(30, "positive", 100.0);
+        let supertrend = Supertrend::calculate(&candles).unwrap();
+        assert!(supertrend.is_uptrend);
+        assert_eq!(supertrend.atr, 10.0);
+        assert_eq!(supertrend.value, 370.0);
+        assert_eq!(supertrend.value, supertrend.lower_band);
+    }
+
+    #[test]
+    fn supertrend_calculate_args() {
+        // Trend flips once the close drops below the lower band
+        let mut candles = Candle::dummy_data(10, "positive", 100.0);
+        candles.extend(Candle::dummy_data(10, "negative", 200.0));
+        let args = IndicatorArgs::SupertrendArgs(5, 2.0);
+        let supertrend = Supertrend::calculate_args(&candles, &args).unwrap();
+        assert!(!supertrend.is_uptrend);
+        assert_eq!(supertrend.value, supertrend.upper_band);
+    }
+
+    #[test]
+    fn supertrend_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let supertrend = Supertrend::calculate(&candles);
+        assert!(supertrend.is_none());
+    }
+
+    #[test]
+    fn supertrend_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = Supertrend::default_args();
+        let supertrend = Supertrend::calculate_args(&candles, &args);
+        assert!(supertrend.is_none());
+    }
+
+    #[test]
+    fn supertrend_populate_candles() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = Supertrend::populate_candles(&mut ts);
+        let indicator_type = IndicatorType::Supertrend(10, FixedPoint::new(3.0));
+
+        for i in 0..ts.candles.len() {
+            let supertrend = ts.indicator(i, &indicator_type).unwrap().as_supertrend();
+            if i < 10 {
+                assert!(supertrend.is_none());
+            } else {
+                let supertrend = supertrend.unwrap();
+                let band = match supertrend.is_uptrend {
+                    true => supertrend.lower_band,
+                    false => supertrend.upper_band,
+                };
+                assert_eq!(supertrend.value, band);
+            }
+        }
+
+        let last_supertrend = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_supertrend()
+            .unwrap();
+        assert_eq!(Some(last_supertrend), Supertrend::calculate(&ts.candles));
+    }
+
+    #[test]
+    fn supertrend_populate_last_candle() {
+        let mut candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = Supertrend::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_supertrend = ts
+            .view()
+            .last_indicator(&IndicatorType::Supertrend(10, FixedPoint::new(3.0)))
+            .unwrap()
+            .as_supertrend()
+            .unwrap();
+
+        assert_eq!(Some(last_supertrend), Supertrend::calculate(&ts.candles));
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries},
+    utils::math::{sma, sma_rolling},
+};
+use anyhow::{anyhow, Result};
+
+/// # Volume Simple Moving Average
+///
+/// Average volume of the n most recent candles. The relative volume is the
+/// ratio of the volume of the last candle to the average, a relative volume
+/// above 1 indicates higher than usual activity.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct VolumeSMA {
+    pub value: f64,
+    pub relative_volume: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for VolumeSMA {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let indicator_type = IndicatorType::VolumeSMA(len);
+
+        for i in 0..ts.candles.len() {
+            let end = i + 1;
+            let volume_sma = if end < len {
+                None
+            } else {
+                Self::calculate_args(&ts.candles[end - len..end], &args)
+            };
+
+            ts.set_indicator(i, indicator_type, Indicator::VolumeSMA(volume_sma));
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let end = ts.candles.len();
+        let indicator_type = IndicatorType::VolumeSMA(len);
+
+        if end == 0 {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_volume_sma());
+
+        let new_volume_sma = match prev {
+            Some(prev) if end > len => {
+                let volume_in = ts.candles[end - 1].volume;
+                let volume_out = ts.candles[end - 1 - len].volume;
+                let value = sma_rolling(volume_in, volume_out, prev.value, len as f64);
+
+                (value > 0.0).then_some(VolumeSMA {
+                    value,
+                    relative_volume: volume_in / value,
+                    len,
+                })
+            }
+            // Not enough candles results in None
+            _ => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::VolumeSMA(new_volume_sma))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for VolumeSMA {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::LengthArg(20)
+    }
+
+    /// Segment should be the same length as the volume SMA wanted. Returns
+    /// None if there is no volume in the segment.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let volumes: Vec<f64> = segment.iter().map(|candle| candle.volume).collect();
+        let value = sma(&volumes);
+
+        if segment.is_empty() || value == 0.0 {
+            return None;
+        }
+
+        Some(VolumeSMA {
+            value,
+            relative_volume: segment.last()?.volume / value,
+            len: segment.len(),
+        })
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.len_opt()?;
+        let candle_len = segment.len();
+
+        if candle_len < len {
+            return None;
+        }
+
+        Self::calculate(&segment[candle_len - len..candle_len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, volume_sma::VolumeSMA,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+    };
+
+    #[test]
+    fn volume_sma_calculate() {
+        let mut candles = Candle::dummy_data(4, "positive", 100.0);
+        candles[3].volume = 3000.0;
+        let volume_sma = VolumeSMA::calculate(&candles).unwrap();
+        assert_eq!(volume_sma.value, 1500.0);
+        assert_eq!(volume_sma.relative_volume, 2.0);
+    }
+
+    #[test]
+    fn volume_sma_calculate_args() {
+        let mut candles = Candle::dummy_data(4, "positive", 100.0);
+        candles[0].volume = 3000.0;
+        let args = IndicatorArgs::LengthArg(3);
+        let volume_sma = VolumeSMA::calculate_args(&candles, &args).unwrap();
+        assert_eq!(volume_sma.value, 1000.0);
+        assert_eq!(volume_sma.relative_volume, 1.0);
+    }
+
+    #[test]
+    fn volume_sma_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let volume_sma = VolumeSMA::calculate(&candles);
+        assert!(volume_sma.is_none());
+    }
+
+    #[test]
+    fn volume_sma_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = VolumeSMA::default_args();
+        let volume_sma = VolumeSMA::calculate_args(&candles, &args);
+        assert!(volume_sma.is_none());
+    }
+
+    #[test]
+    fn volume_sma_populate_candles() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let _ = VolumeSMA::populate_candles_args(&mut ts, IndicatorArgs::LengthArg(8));
+
+        for i in 0..ts.candles.len() {
+            let volume_sma = ts
+                .indicator(i, &IndicatorType::VolumeSMA(8))
+                .unwrap()
+                .as_volume_sma();
+            if i < 7 {
+                assert!(volume_sma.is_none());
+            } else {
+                assert_eq!(volume_sma.unwrap().value, 1000.0);
+            }
+        }
+    }
+
+    #[test]
+    fn volume_sma_populate_last_candle() {
+        let mut candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut candle = candles.pop().unwrap();
+        candle.volume = 1800.0;
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let _ = VolumeSMA::populate_candles_args(&mut ts, IndicatorArgs::LengthArg(8));
+        let _ = ts.add_candle(&candle);
+
+        let last_volume_sma = ts
+            .view()
+            .last_indicator(&IndicatorType::VolumeSMA(8))
+            .unwrap()
+            .as_volume_sma()
+            .unwrap();
+
+        assert_eq!(last_volume_sma.value, 1100.0);
+        assert_eq!(last_volume_sma.relative_volume, 1800.0 / 1100.0);
+    }
+}
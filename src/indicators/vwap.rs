@@ -0,0 +1,276 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+        is_indicator::IsIndicator, populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, timeseries::TimeSeries, vwap_anchor::VWAPAnchor},
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+
+/// # Anchored Volume Weighted Average Price (VWAP)
+///
+/// Average typical price ((high + low + close) / 3) weighted by volume,
+/// accumulated from the start of the current anchor period. The volume
+/// weighted standard deviation is kept to allow for bands around the VWAP.
+/// The running sums are kept to allow rolling calculations on new candles.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct VWAP {
+    pub value: f64,
+    pub std_dev: f64,
+    pub anchor_start: DateTime<Utc>,
+    pub cum_volume: f64,
+    pub cum_pv: f64,
+    pub cum_p2v: f64,
+}
+
+impl PopulatesCandles for VWAP {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let anchor = args.vwap_res()?;
+        let indicator_type = IndicatorType::VWAP(anchor);
+        let mut prev: Option<VWAP> = None;
+
+        for i in 0..ts.candles.len() {
+            let vwap = Self::next(prev, &ts.candles[i], anchor);
+
+            ts.set_indicator(i, indicator_type, Indicator::VWAP(vwap));
+            prev = vwap;
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let anchor = args.vwap_res()?;
+        let indicator_type = IndicatorType::VWAP(anchor);
+        let ctx_err = "Failed to get last candle";
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        let prev = Indicator::get_second_last(ts, &indicator_type)
+            .and_then(|indicator| indicator.as_vwap());
+
+        let new_vwap = match prev {
+            Some(_) => Self::next(prev, ts.candles.last().context(ctx_err)?, anchor),
+            // No previous VWAP, calculate from the start of the anchor period
+            None => Self::calculate_args(&ts.candles, &args),
+        };
+
+        ts.set_last_indicator(indicator_type, Indicator::VWAP(new_vwap))?;
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for VWAP {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::VWAPArgs(VWAPAnchor::Daily)
+    }
+
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Self::calculate_args(segment, &Self::default_args())
+    }
+
+    /// Returns the VWAP of the last candle, only candles in the same anchor
+    /// period as the last candle are used.
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let anchor = args.vwap_opt()?;
+        let anchor_start = anchor.period_start(segment.last()?.timestamp);
+        let start = segment
+            .iter()
+            .rposition(|candle| candle.timestamp < anchor_start)
+            .map_or(0, |i| i + 1);
+
+        segment[start..]
+            .iter()
+            .fold(None, |prev, candle| Self::next(prev, candle, anchor))
+    }
+}
+
+impl VWAP {
+    /// Returns the upper and lower bands the given number of standard
+    /// deviations away from the VWAP.
+    #[allow(dead_code)]
+    pub fn bands(&self, n_std_dev: f64) -> (f64, f64) {
+        (
+            self.value + n_std_dev * self.std_dev,
+            self.value - n_std_dev * self.std_dev,
+        )
+    }
+
+    /// Returns the VWAP including the new candle, the sums are reset if the
+    /// candle starts a new anchor period. None is returned until there is
+    /// volume in the anchor period.
+    fn next(prev: Option<Self>, candle: &Candle, anchor: VWAPAnchor) -> Option<Self> {
+        let anchor_start = anchor.period_start(candle.timestamp);
+        let price = (candle.high + candle.low + candle.close) / 3.0;
+
+        let (cum_volume, cum_pv, cum_p2v) = match prev {
+            Some(prev) if prev.anchor_start == anchor_start => {
+                (prev.cum_volume, prev.cum_pv, prev.cum_p2v)
+            }
+            _ => (0.0, 0.0, 0.0),
+        };
+
+        let cum_volume = cum_volume + candle.volume;
+        let cum_pv = cum_pv + price * candle.volume;
+        let cum_p2v = cum_p2v + price * price * candle.volume;
+
+        if cum_volume == 0.0 {
+            return None;
+        }
+
+        let value = cum_pv / cum_volume;
+        let variance = (cum_p2v / cum_volume - value * value).max(0.0);
+
+        Some(VWAP {
+            value,
+            std_dev: variance.sqrt(),
+            anchor_start,
+            cum_volume,
+            cum_pv,
+            cum_p2v,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, vwap::VWAP,
+        },
+        models::{
+            candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder,
+            vwap_anchor::VWAPAnchor,
+        },
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Hourly candles starting at 22:00, the third candle starts a new day.
+    fn hourly_candles() -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 22, 0, 0).unwrap();
+
+        [(10.0, 1.0), (20.0, 3.0), (30.0, 1.0), (40.0, 1.0)]
+            .iter()
+            .enumerate()
+            .map(|(i, (price, volume))| {
+                let timestamp = start + Duration::hours(i as i64);
+                Candle::new(timestamp, *price, *price, *price, *price, *volume)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vwap_calculate() {
+        let candles = hourly_candles();
+        let vwap = VWAP::calculate(&candles[..2]).unwrap();
+        assert_eq!(vwap.value, 17.5);
+
+        let (upper, lower) = vwap.bands(2.0);
+        let std_dev = (0.75f64 * 0.25 * 100.0).sqrt();
+        assert_eq!(vwap.std_dev, std_dev);
+        assert_eq!(upper, 17.5 + 2.0 * std_dev);
+        assert_eq!(lower, 17.5 - 2.0 * std_dev);
+    }
+
+    #[test]
+    fn vwap_calculate_anchor_reset() {
+        let candles = hourly_candles();
+        let vwap = VWAP::calculate(&candles).unwrap();
+        assert_eq!(vwap.value, 35.0);
+
+        let args = IndicatorArgs::VWAPArgs(VWAPAnchor::Weekly);
+        let vwap = VWAP::calculate_args(&candles, &args).unwrap();
+        assert_eq!(vwap.value, 140.0 / 6.0);
+    }
+
+    #[test]
+    fn vwap_no_volume() {
+        let mut candles = hourly_candles();
+        candles[0].volume = 0.0;
+        let vwap = VWAP::calculate(&candles[..1]);
+        assert!(vwap.is_none());
+    }
+
+    #[test]
+    fn vwap_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let vwap = VWAP::calculate(&candles);
+        assert!(vwap.is_none());
+    }
+
+    #[test]
+    fn vwap_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = VWAP::default_args();
+        let vwap = VWAP::calculate_args(&candles, &args);
+        assert!(vwap.is_none());
+    }
+
+    #[test]
+    fn vwap_populate_candles() {
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(hourly_candles())
+            .build();
+
+        let _ = VWAP::populate_candles(&mut ts);
+
+        let indicator_type = IndicatorType::VWAP(VWAPAnchor::Daily);
+        let values: Vec<f64> = (0..ts.candles.len())
+            .map(|i| {
+                ts.indicator(i, &indicator_type)
+                    .unwrap()
+                    .as_vwap()
+                    .unwrap()
+                    .value
+            })
+            .collect();
+
+        assert_eq!(values, vec![10.0, 17.5, 30.0, 35.0]);
+    }
+
+    #[test]
+    fn vwap_populate_last_candle() {
+        let mut candles = hourly_candles();
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Hour1)
+            .candles(candles)
+            .build();
+        let _ = VWAP::populate_candles(&mut ts);
+        let _ = ts.add_candle(&candle);
+
+        let last_vwap = ts
+            .view()
+            .last_indicator(&IndicatorType::VWAP(VWAPAnchor::Daily))
+            .unwrap()
+            .as_vwap()
+            .unwrap();
+
+        assert_eq!(last_vwap.value, 35.0);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    indicators::{
+        indicator::Indicator, indicator_args::IndicatorArgs, indicator_state::IndicatorState,
+        indicator_type::IndicatorType, is_indicator::IsIndicator,
+        populates_candles::PopulatesCandles,
+    },
+    models::{candle::Candle, candle_window::CandleView, timeseries::TimeSeries},
+    utils::math::vwma,
+};
+use anyhow::{anyhow, Result};
+
+/// # Volume Weighted Moving Average (VWMA)
+///
+/// Average of the n most recent closes weighted by the volume of each candle.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct VWMA {
+    pub value: f64,
+    pub len: usize,
+}
+
+impl PopulatesCandles for VWMA {
+    fn populate_candles(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_candles_args(ts, Self::default_args())
+    }
+
+    fn populate_candles_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+        let indicator_type = IndicatorType::VWMA(len);
+        ts.indicator_states.remove(&indicator_type);
+
+        for end in 1..=ts.candles.len() {
+            Self::populate_candle(ts, end, len);
+        }
+
+        ts.indicators.insert(indicator_type);
+
+        Ok(())
+    }
+
+    fn populate_last_candle(ts: &mut TimeSeries) -> Result<()> {
+        Self::populate_last_candle_args(ts, Self::default_args())
+    }
+
+    fn populate_last_candle_args(ts: &mut TimeSeries, args: IndicatorArgs) -> Result<()> {
+        let len = args.len_res()?;
+
+        if ts.candles.is_empty() {
+            return Err(anyhow!("No candle to populate"));
+        }
+
+        Self::populate_candle(ts, ts.candles.len(), len);
+
+        Ok(())
+    }
+}
+
+impl IsIndicator for VWMA {
+    fn default_args() -> IndicatorArgs {
+        IndicatorArgs::LengthArg(20)
+    }
+
+    /// Segment should be the same length as the VWMA wanted. Returns None if
+    /// there is no volume in the segment.
+    fn calculate(segment: &[Candle]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = segment.len();
+        let values: Vec<(f64, f64)> = segment.iter().map(|c| (c.close, c.volume)).collect();
+
+        if values.iter().map(|(_, volume)| volume).sum::<f64>() == 0.0 {
+            return None;
+        }
+
+        Some(VWMA {
+            len,
+            value: vwma(&values),
+        })
+    }
+
+    fn calculate_args(segment: &[Candle], args: &IndicatorArgs) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let len = args.len_opt()?;
+        let candle_len = segment.len();
+
+        if candle_len < len {
+            return None;
+        }
+
+        Self::calculate(&segment[candle_len - len..candle_len])
+    }
+}
+
+impl VWMA {
+    /// Populates the candle at `end - 1` from the running sums of the
+    /// volume weighted closes and of the volumes kept in the TimeSeries. Not
+    /// enough candles or no volume results in None.
+    fn populate_candle(ts: &mut TimeSeries, end: usize, len: usize) {
+        let indicator_type = IndicatorType::VWMA(len);
+        let new_state = || IndicatorState::new().with_sums(&[len, len]);
+        let step = |state: &mut IndicatorState, view: CandleView<'_>, i: usize| {
+            state.sums[0].push(view[i].close * view[i].volume);
+            state.sums[1].push(view[i].volume);
+        };
+
+        let mut state = IndicatorState::take(ts, &indicator_type, end, len, new_state, step);
+        step(&mut state, ts.view(), end - 1);
+
+        let (weighted, volumes) = (&state.sums[0], &state.sums[1]);
+        let vwma = (volumes.is_full() && !volumes.is_zero()).then(|| VWMA {
+            value: weighted.mean() / volumes.mean(),
+            len,
+        });
+
+        state.put(ts, indicator_type, end);
+        ts.set_indicator(end - 1, indicator_type, Indicator::VWMA(vwma));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_args::IndicatorArgs, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, populates_candles::PopulatesCandles, vwma::VWMA,
+        },
+        models::{candle::Candle, interval::Interval, timeseries_builder::TimeSeriesBuilder},
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+    use chrono::Utc;
+
+    #[test]
+    fn vwma_calculate() {
+        let candles = vec![
+            Candle::new(Utc::now(), 10.0, 10.0, 10.0, 10.0, 1.0),
+            Candle::new(Utc::now(), 20.0, 20.0, 20.0, 20.0, 3.0),
+        ];
+        let vwma = VWMA::calculate(&candles);
+        assert!(vwma.is_some());
+        let vwma = vwma.unwrap();
+        assert_eq!(vwma.value, 17.5);
+    }
+
+    #[test]
+    fn vwma_calculate_args() {
+        let candles = Candle::dummy_data(4, "positive", 100.0);
+        let args = IndicatorArgs::LengthArg(3);
+        let vwma = VWMA::calculate_args(&candles[1..4], &args);
+        assert!(vwma.is_some());
+        let vwma = vwma.unwrap();
+        assert_eq!(vwma.value, 130.0);
+    }
+
+    #[test]
+    fn vwma_no_volume() {
+        let candles = vec![Candle::new(Utc::now(), 10.0, 10.0, 10.0, 10.0, 0.0)];
+        let vwma = VWMA::calculate(&candles);
+        assert!(vwma.is_none());
+    }
+
+    #[test]
+    fn vwma_no_candles() {
+        let candles: Vec<Candle> = Vec::new();
+        let vwma = VWMA::calculate(&candles);
+        assert!(vwma.is_none());
+    }
+
+    #[test]
+    fn vwma_no_candles_args() {
+        let candles: Vec<Candle> = Vec::new();
+        let args = VWMA::default_args();
+        let vwma = VWMA::calculate_args(&candles, &args);
+        assert!(vwma.is_none());
+    }
+
+    #[test]
+    fn vwma_populate_candles() {
+        let candles = Candle::dummy_data(10, "positive", 100.0);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+
+        let args = IndicatorArgs::LengthArg(8);
+        let _ = VWMA::populate_candles_args(&mut ts, args);
+
+        let indicator_type = IndicatorType::VWMA(8);
+
+        for i in 0..ts.candles.len() {
+            let indicator = ts.indicator(i, &indicator_type).unwrap();
+            let vwma = indicator.as_vwma();
+            if i < 7 {
+                assert!(vwma.is_none());
+            } else {
+                assert!(vwma.is_some());
+            }
+        }
+
+        let last_vwma = ts
+            .view()
+            .last_indicator(&indicator_type)
+            .unwrap()
+            .as_vwma()
+            .unwrap();
+        assert_eq!(last_vwma.value, 165.0);
+    }
+
+    #[test]
+    fn vwma_populate_last_candle() {
+        let mut candles = Candle::dummy_data(10, "positive", 100.0);
+        let candle = candles.pop().unwrap();
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        let args = IndicatorArgs::LengthArg(8);
+        let _ = VWMA::populate_candles_args(&mut ts, args);
+        let _ = ts.add_candle(&candle);
+
+        let last_vwma = ts
+            .view()
+            .last_indicator(&IndicatorType::VWMA(8))
+            .unwrap()
+            .as_vwma()
+            .unwrap();
+
+        assert_eq!(last_vwma.value, 165.0);
+    }
+
+    #[test]
+    fn vwma_populate_incrementally() {
+        let candles = Candle::dyn_dummy_from_increments(PRICE_CHANGES);
+        let args = IndicatorArgs::LengthArg(20);
+        let indicator_type = IndicatorType::VWMA(20);
+
+        let mut full_ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+        VWMA::populate_candles_args(&mut full_ts, args).unwrap();
+
+        // Oldest candles are evicted while adding
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(100)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for candle in candles.iter() {
+            ts.add_candle(candle).unwrap();
+        }
+
+        let offset = candles.len() - ts.candles.len();
+
+        for i in 0..ts.candles.len() {
+            let value = ts
+                .indicator(i, &indicator_type)
+                .and_then(|indicator| indicator.as_vwma());
+            let full_value = full_ts
+                .indicator(offset + i, &indicator_type)
+                .and_then(|indicator| indicator.as_vwma());
+            assert_eq!(value, full_value);
+
+            let window = &candles[..offset + i + 1];
+            let expected = VWMA::calculate_args(window, &args);
+            assert_eq!(value.is_some(), expected.is_some());
+
+            if let (Some(value), Some(expected)) = (value, expected) {
+                assert!((value.value - expected.value).abs() < 1e-9);
+            }
+        }
+    }
+}
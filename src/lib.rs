@@ -1,4 +1,5 @@
 mod data_sources;
+mod expressions;
 mod indicators;
 mod models;
 mod notifications;
@@ -26,13 +27,14 @@ use models::{
     message_payloads::{
         ts_subscribe_payload::TSSubscribePayload, websocket_payload::WebsocketPayload,
     },
+    orientation_mode::OrientationMode,
     setups::setup_finder_builder::SetupFinderBuilder,
-    strategy_orientation::StrategyOrientation,
     timeseries::TimeSeries,
     timeseries_builder::TimeSeriesBuilder,
-    traits::trading_strategy::TradingStrategy,
+    traits::{has_min_length::HasMinLength, trading_strategy::TradingStrategy},
 };
 use run_configs::{always_true, manual_candles, multiple_strategies, ws_only};
+use std::env;
 use strategy_testing::strategy_tester::StrategyTester;
 use tokio::time::{sleep, Duration};
 use trading_strategies::{
@@ -62,9 +64,7 @@ pub async fn run_actual_strategy() -> Result<()> {
         .get_historical_data(DEFAULT_SYMBOL, &interval, strategy.min_length() + 300, &net)
         .await?;
 
-    for indicator_type in strategy.required_indicators() {
-        ts.add_indicator(indicator_type)?;
-    }
+    ts.add_indicators(&strategy.required_indicators())?;
 
     let ts_addr = ts.start();
 
@@ -86,7 +86,7 @@ pub async fn run_actual_strategy() -> Result<()> {
 
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
-    wsclient.add_observer(ts_addr);
+    wsclient.add_observer(ts_addr.recipient());
     wsclient.start();
 
     // Run program indefinately
@@ -165,7 +165,7 @@ pub async fn run_single_indicator() -> Result<()> {
     let mut client = WebsocketClient::new(source, interval, net);
     let addr = ts.start();
 
-    client.add_observer(addr);
+    client.add_observer(addr.recipient());
     client.start();
 
     // TODO: Enable check for whether new setups have arisen from updated indicators
@@ -188,9 +188,7 @@ pub async fn run_strategy() -> Result<()> {
     // ts.save_to_local(&source).await?;
     // let ts = source.load_local_data(symbol, &interval).await?;
 
-    for indicator_type in strategy.required_indicators() {
-        ts.add_indicator(indicator_type)?;
-    }
+    ts.add_indicators(&strategy.required_indicators())?;
 
     let ts_addr = ts.start();
 
@@ -206,7 +204,7 @@ pub async fn run_strategy() -> Result<()> {
 
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
-    wsclient.add_observer(ts_addr);
+    wsclient.add_observer(ts_addr.recipient());
     wsclient.start();
 
     loop {
@@ -215,69 +213,43 @@ pub async fn run_strategy() -> Result<()> {
 }
 
 pub async fn run_double_strategies() -> Result<()> {
-    let short_strategy: Box<dyn TradingStrategy> = Box::new(RsiBasic::new_args(
-        14,
-        45.0,
-        55.0,
-        StrategyOrientation::Short,
-    ));
-    let long_strategy: Box<dyn TradingStrategy> = Box::new(RsiBasic::new_args(
-        14,
-        45.0,
-        55.0,
-        StrategyOrientation::Long,
-    ));
+    // A single instance trading both directions, each setup carries its own
+    // orientation
+    let strategy: Box<dyn TradingStrategy> =
+        Box::new(RsiBasic::new_args(14, 45.0, 55.0, OrientationMode::Both));
     let interval = Interval::Minute1;
     let source = DataSource::Bybit;
     let net = NetVersion::Mainnet;
 
     // Initialize timeseries and indicators
     let mut ts = source
-        .get_historical_data(
-            DEFAULT_SYMBOL,
-            &interval,
-            long_strategy.min_length() + 300,
-            &net,
-        )
+        .get_historical_data(DEFAULT_SYMBOL, &interval, strategy.min_length() + 300, &net)
         .await?;
     // ts.save_to_local(&source).await?;
     // let ts = source.load_local_data(symbol, &interval).await?;
 
-    for indicator_type in long_strategy.required_indicators() {
-        ts.add_indicator(indicator_type)?;
-    }
+    ts.add_indicators(&strategy.required_indicators())?;
 
     let ts_addr = ts.start();
 
     // Create setup finder and subscribe to timeseries
-    let long_setup_finder = SetupFinderBuilder::new()
-        .strategy(long_strategy)
-        .ts_addr(ts_addr.clone())
-        .notifications_enabled(true)
-        .build()?;
-    let short_setup_finder = SetupFinderBuilder::new()
-        .strategy(short_strategy)
+    let setup_finder = SetupFinderBuilder::new()
+        .strategy(strategy)
         .ts_addr(ts_addr.clone())
         .notifications_enabled(true)
         .build()?;
 
-    let long_sf_addr = long_setup_finder.start();
-    let short_sf_addr = short_setup_finder.start();
-
-    let long_payload = TSSubscribePayload {
-        observer: long_sf_addr.recipient(),
-    };
+    let sf_addr = setup_finder.start();
 
-    let short_payload = TSSubscribePayload {
-        observer: short_sf_addr.recipient(),
+    let payload = TSSubscribePayload {
+        observer: sf_addr.recipient(),
     };
 
-    ts_addr.do_send(long_payload);
-    ts_addr.do_send(short_payload);
+    ts_addr.do_send(payload);
 
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
-    wsclient.add_observer(ts_addr);
+    wsclient.add_observer(ts_addr.recipient());
     wsclient.start();
 
     loop {
@@ -333,6 +305,7 @@ pub async fn run_setup_finder() -> Result<()> {
             ok: true,
             message: None,
             candle: Some(candle),
+            closed: true,
         };
 
         ts.do_send(payload);
@@ -367,6 +340,7 @@ pub async fn run_manual_setups() -> Result<()> {
             ok: true,
             message: None,
             candle: Some(candle),
+            closed: true,
         };
 
         ts.do_send(payload);
@@ -396,7 +370,35 @@ pub async fn run_strategy_tester() -> Result<()> {
         indicator.populate_candles(&mut ts)?;
     }
 
-    let result = StrategyTester::test_strategy(&mut strategy, &ts.candles[300..])?;
+    let mut tester = StrategyTester::new();
+
+    // Fetch the same period for additional timeframes of the strategy
+    for requirement in strategy.timeframe_requirements() {
+        let n = 20000 * interval.to_millis() / requirement.interval.to_millis();
+        let n = n as usize + requirement.min_length();
+
+        println!("Fetching {} Timeseries data.", requirement.interval);
+        let mut tf_ts = source
+            .get_historical_data(DEFAULT_SYMBOL, &requirement.interval, n, &net)
+            .await?;
+        tf_ts.add_indicators(&requirement.indicators)?;
+
+        tester = tester.timeframe(requirement.interval, tf_ts.view().to_window());
+    }
+
+    // Optionally force-close setups after a maximum number of bars
+
+    let max_bars: Option<usize> = env::var("STRATEGY_TESTER_MAX_BARS")
+        .ok()
+        .map(|max_bars| max_bars.parse())
+        .transpose()?;
+
+    if let Some(max_bars) = max_bars {
+        tester = tester.max_bars(max_bars);
+    }
+
+    let candles = ts.view();
+    let result = tester.test_strategy(&mut strategy, candles.window(300, candles.len()))?;
 
     println!("{:#?}", result);
 
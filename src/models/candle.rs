@@ -1,12 +1,7 @@
-use crate::{
-    indicators::{indicator::Indicator, indicator_type::IndicatorType},
-    models::interval::Interval,
-};
-use anyhow::{Context, Result};
+use crate::models::interval::Interval;
 use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
@@ -16,9 +11,6 @@ pub struct Candle {
     pub high: f64,
     pub low: f64,
     pub volume: f64,
-
-    #[serde(skip_serializing, skip_deserializing)]
-    pub indicators: HashMap<IndicatorType, Indicator>,
 }
 
 impl Candle {
@@ -37,7 +29,6 @@ impl Candle {
             high,
             low,
             volume,
-            indicators: HashMap::new(),
         }
     }
 
@@ -52,7 +43,6 @@ impl Candle {
             high: val,
             low: val,
             volume: 1000.0,
-            indicators: HashMap::new(),
         }
     }
 
@@ -65,7 +55,6 @@ impl Candle {
             high: val,
             low: val,
             volume,
-            indicators: HashMap::new(),
         }
     }
 
@@ -84,7 +73,6 @@ impl Candle {
                     high: val,
                     low: val,
                     volume: 1000.0,
-                    indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -106,7 +94,6 @@ impl Candle {
                     high: val,
                     low: val,
                     volume: 1000.0,
-                    indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -134,13 +121,12 @@ impl Candle {
         }
 
         Candle {
-            timestamp: candle.timestamp + interval.to_duration(),
+            timestamp: interval.next_start(candle.timestamp),
             open,
             close,
             high,
             low,
             volume: rng.gen_range(200..1500) as f64,
-            indicators: HashMap::new(),
         }
     }
 
@@ -174,7 +160,6 @@ impl Candle {
                     high,
                     low,
                     volume: rng.gen_range(200..1500) as f64,
-                    indicators: HashMap::new(),
                 }
             })
             .collect()
@@ -207,16 +192,8 @@ impl Candle {
                     high: val,
                     low: val,
                     volume: 1000.0,
-                    indicators: HashMap::new(),
                 }
             })
             .collect()
     }
-
-    pub fn clone_indicator(&self, key: &IndicatorType) -> Result<Indicator> {
-        self.indicators
-            .get(key)
-            .context(format!("Unable to find indicator with type: {:#?}", key))
-            .cloned()
-    }
 }
@@ -0,0 +1,204 @@
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        indicator_column::IndicatorColumn,
+        indicator_columns::{IndicatorColumns, IndicatorRow},
+        indicator_type::IndicatorType,
+    },
+    models::candle::Candle,
+};
+use std::ops::Deref;
+
+/// # CandleView
+///
+/// Borrowed window of candles along with the columns of their indicators, as
+/// given to strategies and resolution strategies. Dereferences to the OHLCV
+/// of the candles, indicators are looked up by candle index. Narrowing the
+/// window only adjusts the bounds, nothing is copied.
+#[derive(Debug, Clone, Copy)]
+pub struct CandleView<'a> {
+    candles: &'a [Candle],
+    columns: &'a IndicatorColumns,
+    /// Index of the first candle of the view in the columns
+    offset: usize,
+}
+
+impl<'a> CandleView<'a> {
+    /// Creates a view of all candles, the columns should be aligned to them.
+    pub fn new(candles: &'a [Candle], columns: &'a IndicatorColumns) -> Self {
+        Self {
+            candles,
+            columns,
+            offset: 0,
+        }
+    }
+
+    pub fn candles(&self) -> &'a [Candle] {
+        self.candles
+    }
+
+    /// Returns the indicator of the candle at index `i`.
+    pub fn indicator(&self, i: usize, indicator_type: &IndicatorType) -> Option<&'a Indicator> {
+        if i >= self.candles.len() {
+            return None;
+        }
+
+        self.columns.get(indicator_type, self.offset + i)
+    }
+
+    /// Returns the indicator of the last candle.
+    pub fn last_indicator(&self, indicator_type: &IndicatorType) -> Option<&'a Indicator> {
+        self.indicator(self.candles.len().checked_sub(1)?, indicator_type)
+    }
+
+    /// Returns a borrowed view of the given indicator across the candles.
+    pub fn column(&self, indicator_type: &IndicatorType) -> IndicatorColumn<'a> {
+        let len = self.candles.len();
+        let values = self
+            .columns
+            .column(indicator_type)
+            .map_or(&[][..], |values| &values[self.offset..self.offset + len]);
+
+        IndicatorColumn::new(values, len)
+    }
+
+    /// Returns the indicators of the candle at index `i`.
+    pub fn row(&self, i: usize) -> IndicatorRow {
+        if i >= self.candles.len() {
+            return IndicatorRow::new();
+        }
+
+        self.columns.row(self.offset + i)
+    }
+
+    /// Narrows the view to the candles in `start..end`.
+    pub fn window(&self, start: usize, end: usize) -> Self {
+        Self {
+            candles: &self.candles[start..end],
+            columns: self.columns,
+            offset: self.offset + start,
+        }
+    }
+
+    /// Narrows the view to the last `n` candles.
+    pub fn tail(&self, n: usize) -> Self {
+        let len = self.candles.len();
+        self.window(len.saturating_sub(n), len)
+    }
+
+    /// Returns an owned copy of the candles in view.
+    pub fn to_window(self) -> CandleWindow {
+        let len = self.candles.len();
+
+        CandleWindow {
+            candles: self.candles.to_vec(),
+            columns: self.columns.slice(self.offset, self.offset + len),
+        }
+    }
+}
+
+impl<'a> Deref for CandleView<'a> {
+    type Target = [Candle];
+
+    fn deref(&self) -> &[Candle] {
+        self.candles
+    }
+}
+
+/// # CandleWindow
+///
+/// Owned window of candles along with the columns of their indicators, e.g.
+/// the latest candles sent by a TimeSeries. Borrow it through `view`.
+#[derive(Debug, Clone)]
+pub struct CandleWindow {
+    pub candles: Vec<Candle>,
+    pub columns: IndicatorColumns,
+}
+
+impl CandleWindow {
+    /// Creates a window of candles without indicators.
+    pub fn new(candles: Vec<Candle>) -> Self {
+        let len = candles.len();
+
+        Self {
+            candles,
+            columns: IndicatorColumns::new(usize::MAX, len),
+        }
+    }
+
+    pub fn view(&self) -> CandleView<'_> {
+        CandleView::new(&self.candles, &self.columns)
+    }
+
+    /// Appends a candle with the given indicators.
+    pub fn push(&mut self, candle: Candle, row: &IndicatorRow) {
+        self.candles.push(candle);
+        self.columns.push_row_with(row);
+    }
+
+    /// Sets the indicator of the candle at index `i`.
+    #[allow(dead_code)]
+    pub fn set_indicator(&mut self, i: usize, indicator_type: IndicatorType, indicator: Indicator) {
+        self.columns.set(indicator_type, i, indicator);
+    }
+}
+
+impl From<Vec<Candle>> for CandleWindow {
+    fn from(candles: Vec<Candle>) -> Self {
+        Self::new(candles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CandleWindow;
+    use crate::{
+        indicators::{atr::ATR, indicator::Indicator, indicator_type::IndicatorType},
+        models::candle::Candle,
+    };
+
+    fn atr(value: f64) -> Indicator {
+        Indicator::ATR(Some(ATR { len: 14, value }))
+    }
+
+    #[test]
+    fn candle_view_windows() {
+        let indicator_type = IndicatorType::ATR(14);
+        let mut window = CandleWindow::new(Candle::dummy_from_arr(&[1.0, 2.0, 3.0, 4.0]));
+
+        for i in 1..4 {
+            window.set_indicator(i, indicator_type, atr(i as f64));
+        }
+
+        let view = window.view();
+        assert!(view.indicator(0, &indicator_type).is_none());
+        assert!(view.indicator(4, &indicator_type).is_none());
+
+        let tail = view.tail(2);
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].close, 3.0);
+        assert_eq!(
+            tail.last_indicator(&indicator_type)
+                .and_then(|i| i.as_atr())
+                .map(|a| a.value),
+            Some(3.0)
+        );
+        assert_eq!(tail.column(&indicator_type).len(), 2);
+        assert!(tail.column(&IndicatorType::ATR(7)).last().is_none());
+
+        let copy = view.window(1, 3).to_window();
+        assert_eq!(copy.candles.len(), 2);
+        assert_eq!(copy.view().row(0).len(), 1);
+
+        let row = view.row(3);
+        window.push(Candle::dummy_from_arr(&[5.0]).remove(0), &row);
+        assert_eq!(
+            window
+                .view()
+                .last_indicator(&indicator_type)
+                .and_then(|i| i.as_atr())
+                .map(|a| a.value),
+            Some(3.0)
+        );
+    }
+}
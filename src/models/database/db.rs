@@ -1,13 +1,22 @@
-use crate::models::message_payloads::{
-    create_db_trade_payload::CreateDBTradePayload, finish_db_trade_payload::FinishDBTradePayload,
+use crate::models::{
+    database::pending_writes::{PendingWrites, TradeWrite},
+    message_payloads::{
+        add_db_trade_exit_payload::AddDBTradeExitPayload,
+        create_db_trade_payload::CreateDBTradePayload,
+        finish_db_trade_payload::FinishDBTradePayload,
+    },
 };
 use actix::{Actor, AsyncContext, Context, Handler, WrapFuture};
 use anyhow::Result;
 use sqlx::{postgres::PgPoolOptions, query, Pool, Postgres};
 use std::env;
 
+/// Writes trades to the database. Writes are executed one at a time in the
+/// order they arrive, see `PendingWrites` for writes arriving before the
+/// trade they reference.
 pub struct DB {
     pool: Pool<Postgres>,
+    pending_writes: PendingWrites,
 }
 
 impl DB {
@@ -18,7 +27,10 @@ impl DB {
             .connect(&db_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            pending_writes: PendingWrites::default(),
+        })
     }
 }
 
@@ -26,18 +38,74 @@ impl Actor for DB {
     type Context = Context<Self>;
 }
 
+async fn write(pool: &Pool<Postgres>, write: TradeWrite) {
+    match write {
+        TradeWrite::Exit(exit) => {
+            let q = r#"
+            insert into trade_exits (
+                id, trade_id, exited_at, exit_price, quantity, exit_fee, kind
+            ) values (
+                $1, $2, $3, $4, $5, $6, $7
+            )
+            "#;
+
+            let res = query(q)
+                .bind(exit.id)
+                .bind(exit.trade_id)
+                .bind(exit.exited_at)
+                .bind(exit.exit_price)
+                .bind(exit.quantity)
+                .bind(exit.exit_fee)
+                .bind(&exit.kind)
+                .execute(pool)
+                .await;
+
+            match res {
+                Ok(_) => println!("DBTradeExit sucessfully inserted!"),
+                Err(e) => println!("DBTradeExit failed to insert with error: {:#?}", e),
+            };
+        }
+        TradeWrite::Finish(msg) => {
+            let q = r#"
+            update trades
+            set
+                exited_at = $1,
+                bars_in_trade = $2,
+                exit_price = $3
+            where
+                id = $4
+            "#;
+
+            let res = query(q)
+                .bind(&msg.exited_at)
+                .bind(&msg.bars_in_trade)
+                .bind(&msg.exit_price)
+                .bind(&msg.id)
+                .execute(pool)
+                .await;
+
+            match res {
+                Ok(_) => println!("DBTrade sucessfully updated!"),
+                Err(e) => println!("DBTrade failed to update with error: {:#?}", e),
+            };
+        }
+    }
+}
+
 impl Handler<CreateDBTradePayload> for DB {
     type Result = ();
 
     fn handle(&mut self, msg: CreateDBTradePayload, ctx: &mut Context<Self>) {
         let pool = self.pool.clone();
+        let held_back = self.pending_writes.created(msg.db_trade.id);
+
         let fut = async move {
             let db_trade = msg.db_trade;
 
             let q = r#"
             insert into trades (
-                id, symbol, interval, orientation, trading_strategy, 
-                resolution_strategy, data_source, entered_at, entry_price, 
+                id, symbol, interval, orientation, trading_strategy,
+                resolution_strategy, data_source, entered_at, entry_price,
                 quantity, dollar_value, entry_fee, comments
             ) values (
                 $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
@@ -65,9 +133,14 @@ impl Handler<CreateDBTradePayload> for DB {
                 Ok(_) => println!("DBTrade sucessfully inserted!"),
                 Err(e) => println!("DBTrade failed to insert with error: {:#?}", e),
             };
+
+            for held_back in held_back {
+                write(&pool, held_back).await;
+            }
         };
 
-        ctx.spawn(fut.into_actor(self));
+        // Waits so writes referencing the trade are executed after it
+        ctx.wait(fut.into_actor(self));
     }
 }
 
@@ -75,37 +148,27 @@ impl Handler<FinishDBTradePayload> for DB {
     type Result = ();
 
     fn handle(&mut self, msg: FinishDBTradePayload, ctx: &mut Context<Self>) -> Self::Result {
-        let id = msg.id;
-        let exited_at = msg.exited_at;
-        let bars_in_trade = msg.bars_in_trade;
-        let exit_price = msg.exit_price;
-        let pool = self.pool.clone();
+        if let Some(finish) = self.pending_writes.ready(TradeWrite::Finish(msg)) {
+            let pool = self.pool.clone();
+            let fut = async move { write(&pool, finish).await };
 
-        let q = r#"
-            update trades 
-            set 
-                exited_at = $1,
-                bars_in_trade = $2,
-                exit_price = $3
-            where 
-                id = $4
-            "#;
+            ctx.wait(fut.into_actor(self));
+        }
+    }
+}
 
-        let fut = async move {
-            let res = query(q)
-                .bind(&exited_at)
-                .bind(&bars_in_trade)
-                .bind(&exit_price)
-                .bind(&id)
-                .execute(&pool)
-                .await;
+impl Handler<AddDBTradeExitPayload> for DB {
+    type Result = ();
 
-            match res {
-                Ok(_) => println!("DBTrade sucessfully updated!"),
-                Err(e) => println!("DBTrade failed to update with error: {:#?}", e),
-            };
-        };
+    fn handle(&mut self, msg: AddDBTradeExitPayload, ctx: &mut Context<Self>) -> Self::Result {
+        if let Some(exit) = self
+            .pending_writes
+            .ready(TradeWrite::Exit(msg.db_trade_exit))
+        {
+            let pool = self.pool.clone();
+            let fut = async move { write(&pool, exit).await };
 
-        ctx.spawn(fut.into_actor(self));
+            ctx.wait(fut.into_actor(self));
+        }
     }
 }
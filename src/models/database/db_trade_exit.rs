@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Single fill closing (part of) a trade. Trades scaling out of positions
+/// have several exits, the exit price stored on the trade is their volume
+/// weighted average.
+#[derive(Debug, Clone)]
+pub struct DBTradeExit {
+    pub id: Uuid,
+    pub trade_id: Uuid,
+    pub exited_at: DateTime<Utc>,
+    pub exit_price: f64,
+    pub quantity: f64,
+    pub exit_fee: f64,
+    pub kind: String,
+}
+
+impl DBTradeExit {
+    /// Returns the volume weighted average price of the exits, None if
+    /// nothing has been exited.
+    pub fn average_price(exits: &[DBTradeExit]) -> Option<f64> {
+        let quantity: f64 = exits.iter().map(|exit| exit.quantity).sum();
+
+        if quantity <= 0.0 {
+            return None;
+        }
+
+        let value: f64 = exits
+            .iter()
+            .map(|exit| exit.exit_price * exit.quantity)
+            .sum();
+
+        Some(value / quantity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DBTradeExit;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn exit(exit_price: f64, quantity: f64) -> DBTradeExit {
+        DBTradeExit {
+            id: Uuid::new_v4(),
+            trade_id: Uuid::nil(),
+            exited_at: Utc::now(),
+            exit_price,
+            quantity,
+            exit_fee: 0.0,
+            kind: "Take-profit".to_string(),
+        }
+    }
+
+    #[test]
+    fn db_trade_exit_average_price() {
+        let exits = vec![exit(110.0, 5.0), exit(120.0, 2.5), exit(100.0, 2.5)];
+
+        assert_eq!(DBTradeExit::average_price(&exits), Some(110.0));
+        assert_eq!(DBTradeExit::average_price(&[]), None);
+    }
+}
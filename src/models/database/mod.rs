@@ -1,2 +1,4 @@
 pub mod db;
 pub mod db_trade;
+pub mod db_trade_exit;
+pub mod pending_writes;
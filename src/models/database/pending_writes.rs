@@ -0,0 +1,144 @@
+use crate::models::{
+    database::db_trade_exit::DBTradeExit,
+    message_payloads::finish_db_trade_payload::FinishDBTradePayload,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Write referencing the row of a trade.
+#[derive(Debug, Clone)]
+pub enum TradeWrite {
+    Exit(DBTradeExit),
+    Finish(FinishDBTradePayload),
+}
+
+impl TradeWrite {
+    pub fn trade_id(&self) -> Uuid {
+        match self {
+            Self::Exit(exit) => exit.trade_id,
+            Self::Finish(finish) => finish.id,
+        }
+    }
+}
+
+/// Holds back the exits and updates of trades until the trade itself has
+/// been written. Trades are only written once their entry is executed, a
+/// fast exit can arrive before that and would otherwise break the foreign
+/// key of `trade_exits` or update no row at all.
+#[derive(Debug, Default)]
+pub struct PendingWrites {
+    created: HashSet<Uuid>,
+    pending: HashMap<Uuid, Vec<TradeWrite>>,
+}
+
+impl PendingWrites {
+    /// Marks the trade as written, returns the writes held back for it in
+    /// the order they arrived.
+    pub fn created(&mut self, id: Uuid) -> Vec<TradeWrite> {
+        let writes = self.pending.remove(&id).unwrap_or_default();
+
+        if writes
+            .iter()
+            .any(|write| matches!(write, TradeWrite::Finish(_)))
+        {
+            return writes;
+        }
+
+        self.created.insert(id);
+        writes
+    }
+
+    /// Returns the write if its trade has been written, holds it back
+    /// otherwise. Finished trades are forgotten.
+    pub fn ready(&mut self, write: TradeWrite) -> Option<TradeWrite> {
+        let id = write.trade_id();
+
+        if !self.created.contains(&id) {
+            self.pending.entry(id).or_default().push(write);
+            return None;
+        }
+
+        if let TradeWrite::Finish(_) = write {
+            self.created.remove(&id);
+        }
+
+        Some(write)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PendingWrites, TradeWrite};
+    use crate::models::{
+        database::db_trade_exit::DBTradeExit,
+        message_payloads::finish_db_trade_payload::FinishDBTradePayload,
+    };
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn exit(trade_id: Uuid, quantity: f64) -> TradeWrite {
+        TradeWrite::Exit(DBTradeExit {
+            id: Uuid::new_v4(),
+            trade_id,
+            exited_at: Utc::now(),
+            exit_price: 100.0,
+            quantity,
+            exit_fee: 0.0,
+            kind: "Take-profit".to_string(),
+        })
+    }
+
+    fn finish(id: Uuid) -> TradeWrite {
+        TradeWrite::Finish(FinishDBTradePayload {
+            id,
+            exited_at: Utc::now(),
+            bars_in_trade: 1,
+            exit_price: 100.0,
+        })
+    }
+
+    fn quantities(writes: &[TradeWrite]) -> Vec<f64> {
+        writes
+            .iter()
+            .filter_map(|write| match write {
+                TradeWrite::Exit(exit) => Some(exit.quantity),
+                TradeWrite::Finish(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn partial_exit_before_entry_is_persisted() {
+        let mut writes = PendingWrites::default();
+        let id = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        writes.created(other);
+        assert!(writes.ready(exit(id, 1.0)).is_none());
+        assert!(writes.ready(exit(id, 2.0)).is_none());
+        assert!(writes.ready(exit(other, 3.0)).is_some());
+
+        assert_eq!(quantities(&writes.created(id)), vec![1.0, 2.0]);
+        assert_eq!(
+            quantities(&writes.ready(exit(id, 4.0)).into_iter().collect::<Vec<_>>()),
+            vec![4.0]
+        );
+
+        assert!(writes.ready(finish(id)).is_some());
+        assert!(writes.ready(exit(id, 5.0)).is_none());
+    }
+
+    #[test]
+    fn trade_finished_before_entry_is_persisted() {
+        let mut writes = PendingWrites::default();
+        let id = Uuid::new_v4();
+
+        assert!(writes.ready(exit(id, 1.0)).is_none());
+        assert!(writes.ready(finish(id)).is_none());
+
+        let held_back = writes.created(id);
+        assert_eq!(held_back.len(), 2);
+        assert!(matches!(held_back[1], TradeWrite::Finish(_)));
+        assert!(writes.ready(exit(id, 2.0)).is_none());
+    }
+}
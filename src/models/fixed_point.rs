@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+const SCALE: f64 = 10_000.0;
+
+/// Decimal number stored as a whole number of ten-thousandths. Floats are
+/// neither `Hash` nor `Eq`, this allows decimal parameters such as standard
+/// deviation multipliers to be part of keys like `IndicatorType`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "f64", into = "f64")]
+pub struct FixedPoint(i64);
+
+impl FixedPoint {
+    /// Rounds the value to the nearest ten-thousandth.
+    pub fn new(value: f64) -> Self {
+        Self((value * SCALE).round() as i64)
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / SCALE
+    }
+}
+
+impl From<f64> for FixedPoint {
+    fn from(value: f64) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<FixedPoint> for f64 {
+    fn from(value: FixedPoint) -> Self {
+        value.as_f64()
+    }
+}
+
+impl Display for FixedPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::fixed_point::FixedPoint;
+    use std::collections::HashSet;
+
+    #[test]
+    fn fixed_point_round_trip() {
+        assert_eq!(FixedPoint::new(2.5).as_f64(), 2.5);
+        assert_eq!(FixedPoint::new(0.1 + 0.2), FixedPoint::new(0.3));
+        assert_eq!(FixedPoint::new(1.23456).as_f64(), 1.2346);
+        assert_eq!(FixedPoint::new(-1.5).to_string(), "-1.5");
+    }
+
+    #[test]
+    fn fixed_point_as_key() {
+        let keys: HashSet<FixedPoint> = [2.0, 2.5, 2.0].into_iter().map(FixedPoint::new).collect();
+        assert_eq!(keys.len(), 2);
+    }
+}
@@ -1,9 +1,20 @@
-use chrono::Duration;
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter, Result};
+use std::{
+    fmt::{Display, Formatter, Result},
+    str::FromStr,
+};
 
+const HOUR: u32 = 60;
+const DAY: u32 = 24 * HOUR;
+const WEEK: u32 = 7 * DAY;
+
+/// Intervals are (de)serialized using their short notation, e.g. `15m`, `4h`,
+/// `3d` or `1M`, see `Interval::to_code`.
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
 pub enum Interval {
     Minute1,
     Minute5,
@@ -15,38 +26,175 @@ pub enum Interval {
     Day1,
     Day5,
     Week1,
+    Month1,
+    /// Any other fixed length interval, in minutes. Construct using
+    /// `Interval::from_minutes` so lengths matching one of the named variants
+    /// are normalized, otherwise equal intervals would not compare equal.
+    Custom(u32),
+}
+
+/// The unit an interval is expressed in, e.g. 4 hours or 3 days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalUnit {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl IntervalUnit {
+    pub fn name(&self) -> &str {
+        match self {
+            IntervalUnit::Minute => "minute",
+            IntervalUnit::Hour => "hour",
+            IntervalUnit::Day => "day",
+            IntervalUnit::Week => "week",
+            IntervalUnit::Month => "month",
+        }
+    }
+
+    fn symbol(&self) -> &str {
+        match self {
+            IntervalUnit::Minute => "m",
+            IntervalUnit::Hour => "h",
+            IntervalUnit::Day => "d",
+            IntervalUnit::Week => "w",
+            IntervalUnit::Month => "M",
+        }
+    }
 }
 
 impl Display for Interval {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match *self {
-            Interval::Minute1 => write!(f, "1 Minute"),
-            Interval::Minute5 => write!(f, "5 Minute"),
-            Interval::Minute15 => write!(f, "15 Minute"),
-            Interval::Minute30 => write!(f, "30 Minute"),
-            Interval::Hour1 => write!(f, "Hourly"),
-            Interval::Hour4 => write!(f, "4 Hour"),
-            Interval::Hour12 => write!(f, "12 Hour"),
-            Interval::Day1 => write!(f, "Daily"),
-            Interval::Day5 => write!(f, "5 Day"),
-            Interval::Week1 => write!(f, "Weekly"),
+        match self.unit_and_count() {
+            (IntervalUnit::Hour, 1) => write!(f, "Hourly"),
+            (IntervalUnit::Day, 1) => write!(f, "Daily"),
+            (IntervalUnit::Week, 1) => write!(f, "Weekly"),
+            (IntervalUnit::Month, 1) => write!(f, "Monthly"),
+            (unit, count) => {
+                let name = unit.name();
+                write!(f, "{} {}{}", count, name[..1].to_uppercase(), &name[1..])
+            }
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    /// Parses the short notation of an interval, e.g. `15m`, `2h` or `1M`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let err = || anyhow!("Invalid interval '{}'.", s);
+
+        let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(err)?;
+        let (count, unit) = s.split_at(split);
+        let count: u32 = count.parse().map_err(|_| err())?;
+
+        let minutes = match unit {
+            "m" => 1,
+            "h" => HOUR,
+            "d" => DAY,
+            "w" => WEEK,
+            "M" if count == 1 => return Ok(Interval::Month1),
+            _ => return Err(err()),
+        };
+
+        match count.checked_mul(minutes) {
+            Some(minutes) if minutes > 0 => Ok(Self::from_minutes(minutes)),
+            _ => Err(err()),
         }
     }
 }
 
+impl TryFrom<String> for Interval {
+    type Error = Error;
+
+    fn try_from(value: String) -> anyhow::Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<Interval> for String {
+    fn from(interval: Interval) -> Self {
+        interval.to_code()
+    }
+}
+
 impl Interval {
+    /// Returns the interval of the given number of minutes, using the named
+    /// variant where one exists.
+    pub fn from_minutes(minutes: u32) -> Self {
+        match minutes {
+            1 => Interval::Minute1,
+            5 => Interval::Minute5,
+            15 => Interval::Minute15,
+            30 => Interval::Minute30,
+            HOUR => Interval::Hour1,
+            240 => Interval::Hour4,
+            720 => Interval::Hour12,
+            DAY => Interval::Day1,
+            7200 => Interval::Day5,
+            WEEK => Interval::Week1,
+            _ => Interval::Custom(minutes),
+        }
+    }
+
+    /// Returns the length of the interval in minutes, None for calendar based
+    /// intervals such as months which have no fixed length.
+    pub fn minutes(&self) -> Option<u32> {
+        let minutes = match self {
+            Interval::Minute1 => 1,
+            Interval::Minute5 => 5,
+            Interval::Minute15 => 15,
+            Interval::Minute30 => 30,
+            Interval::Hour1 => HOUR,
+            Interval::Hour4 => 4 * HOUR,
+            Interval::Hour12 => 12 * HOUR,
+            Interval::Day1 => DAY,
+            Interval::Day5 => 5 * DAY,
+            Interval::Week1 => WEEK,
+            Interval::Month1 => return None,
+            Interval::Custom(minutes) => *minutes,
+        };
+
+        Some(minutes)
+    }
+
+    /// Returns the largest unit the interval can be expressed in as a whole
+    /// number, along with that number.
+    pub fn unit_and_count(&self) -> (IntervalUnit, u32) {
+        let minutes = match self.minutes() {
+            Some(minutes) => minutes,
+            None => return (IntervalUnit::Month, 1),
+        };
+
+        [
+            (IntervalUnit::Week, WEEK),
+            (IntervalUnit::Day, DAY),
+            (IntervalUnit::Hour, HOUR),
+        ]
+        .into_iter()
+        .find(|(_, length)| minutes.is_multiple_of(*length))
+        .map(|(unit, length)| (unit, minutes / length))
+        .unwrap_or((IntervalUnit::Minute, minutes))
+    }
+
+    /// Returns the short notation of the interval, e.g. `15m`, `4h` or `1M`.
+    /// This is what intervals are serialized as and can be parsed back using
+    /// `str::parse`.
+    pub fn to_code(&self) -> String {
+        let (unit, count) = self.unit_and_count();
+        format!("{}{}", count, unit.symbol())
+    }
+
+    /// Returns the length of the interval. Months vary in length, for monthly
+    /// candles this is the length of the longest month. Use `next_start` for
+    /// calendar aware arithmetic.
     pub fn to_duration(&self) -> Duration {
-        match self {
-            Interval::Minute1 => Duration::minutes(1),
-            Interval::Minute5 => Duration::minutes(5),
-            Interval::Minute15 => Duration::minutes(15),
-            Interval::Minute30 => Duration::minutes(30),
-            Interval::Hour1 => Duration::hours(1),
-            Interval::Hour4 => Duration::hours(4),
-            Interval::Hour12 => Duration::hours(12),
-            Interval::Day1 => Duration::days(1),
-            Interval::Day5 => Duration::days(5),
-            Interval::Week1 => Duration::weeks(1),
+        match self.minutes() {
+            Some(minutes) => Duration::minutes(minutes as i64),
+            None => Duration::days(31),
         }
     }
 
@@ -54,20 +202,176 @@ impl Interval {
         self.to_duration().num_milliseconds()
     }
 
+    /// Returns the opening time of the candle of this interval which contains
+    /// the given timestamp. Candles are aligned to the unix epoch except for
+    /// weekly candles which open on Mondays and monthly candles which open on
+    /// the first day of the month.
+    pub fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let minutes = match self.minutes() {
+            Some(minutes) => minutes,
+            None => {
+                return Utc
+                    .with_ymd_and_hms(timestamp.year(), timestamp.month(), 1, 0, 0, 0)
+                    .single()
+                    .expect("Expected valid bucket timestamp.")
+            }
+        };
+
+        // The unix epoch is a Thursday, offset weekly candles to Mondays
+        let offset = match minutes % WEEK {
+            0 => Duration::days(4).num_milliseconds(),
+            _ => 0,
+        };
+
+        let millis = timestamp.timestamp_millis() - offset;
+        let start = millis - millis.rem_euclid(self.to_millis()) + offset;
+
+        Utc.timestamp_millis_opt(start)
+            .single()
+            .expect("Expected valid bucket timestamp.")
+    }
+
+    /// Returns the opening time of the candle following the candle opened at
+    /// `start`, which is also the closing time of that candle.
+    pub fn next_start(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Interval::Month1 => start
+                .checked_add_months(Months::new(1))
+                .expect("Expected valid candle timestamp."),
+            _ => start + self.to_duration(),
+        }
+    }
+
+    /// Returns the number of whole candles of this interval between `from`
+    /// and `to`.
+    pub fn bars_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> i64 {
+        match self {
+            Interval::Month1 => {
+                let months = |ts: DateTime<Utc>| ts.year() as i64 * 12 + ts.month0() as i64;
+                let bars = months(to) - months(from);
+
+                // Don't count the last month if it is not yet complete
+                match from
+                    .with_year(to.year())
+                    .and_then(|f| f.with_month(to.month()))
+                {
+                    Some(anniversary) if anniversary > to => bars - 1,
+                    _ => bars,
+                }
+            }
+            _ => (to - from).num_milliseconds() / self.to_millis(),
+        }
+    }
+
+    /// Returns true if candles of this interval can be built from candles of
+    /// the `base` interval, i.e. every candle of this interval consists of a
+    /// whole number of `base` candles.
+    pub fn can_resample_from(&self, base: &Interval) -> bool {
+        match (self.minutes(), base.minutes()) {
+            (_, None) => false,
+            (None, Some(base)) => DAY.is_multiple_of(base),
+            (Some(minutes), Some(base)) => minutes > base && minutes.is_multiple_of(base),
+        }
+    }
+
+    /// Returns the largest of the given intervals this interval can be
+    /// resampled from.
+    pub fn resample_base(&self, intervals: &[Interval]) -> Option<Interval> {
+        intervals
+            .iter()
+            .filter(|base| self.can_resample_from(base))
+            .max_by_key(|base| base.to_millis())
+            .cloned()
+    }
+
     /// Returns the acceptable difference in duration to still consider candles
     /// subsequent in a timeseries.
     pub fn max_diff(&self) -> Duration {
-        match self {
-            Interval::Minute1 => Duration::seconds(1),
-            Interval::Minute5 => Duration::seconds(5),
-            Interval::Minute15 => Duration::seconds(5),
-            Interval::Minute30 => Duration::seconds(5),
-            Interval::Hour1 => Duration::minutes(1),
-            Interval::Hour4 => Duration::minutes(1),
-            Interval::Hour12 => Duration::minutes(1),
-            Interval::Day1 => Duration::hours(1),
-            Interval::Day5 => Duration::hours(1),
-            Interval::Week1 => Duration::hours(1),
+        match self.minutes() {
+            Some(1) => Duration::seconds(1),
+            Some(minutes) if minutes < HOUR => Duration::seconds(5),
+            Some(minutes) if minutes < DAY => Duration::minutes(1),
+            _ => Duration::hours(1),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::models::interval::Interval;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn interval_parse_and_format() {
+        let cases = [
+            ("1m", Interval::Minute1),
+            ("60m", Interval::Hour1),
+            ("12h", Interval::Hour12),
+            ("2h", Interval::Custom(120)),
+            ("90m", Interval::Custom(90)),
+            ("3d", Interval::Custom(3 * 24 * 60)),
+            ("7d", Interval::Week1),
+            ("2w", Interval::Custom(2 * 7 * 24 * 60)),
+            ("1M", Interval::Month1),
+        ];
+
+        for (code, interval) in cases {
+            assert_eq!(code.parse::<Interval>().unwrap(), interval);
+        }
+
+        assert_eq!(Interval::Custom(90).to_code(), "90m");
+        assert_eq!(Interval::from_minutes(3 * 24 * 60).to_code(), "3d");
+        assert_eq!(Interval::Custom(120).to_string(), "2 Hour");
+        assert_eq!(Interval::Day1.to_string(), "Daily");
+        assert_eq!(Interval::Minute15.to_string(), "15 Minute");
+
+        for code in ["", "m", "0m", "1x", "2M", "1.5h"] {
+            assert!(code.parse::<Interval>().is_err());
+        }
+    }
+
+    #[test]
+    fn interval_serde_roundtrip() {
+        let json = serde_json::to_string(&Interval::Custom(120)).unwrap();
+        assert_eq!(json, "\"2h\"");
+
+        let interval: Interval = serde_json::from_str("\"1M\"").unwrap();
+        assert_eq!(interval, Interval::Month1);
+    }
+
+    #[test]
+    fn interval_monthly_calendar() {
+        let ts = Utc.with_ymd_and_hms(2024, 2, 17, 13, 0, 0).unwrap();
+        let start = Interval::Month1.bucket_start(ts);
+
+        assert_eq!(start, Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            Interval::Month1.next_start(start),
+            Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+        );
+
+        let exit = Utc.with_ymd_and_hms(2024, 5, 16, 0, 0, 0).unwrap();
+        assert_eq!(Interval::Month1.bars_between(ts, exit), 2);
+        assert_eq!(Interval::Day1.bars_between(ts, exit), 88);
+    }
+
+    #[test]
+    fn interval_resample_base() {
+        let native = [Interval::Minute1, Interval::Hour1, Interval::Day1];
+
+        assert_eq!(
+            Interval::Custom(120).resample_base(&native),
+            Some(Interval::Hour1)
+        );
+        assert_eq!(
+            Interval::Month1.resample_base(&native),
+            Some(Interval::Day1)
+        );
+        assert_eq!(
+            Interval::Custom(90).resample_base(&native),
+            Some(Interval::Minute1)
+        );
+        assert!(!Interval::Week1.can_resample_from(&Interval::Day5));
+        assert!(!Interval::Day1.can_resample_from(&Interval::Month1));
+    }
+}
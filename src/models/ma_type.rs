@@ -1,4 +1,10 @@
+use crate::{
+    indicators::{indicator_type::IndicatorType, is_indicator::IsIndicator, sma::SMA, vwma::VWMA},
+    models::{candle_window::CandleView, timeseries::TimeSeries},
+};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 
 #[allow(dead_code)]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -7,3 +13,62 @@ pub enum MAType {
     EMA,
     VWMA,
 }
+
+impl MAType {
+    /// Returns the indicator type of the moving average with the given length.
+    pub fn indicator_type(&self, len: usize) -> IndicatorType {
+        match self {
+            MAType::SMA => IndicatorType::SMA(len),
+            MAType::EMA => IndicatorType::EMA(len),
+            MAType::VWMA => IndicatorType::VWMA(len),
+        }
+    }
+
+    /// Returns the moving average of the last `len` candles of the segment.
+    ///
+    /// EMAs are rolling and can't be calculated from the segment alone, they
+    /// are read from the indicators of the last candle and need to be
+    /// populated beforehand, see `populate_rolling`.
+    pub fn calculate(&self, segment: CandleView<'_>, len: usize) -> Option<f64> {
+        if len == 0 || segment.len() < len {
+            return None;
+        }
+
+        let segment = segment.tail(len);
+
+        match self {
+            MAType::SMA => SMA::calculate(&segment).map(|sma| sma.value),
+            MAType::EMA => segment
+                .last_indicator(&self.indicator_type(len))?
+                .as_ema()
+                .map(|ema| ema.value),
+            MAType::VWMA => VWMA::calculate(&segment).map(|vwma| vwma.value),
+        }
+    }
+
+    /// Populates the moving average if it can't be calculated from a segment
+    /// alone, which is the case for rolling averages (EMA). TimeSeries
+    /// already carrying the moving average are left untouched.
+    pub fn populate_rolling(&self, ts: &mut TimeSeries, len: usize) -> Result<()> {
+        let ma_indicator = self.indicator_type(len);
+        let populated = ts.indicators.contains(&ma_indicator) || ts.columns.contains(&ma_indicator);
+
+        if *self == MAType::EMA && !populated {
+            ts.add_indicator(ma_indicator)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for MAType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MAType::SMA => "SMA",
+            MAType::EMA => "EMA",
+            MAType::VWMA => "VWMA",
+        };
+
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{indicators::indicator_columns::IndicatorRow, models::candle::Candle};
+use actix::Message;
+
+/// Adds a candle along with preset indicator values, e.g. to play out
+/// scenarios with chosen indicators. Indicators populated by the TimeSeries
+/// replace the preset values.
+#[derive(Debug, Clone)]
+pub struct AddCandleWithIndicatorsPayload {
+    pub candle: Candle,
+    pub indicators: IndicatorRow,
+}
+
+impl Message for AddCandleWithIndicatorsPayload {
+    type Result = ();
+}
@@ -0,0 +1,11 @@
+use crate::models::database::db_trade_exit::DBTradeExit;
+use actix::Message;
+
+#[derive(Debug, Clone)]
+pub struct AddDBTradeExitPayload {
+    pub db_trade_exit: DBTradeExit,
+}
+
+impl Message for AddDBTradeExitPayload {
+    type Result = ();
+}
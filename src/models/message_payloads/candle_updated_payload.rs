@@ -0,0 +1,15 @@
+use crate::{indicators::indicator_columns::IndicatorRow, models::candle::Candle};
+use actix::Message;
+
+/// Sent by a TimeSeries when the candle following its last candle is updated
+/// while still forming. The indicators are populated as if the candle closed
+/// at its current values.
+#[derive(Debug, Clone)]
+pub struct CandleUpdatedPayload {
+    pub candle: Candle,
+    pub indicators: IndicatorRow,
+}
+
+impl Message for CandleUpdatedPayload {
+    type Result = ();
+}
@@ -1,11 +1,11 @@
-use crate::models::{candle::Candle, interval::Interval};
+use crate::models::{candle_window::CandleWindow, interval::Interval};
 use actix::Message;
 
 #[allow(dead_code)]
 pub struct LatestCandleResponse {
     pub symbol: String,
     pub interval: Interval,
-    pub candles: Vec<Candle>,
+    pub candles: CandleWindow,
 }
 
 impl Message for LatestCandleResponse {
@@ -14,7 +14,7 @@ impl Message for LatestCandleResponse {
 
 #[allow(dead_code)]
 impl LatestCandleResponse {
-    pub fn new(symbol: String, interval: Interval, candles: Vec<Candle>) -> Self {
+    pub fn new(symbol: String, interval: Interval, candles: CandleWindow) -> Self {
         LatestCandleResponse {
             symbol,
             interval,
@@ -1,5 +1,8 @@
+pub mod add_candle_with_indicators_payload;
 pub mod add_candles_payload;
+pub mod add_db_trade_exit_payload;
 pub mod candle_added_payload;
+pub mod candle_updated_payload;
 pub mod create_db_trade_payload;
 pub mod fill_historical_candles_payload;
 pub mod finish_db_trade_payload;
@@ -9,4 +12,5 @@ pub mod request_latest_candles_payload;
 pub mod stop_payload;
 pub mod triggered_payload;
 pub mod ts_subscribe_payload;
+pub mod ts_update_subscribe_payload;
 pub mod websocket_payload;
@@ -0,0 +1,11 @@
+use crate::models::message_payloads::candle_updated_payload::CandleUpdatedPayload;
+use actix::{Message, Recipient};
+
+#[derive(Debug, Clone)]
+pub struct TSUpdateSubscribePayload {
+    pub observer: Recipient<CandleUpdatedPayload>,
+}
+
+impl Message for TSUpdateSubscribePayload {
+    type Result = ();
+}
@@ -6,6 +6,9 @@ pub struct WebsocketPayload {
     pub ok: bool,
     pub message: Option<String>,
     pub candle: Option<Candle>,
+    /// False for updates of a candle which is still forming, these are only
+    /// sent when intra-candle updates are enabled on the websocket client.
+    pub closed: bool,
 }
 
 impl Message for WebsocketPayload {
@@ -1,17 +1,25 @@
 pub mod calculation_mode;
 pub mod candle;
+pub mod candle_window;
 pub mod database;
+pub mod fixed_point;
 pub mod interval;
 pub mod ma_type;
 pub mod message_payloads;
+pub mod multi_timeframe_view;
 pub mod net_version;
+pub mod orientation_mode;
+pub mod resampler;
 pub mod setups;
 pub mod strategy_orientation;
+pub mod timeframe_requirement;
 pub mod timeseries;
 pub mod timeseries_builder;
 pub mod trade;
 pub mod trade_builder;
+pub mod trading_schedule;
 pub mod traits;
+pub mod vwap_anchor;
 pub mod wallet;
 pub mod wallet_builder;
 pub mod websockets;
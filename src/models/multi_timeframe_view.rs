@@ -0,0 +1,266 @@
+use crate::models::{
+    candle::Candle,
+    candle_window::{CandleView, CandleWindow},
+    interval::Interval,
+};
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, ops::Range};
+
+/// Aligned candles of a single interval, either a view of candles owned by
+/// the caller or a window requested from a TimeSeries along with the range
+/// of its candles in view.
+#[derive(Debug, Clone)]
+enum Timeframe<'a> {
+    Borrowed(CandleView<'a>),
+    Owned(CandleWindow, Range<usize>),
+}
+
+impl<'a> Timeframe<'a> {
+    fn view(&self) -> CandleView<'_> {
+        match self {
+            Timeframe::Borrowed(view) => *view,
+            Timeframe::Owned(window, range) => window.view().window(range.start, range.end),
+        }
+    }
+}
+
+/// # MultiTimeframeView
+///
+/// Candles of the additional intervals a TradingStrategy has requested,
+/// aligned to the last candle of the strategy's main interval. Only candles
+/// that had closed when the last main candle closed are included, so a
+/// strategy never sees an unfinished higher interval candle.
+///
+/// Candles and their indicators are borrowed where possible so building a
+/// view for every window of a strategy test does not copy any candles.
+#[derive(Debug, Clone, Default)]
+pub struct MultiTimeframeView<'a> {
+    timeframes: HashMap<Interval, Timeframe<'a>>,
+}
+
+impl<'a> MultiTimeframeView<'a> {
+    pub fn new() -> Self {
+        Self {
+            timeframes: HashMap::new(),
+        }
+    }
+
+    /// Adds the `n` last candles of `candles` that had closed at the close of
+    /// `last` on the `base` interval.
+    ///
+    /// # Arguments
+    /// * `interval` - Interval of the candles being added.
+    /// * `candles` - Candles of the interval sorted from oldest to newest.
+    /// * `last` - Last candle of the main interval.
+    /// * `base` - The main interval.
+    /// * `n` - Maximum number of candles to keep.
+    pub fn insert_aligned(
+        &mut self,
+        interval: &Interval,
+        candles: CandleView<'a>,
+        last: &Candle,
+        base: &Interval,
+        n: usize,
+    ) {
+        let range = Self::aligned_range(&candles, interval, last, base, n);
+
+        self.timeframes.insert(
+            interval.clone(),
+            Timeframe::Borrowed(candles.window(range.start, range.end)),
+        );
+    }
+
+    /// Same as `insert_aligned` but takes ownership of the candles, e.g. when
+    /// they have been requested from a TimeSeries.
+    pub fn insert_aligned_owned(
+        &mut self,
+        interval: &Interval,
+        candles: CandleWindow,
+        last: &Candle,
+        base: &Interval,
+        n: usize,
+    ) {
+        let range = Self::aligned_range(&candles.candles, interval, last, base, n);
+
+        self.timeframes
+            .insert(interval.clone(), Timeframe::Owned(candles, range));
+    }
+
+    /// Returns the aligned candles of the given interval.
+    pub fn candles(&self, interval: &Interval) -> Option<CandleView<'_>> {
+        self.timeframes.get(interval).map(Timeframe::view)
+    }
+
+    /// Range of the `n` last candles which had closed at the close of `last`.
+    fn aligned_range(
+        candles: &[Candle],
+        interval: &Interval,
+        last: &Candle,
+        base: &Interval,
+        n: usize,
+    ) -> Range<usize> {
+        let cutoff = base.next_start(last.timestamp);
+        let end = Self::closed_at(candles, interval, cutoff).len();
+
+        end.saturating_sub(n)..end
+    }
+
+    /// Returns the leading part of `candles` consisting of candles which had
+    /// closed at `cutoff`. Candle timestamps mark the opening of the candle.
+    pub fn closed_at<'c>(
+        candles: &'c [Candle],
+        interval: &Interval,
+        cutoff: DateTime<Utc>,
+    ) -> &'c [Candle] {
+        let end = candles.partition_point(|candle| interval.next_start(candle.timestamp) <= cutoff);
+
+        &candles[..end]
+    }
+
+    /// Returns true if `candles` contain the latest candle of `interval` that
+    /// had closed at the close of `last` on the `base` interval. Live
+    /// TimeSeries of different intervals are updated independently, so the
+    /// higher interval may not have received its latest candle yet.
+    pub fn is_aligned(
+        candles: &[Candle],
+        interval: &Interval,
+        last: &Candle,
+        base: &Interval,
+    ) -> bool {
+        let cutoff = base.next_start(last.timestamp);
+        let boundary = interval.bucket_start(cutoff);
+
+        Self::closed_at(candles, interval, cutoff)
+            .last()
+            .is_some_and(|candle| interval.next_start(candle.timestamp) >= boundary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        candle::Candle, candle_window::CandleWindow, interval::Interval,
+        multi_timeframe_view::MultiTimeframeView,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn candles(interval: &Interval, n: usize) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        (0..n)
+            .map(|i| {
+                let timestamp = start + interval.to_duration() * i as i32;
+                Candle::from_val(timestamp, i as f64, 1000.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn view_excludes_unfinished_higher_candles() {
+        let hourly = CandleWindow::new(candles(&Interval::Hour1, 10));
+        let minutes = candles(&Interval::Minute15, 40);
+        let mut view = MultiTimeframeView::new();
+
+        // Candle opening 02:30 closes 02:45, only hours 00:00 and 01:00 are done
+        let last = &minutes[10];
+        view.insert_aligned(
+            &Interval::Hour1,
+            hourly.view(),
+            last,
+            &Interval::Minute15,
+            5,
+        );
+        let aligned = view.candles(&Interval::Hour1).unwrap();
+        assert_eq!(aligned.len(), 2);
+        assert_eq!(aligned.last().unwrap().close, 1.0);
+
+        // Candle opening 02:45 closes 03:00 which also closes the 02:00 hour
+        let last = &minutes[11];
+        view.insert_aligned(
+            &Interval::Hour1,
+            hourly.view(),
+            last,
+            &Interval::Minute15,
+            5,
+        );
+        let aligned = view.candles(&Interval::Hour1).unwrap();
+        let last_hour = aligned.last().unwrap();
+        assert_eq!(last_hour.close, 2.0);
+        assert_eq!(
+            last_hour.timestamp + Duration::hours(1),
+            last.timestamp + Duration::minutes(15)
+        );
+    }
+
+    #[test]
+    fn view_limits_number_of_candles() {
+        let hourly = CandleWindow::new(candles(&Interval::Hour1, 10));
+        let minutes = candles(&Interval::Minute15, 40);
+        let mut view = MultiTimeframeView::new();
+
+        view.insert_aligned(
+            &Interval::Hour1,
+            hourly.view(),
+            &minutes[39],
+            &Interval::Minute15,
+            3,
+        );
+        let aligned = view.candles(&Interval::Hour1).unwrap();
+        assert_eq!(aligned.len(), 3);
+        assert_eq!(aligned[0].close, 7.0);
+        assert_eq!(aligned[2].close, 9.0);
+    }
+
+    #[test]
+    fn view_owned_candles() {
+        let hourly = candles(&Interval::Hour1, 10);
+        let minutes = candles(&Interval::Minute15, 40);
+        let mut view = MultiTimeframeView::new();
+
+        // Closes 03:00, hours up to 02:00 are done
+        view.insert_aligned_owned(
+            &Interval::Hour1,
+            CandleWindow::new(hourly),
+            &minutes[11],
+            &Interval::Minute15,
+            2,
+        );
+        let aligned = view.candles(&Interval::Hour1).unwrap();
+        assert_eq!(aligned.len(), 2);
+        assert_eq!(aligned[0].close, 1.0);
+        assert_eq!(aligned[1].close, 2.0);
+    }
+
+    #[test]
+    fn view_detects_lagging_timeframe() {
+        let hourly = candles(&Interval::Hour1, 10);
+        let minutes = candles(&Interval::Minute15, 40);
+        let (hour, min) = (&Interval::Hour1, &Interval::Minute15);
+
+        // Closing 03:00 requires the 02:00 hour, closing 02:45 only 01:00
+        assert!(MultiTimeframeView::is_aligned(
+            &hourly,
+            hour,
+            &minutes[11],
+            min
+        ));
+        assert!(!MultiTimeframeView::is_aligned(
+            &hourly[..2],
+            hour,
+            &minutes[11],
+            min
+        ));
+        assert!(MultiTimeframeView::is_aligned(
+            &hourly[..2],
+            hour,
+            &minutes[10],
+            min
+        ));
+        assert!(!MultiTimeframeView::is_aligned(
+            &[],
+            hour,
+            &minutes[10],
+            min
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::models::strategy_orientation::StrategyOrientation;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result};
+
+/// Directions a TradingStrategy is allowed to trade in. Strategies trading
+/// in `Both` directions report the orientation of each setup on the Setup
+/// itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrientationMode {
+    #[serde(alias = "Long")]
+    LongOnly,
+    #[serde(alias = "Short")]
+    ShortOnly,
+    Both,
+}
+
+impl OrientationMode {
+    /// Returns whether setups with the given orientation may be triggered.
+    pub fn allows(&self, orientation: &StrategyOrientation) -> bool {
+        matches!(
+            (self, orientation),
+            (Self::Both, _)
+                | (Self::LongOnly, StrategyOrientation::Long)
+                | (Self::ShortOnly, StrategyOrientation::Short)
+        )
+    }
+}
+
+impl From<StrategyOrientation> for OrientationMode {
+    fn from(orientation: StrategyOrientation) -> Self {
+        match orientation {
+            StrategyOrientation::Long => Self::LongOnly,
+            StrategyOrientation::Short => Self::ShortOnly,
+        }
+    }
+}
+
+impl Display for OrientationMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match *self {
+            Self::LongOnly => write!(f, "Long only"),
+            Self::ShortOnly => write!(f, "Short only"),
+            Self::Both => write!(f, "Long & Short"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrientationMode;
+    use crate::models::strategy_orientation::StrategyOrientation;
+
+    #[test]
+    fn orientation_mode_allows() {
+        let long = StrategyOrientation::Long;
+        let short = StrategyOrientation::Short;
+
+        assert!(OrientationMode::LongOnly.allows(&long));
+        assert!(!OrientationMode::LongOnly.allows(&short));
+        assert!(!OrientationMode::ShortOnly.allows(&long));
+        assert!(OrientationMode::ShortOnly.allows(&short));
+        assert!(OrientationMode::Both.allows(&long));
+        assert!(OrientationMode::Both.allows(&short));
+        assert_eq!(OrientationMode::from(short), OrientationMode::ShortOnly);
+    }
+}
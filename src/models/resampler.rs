@@ -0,0 +1,296 @@
+use crate::models::{
+    candle::Candle, interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
+};
+use actix::{Actor, Context, Handler, Recipient};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+
+/// # Resampler
+///
+/// Builds candles of a higher interval from candles of a lower interval. Used
+/// both to resample historical data and as an actor that aggregates live
+/// candles from a websocket client, forwarding every completed higher interval
+/// candle to its observers.
+///
+/// Higher interval candles are aligned the same way exchanges align them, see
+/// `Interval::bucket_start`. Candles which can not be completed, e.g. the
+/// first bucket when starting mid-interval or buckets with a missing lower
+/// candle anywhere within them, are discarded rather than forwarded with
+/// incorrect values.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    from: Interval,
+    to: Interval,
+    current: Option<Candle>,
+    /// Timestamp of the lower candle expected to be added next
+    next: Option<DateTime<Utc>>,
+    observers: Vec<Recipient<WebsocketPayload>>,
+}
+
+impl Actor for Resampler {
+    type Context = Context<Self>;
+}
+
+impl Handler<WebsocketPayload> for Resampler {
+    type Result = ();
+
+    fn handle(&mut self, msg: WebsocketPayload, _ctx: &mut Context<Self>) -> Self::Result {
+        let candle = match msg.candle {
+            Some(candle) if msg.ok => candle,
+            _ => return,
+        };
+
+        let (resampled, closed) = match msg.closed {
+            true => (self.add_candle(&candle), true),
+            false => (self.preview_candle(&candle), false),
+        };
+
+        if let Some(resampled) = resampled {
+            let payload = WebsocketPayload {
+                ok: true,
+                message: None,
+                candle: Some(resampled),
+                closed,
+            };
+
+            for observer in &self.observers {
+                observer.do_send(payload.clone());
+            }
+        }
+    }
+}
+
+impl Resampler {
+    pub fn new(from: Interval, to: Interval) -> Result<Self> {
+        Self::validate(&from, &to)?;
+
+        Ok(Self {
+            from,
+            to,
+            current: None,
+            next: None,
+            observers: vec![],
+        })
+    }
+
+    pub fn add_observer(&mut self, observer: Recipient<WebsocketPayload>) {
+        self.observers.push(observer);
+    }
+
+    /// Adds a candle of the lower interval. Returns the higher interval candle
+    /// if the added candle completes it.
+    pub fn add_candle(&mut self, candle: &Candle) -> Option<Candle> {
+        let bucket = self.to.bucket_start(candle.timestamp);
+        let subsequent = self.next == Some(candle.timestamp);
+        self.next = Some(self.from.next_start(candle.timestamp));
+
+        match self.current.as_mut() {
+            Some(current) if current.timestamp == bucket && subsequent => {
+                Self::merge(current, candle)
+            }
+            _ => {
+                if self.current.is_some() {
+                    println!(
+                        "Discarding incomplete {} candle at {}",
+                        self.to,
+                        self.current.as_ref()?.timestamp
+                    );
+                }
+
+                // Only start new candles on the first lower candle of a bucket
+                self.current = (candle.timestamp == bucket).then(|| Self::open(candle, bucket));
+            }
+        }
+
+        let closes_bucket = self.from.next_start(candle.timestamp) >= self.to.next_start(bucket);
+
+        if closes_bucket {
+            self.current.take()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the forming higher interval candle as it would be if the given
+    /// forming lower interval candle closed at its current values. The
+    /// candle is not added.
+    pub fn preview_candle(&self, candle: &Candle) -> Option<Candle> {
+        let bucket = self.to.bucket_start(candle.timestamp);
+
+        match self.current.as_ref() {
+            Some(current) if current.timestamp == bucket && self.next == Some(candle.timestamp) => {
+                let mut preview = current.clone();
+                Self::merge(&mut preview, candle);
+                Some(preview)
+            }
+            _ if candle.timestamp == bucket => Some(Self::open(candle, bucket)),
+            _ => None,
+        }
+    }
+
+    /// Resamples candles of interval `from`, sorted from oldest to newest,
+    /// into candles of interval `to`. Incomplete buckets are left out.
+    pub fn resample(candles: &[Candle], from: &Interval, to: &Interval) -> Result<Vec<Candle>> {
+        let mut resampler = Self::new(from.clone(), to.clone())?;
+
+        Ok(candles
+            .iter()
+            .filter_map(|candle| resampler.add_candle(candle))
+            .collect())
+    }
+
+    fn validate(from: &Interval, to: &Interval) -> Result<()> {
+        if !to.can_resample_from(from) {
+            return Err(anyhow!("Unable to resample {} candles to {}.", from, to));
+        }
+
+        Ok(())
+    }
+
+    fn open(candle: &Candle, bucket: DateTime<Utc>) -> Candle {
+        Candle {
+            timestamp: bucket,
+            open: candle.open,
+            close: candle.close,
+            high: candle.high,
+            low: candle.low,
+            volume: candle.volume,
+        }
+    }
+
+    fn merge(acc: &mut Candle, candle: &Candle) {
+        acc.close = candle.close;
+        acc.high = acc.high.max(candle.high);
+        acc.low = acc.low.min(candle.low);
+        acc.volume += candle.volume;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{candle::Candle, interval::Interval, resampler::Resampler};
+    use chrono::{TimeZone, Utc};
+
+    fn minute_candles(start_minute: i64, n: usize) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        (0..n)
+            .map(|i| {
+                let timestamp = start + Interval::Minute1.to_duration() * (start_minute as i32);
+                let timestamp = timestamp + Interval::Minute1.to_duration() * i as i32;
+                let val = 100.0 + i as f64;
+                Candle::new(timestamp, val, val + 0.5, val + 1.0, val - 1.0, 10.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resample_minutes_to_five_minutes() {
+        let candles = minute_candles(0, 10);
+        let resampled = Resampler::resample(&candles, &Interval::Minute1, &Interval::Minute5);
+        let resampled = resampled.unwrap();
+
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].timestamp, candles[0].timestamp);
+        assert_eq!(resampled[0].open, 100.0);
+        assert_eq!(resampled[0].close, 104.5);
+        assert_eq!(resampled[0].high, 105.0);
+        assert_eq!(resampled[0].low, 99.0);
+        assert_eq!(resampled[0].volume, 50.0);
+        assert_eq!(resampled[1].timestamp, candles[5].timestamp);
+    }
+
+    #[test]
+    fn resample_discards_incomplete_buckets() {
+        // Starts mid bucket and ends mid bucket
+        let candles = minute_candles(3, 10);
+        let resampled = Resampler::resample(&candles, &Interval::Minute1, &Interval::Minute5);
+        let resampled = resampled.unwrap();
+
+        assert_eq!(resampled.len(), 1);
+        assert_eq!(resampled[0].timestamp, candles[2].timestamp);
+        assert_eq!(resampled[0].open, 102.0);
+    }
+
+    #[test]
+    fn resample_discards_buckets_with_gaps() {
+        let mut candles = minute_candles(0, 15);
+        candles.remove(7);
+
+        let resampled = Resampler::resample(&candles, &Interval::Minute1, &Interval::Minute5);
+        let resampled = resampled.unwrap();
+
+        // The 00:05 bucket misses 00:07, the others are complete
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].timestamp, candles[0].timestamp);
+        assert_eq!(resampled[1].timestamp, candles[9].timestamp);
+        assert_eq!(resampled[1].volume, 50.0);
+
+        let mut resampler = Resampler::new(Interval::Minute1, Interval::Minute5).unwrap();
+        resampler.add_candle(&candles[5]);
+        resampler.add_candle(&candles[6]);
+        assert!(resampler.preview_candle(&candles[7]).is_none());
+    }
+
+    #[test]
+    fn resample_incrementally() {
+        let candles = minute_candles(0, 15);
+        let mut resampler = Resampler::new(Interval::Minute1, Interval::Minute15).unwrap();
+
+        for candle in candles[..14].iter() {
+            assert!(resampler.add_candle(candle).is_none());
+        }
+
+        let resampled = resampler.add_candle(&candles[14]).unwrap();
+        assert_eq!(resampled.close, 114.5);
+        assert_eq!(resampled.volume, 150.0);
+    }
+
+    #[test]
+    fn resample_preview_forming_candle() {
+        let candles = minute_candles(0, 3);
+        let mut resampler = Resampler::new(Interval::Minute1, Interval::Minute5).unwrap();
+
+        let preview = resampler.preview_candle(&candles[0]).unwrap();
+        assert_eq!(preview.close, 100.5);
+
+        resampler.add_candle(&candles[0]);
+        resampler.add_candle(&candles[1]);
+
+        let preview = resampler.preview_candle(&candles[2]).unwrap();
+        assert_eq!(preview.open, 100.0);
+        assert_eq!(preview.close, 102.5);
+        assert_eq!(preview.volume, 30.0);
+
+        // Previewing does not add the candle
+        assert_eq!(resampler.current.as_ref().unwrap().volume, 20.0);
+    }
+
+    #[test]
+    fn resample_invalid_intervals() {
+        assert!(Resampler::new(Interval::Hour1, Interval::Minute15).is_err());
+        assert!(Resampler::new(Interval::Day5, Interval::Week1).is_err());
+        assert!(Resampler::new(Interval::Hour1, Interval::Hour1).is_err());
+        assert!(Resampler::new(Interval::Month1, Interval::Custom(90 * 24 * 60)).is_err());
+    }
+
+    #[test]
+    fn resample_days_to_months() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..70)
+            .map(|i| {
+                let timestamp = start + Interval::Day1.to_duration() * i;
+                Candle::new(timestamp, 100.0, 100.0, 100.0 + i as f64, 100.0, 1.0)
+            })
+            .collect();
+
+        let resampled = Resampler::resample(&candles, &Interval::Day1, &Interval::Month1).unwrap();
+
+        // January and the leap year February, March is incomplete
+        assert_eq!(resampled.len(), 2);
+        assert_eq!(resampled[0].volume, 31.0);
+        assert_eq!(resampled[1].volume, 29.0);
+        assert_eq!(resampled[1].timestamp, candles[31].timestamp);
+        assert_eq!(resampled[1].high, 159.0);
+    }
+}
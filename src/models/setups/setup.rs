@@ -1,4 +1,5 @@
 use crate::{
+    indicators::indicator_columns::IndicatorRow,
     models::{
         candle::Candle, interval::Interval, setups::csv_setup_row::CsvSetupRow,
         strategy_orientation::StrategyOrientation,
@@ -10,6 +11,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Setup {
     pub candle: Candle,
+    /// Indicators of the setup candle
+    #[serde(skip_serializing, skip_deserializing)]
+    pub indicators: IndicatorRow,
     pub orientation: StrategyOrientation,
     pub symbol: String,
     pub interval: Interval,
@@ -22,6 +26,7 @@ impl Setup {
         Setup {
             symbol: DEFAULT_SYMBOL.to_string(),
             candle,
+            indicators: IndicatorRow::new(),
             interval: Interval::Day1,
             orientation: StrategyOrientation::Long,
         }
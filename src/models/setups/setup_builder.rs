@@ -1,12 +1,16 @@
-use crate::models::{
-    candle::Candle, interval::Interval, setups::setup::Setup,
-    strategy_orientation::StrategyOrientation,
+use crate::{
+    indicators::indicator_columns::IndicatorRow,
+    models::{
+        candle::Candle, interval::Interval, setups::setup::Setup,
+        strategy_orientation::StrategyOrientation,
+    },
 };
 use anyhow::{anyhow, Result};
 
 #[derive(Debug, Clone)]
 pub struct SetupBuilder {
     pub candle: Option<Candle>,
+    pub indicators: IndicatorRow,
     pub orientation: Option<StrategyOrientation>,
     pub symbol: Option<String>,
     pub interval: Option<Interval>,
@@ -16,6 +20,7 @@ impl SetupBuilder {
     pub fn new() -> Self {
         SetupBuilder {
             candle: None,
+            indicators: IndicatorRow::new(),
             orientation: None,
             symbol: None,
             interval: None,
@@ -27,6 +32,12 @@ impl SetupBuilder {
         self
     }
 
+    /// Sets the indicators of the setup candle.
+    pub fn indicators(mut self, indicators: IndicatorRow) -> Self {
+        self.indicators = indicators;
+        self
+    }
+
     pub fn orientation(mut self, orientation: &StrategyOrientation) -> Self {
         self.orientation = Some(orientation.clone());
         self
@@ -55,6 +66,7 @@ impl SetupBuilder {
 
         Ok(Setup {
             candle,
+            indicators: self.indicators.clone(),
             orientation,
             symbol,
             interval,
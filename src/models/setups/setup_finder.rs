@@ -1,12 +1,17 @@
 use crate::{
     data_sources::datasource::DataSource,
     models::{
+        candle::Candle,
         database::db::DB,
+        interval::Interval,
         message_payloads::{
             candle_added_payload::CandleAddedPayload, ping_payload::PingPayload,
             request_latest_candles_payload::RequestLatestCandlesPayload,
             triggered_payload::TriggeredPayload, ts_subscribe_payload::TSSubscribePayload,
+            ts_update_subscribe_payload::TSUpdateSubscribePayload,
         },
+        multi_timeframe_view::MultiTimeframeView,
+        timeframe_requirement::TimeframeRequirement,
         timeseries::TimeSeries,
         trade::Trade,
         trade_builder::TradeBuilder,
@@ -15,20 +20,27 @@ use crate::{
     notifications::notification_center::NotificationCenter,
 };
 use actix::{fut::wrap_future, Actor, Addr, AsyncContext, Context, Handler, Message};
-use anyhow::Result;
-use tokio::try_join;
+use anyhow::{anyhow, Context as _, Result};
+use std::{collections::HashMap, time::Duration};
+use tokio::{time::sleep, try_join};
+
+// Times the TimeSeries of an additional timeframe is requested before giving
+// up on it catching up with the main interval.
+const ALIGNMENT_ATTEMPTS: usize = 10;
+const ALIGNMENT_RETRY_DELAY: Duration = Duration::from_millis(250);
 
 #[derive(Debug)]
 pub struct SetupFinder {
-    strategy: Box<dyn TradingStrategy>,
-    ts_addr: Addr<TimeSeries>,
-    db_addr: Addr<DB>,
-    source: DataSource,
-    notifications_enabled: bool,
-    live_trading_enabled: bool,
-    only_trigger_once: bool,
-    triggered: bool,
-    spawned_trade_addrs: Vec<Addr<Trade>>,
+    pub strategy: Box<dyn TradingStrategy>,
+    pub ts_addr: Addr<TimeSeries>,
+    pub timeframe_addrs: HashMap<Interval, Addr<TimeSeries>>,
+    pub db_addr: Addr<DB>,
+    pub source: DataSource,
+    pub notifications_enabled: bool,
+    pub live_trading_enabled: bool,
+    pub only_trigger_once: bool,
+    pub triggered: bool,
+    pub spawned_trade_addrs: Vec<Addr<Trade>>,
 }
 
 impl Actor for SetupFinder {
@@ -53,6 +65,7 @@ impl Handler<CandleAddedPayload> for SetupFinder {
 
         let self_addr = ctx.address();
         let ts = self.ts_addr.clone();
+        let timeframe_addrs = self.timeframe_addrs.clone();
         let mut strategy = self.strategy.clone_box();
         let notifications_enabled = self.notifications_enabled;
         let live_trading_enabled = self.live_trading_enabled;
@@ -74,7 +87,41 @@ impl Handler<CandleAddedPayload> for SetupFinder {
                 .expect("Failed to request latest candles")
                 .expect("Failed to unwrap LatestCandleResponse");
 
-            let sb = strategy.check_last_for_setup(&candle_response.candles);
+            let candles = candle_response.candles.view();
+            let is_active = candles
+                .last()
+                .is_some_and(|last| strategy.trading_schedule().is_active(&last.timestamp));
+
+            if !is_active {
+                return;
+            }
+
+            let requirements = strategy.timeframe_requirements();
+
+            let sb = if requirements.is_empty() {
+                strategy.check_last_for_setup(candles)
+            } else {
+                let last = match candles.last() {
+                    Some(candle) => candle,
+                    None => return,
+                };
+
+                let view = get_timeframe_view(
+                    &requirements,
+                    &timeframe_addrs,
+                    last,
+                    &candle_response.interval,
+                )
+                .await;
+
+                match view {
+                    Ok(view) => strategy.check_last_for_setup_with_timeframes(candles, &view),
+                    Err(e) => {
+                        println!("Error: {:#?}", e);
+                        return;
+                    }
+                }
+            };
 
             if sb.is_none() {
                 return;
@@ -83,6 +130,7 @@ impl Handler<CandleAddedPayload> for SetupFinder {
             let sb = sb.unwrap();
             let resolution_strategy = strategy.default_resolution_strategy();
             let setup = sb
+                .indicators(candles.row(candles.len() - 1))
                 .symbol(&candle_response.symbol)
                 .interval(&candle_response.interval)
                 .build();
@@ -127,7 +175,7 @@ impl Handler<CandleAddedPayload> for SetupFinder {
                     .notifications_enabled(notifications_enabled)
                     .trading_enabled(true)
                     .resolution_strategy(resolution_strategy)
-                    .orientation(strategy.orientation())
+                    .orientation(setup.orientation)
                     .timeseries_addr(ts.clone())
                     .trading_strategy(strategy.clone_box())
                     .db_addr(db_addr)
@@ -143,6 +191,13 @@ impl Handler<CandleAddedPayload> for SetupFinder {
                 };
                 ts.do_send(ts_subscribe_payload);
 
+                // Also subscribe to updates of forming candles, only sent when
+                // intra-candle updates are enabled on the websocket client.
+                let ts_update_subscribe_payload = TSUpdateSubscribePayload {
+                    observer: trade_addr.clone().recipient(),
+                };
+                ts.do_send(ts_update_subscribe_payload);
+
                 let msg = TradeSpawnedMsg { addr: trade_addr };
                 self_addr.do_send(msg);
             }
@@ -186,29 +241,6 @@ impl Handler<TriggeredPayload> for SetupFinder {
 }
 
 impl SetupFinder {
-    pub fn new(
-        strategy: Box<dyn TradingStrategy>,
-        ts_addr: Addr<TimeSeries>,
-        db_addr: Addr<DB>,
-        notifications_enabled: bool,
-        live_trading_enabled: bool,
-        only_trigger_once: bool,
-        spawned_trade_addrs: &[Addr<Trade>],
-        source: DataSource,
-    ) -> Result<Self> {
-        Ok(SetupFinder {
-            strategy,
-            ts_addr,
-            db_addr,
-            notifications_enabled,
-            live_trading_enabled,
-            only_trigger_once,
-            spawned_trade_addrs: spawned_trade_addrs.to_vec(),
-            source,
-            triggered: false,
-        })
-    }
-
     fn clear_closed_trades(&mut self) {
         let mut trade_addrs = vec![];
 
@@ -223,3 +255,65 @@ impl SetupFinder {
         self.spawned_trade_addrs = trade_addrs;
     }
 }
+
+/// Fetches the candles of every required timeframe and aligns them to the last
+/// candle of the main interval. A higher interval closing together with the
+/// main interval may not have received its candle yet, in which case it is
+/// polled again until it has caught up.
+async fn get_timeframe_view(
+    requirements: &[TimeframeRequirement],
+    timeframe_addrs: &HashMap<Interval, Addr<TimeSeries>>,
+    last: &Candle,
+    base: &Interval,
+) -> Result<MultiTimeframeView<'static>> {
+    let mut view = MultiTimeframeView::new();
+
+    for requirement in requirements {
+        let addr = timeframe_addrs.get(&requirement.interval).context(format!(
+            "No TimeSeries provided for interval {}",
+            requirement.interval
+        ))?;
+
+        let mut attempts = 0;
+
+        let candles = loop {
+            // Request one extra candle in case the latest one has not closed
+            // yet relative to the main interval.
+            let payload = RequestLatestCandlesPayload {
+                n: requirement.candles_needed + 1,
+            };
+            let response = addr.send(payload).await??;
+
+            if MultiTimeframeView::is_aligned(
+                &response.candles.candles,
+                &requirement.interval,
+                last,
+                base,
+            ) {
+                break response.candles;
+            }
+
+            attempts += 1;
+
+            if attempts >= ALIGNMENT_ATTEMPTS {
+                return Err(anyhow!(
+                    "TimeSeries for interval {} has not caught up with candle at {}",
+                    requirement.interval,
+                    last.timestamp
+                ));
+            }
+
+            sleep(ALIGNMENT_RETRY_DELAY).await;
+        };
+
+        view.insert_aligned_owned(
+            &requirement.interval,
+            candles,
+            last,
+            base,
+            requirement.candles_needed,
+        );
+    }
+
+    Ok(view)
+}
@@ -1,16 +1,18 @@
 use crate::{
     data_sources::datasource::DataSource,
     models::{
-        database::db::DB, setups::setup_finder::SetupFinder, timeseries::TimeSeries, trade::Trade,
-        traits::trading_strategy::TradingStrategy,
+        database::db::DB, interval::Interval, setups::setup_finder::SetupFinder,
+        timeseries::TimeSeries, trade::Trade, traits::trading_strategy::TradingStrategy,
     },
 };
 use actix::Addr;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
 
 pub struct SetupFinderBuilder {
     strategy: Option<Box<dyn TradingStrategy>>,
     ts_addr: Option<Addr<TimeSeries>>,
+    timeframe_addrs: HashMap<Interval, Addr<TimeSeries>>,
     source: Option<DataSource>,
     db_addr: Option<Addr<DB>>,
     notifications_enabled: bool,
@@ -24,6 +26,7 @@ impl SetupFinderBuilder {
         SetupFinderBuilder {
             strategy: None,
             ts_addr: None,
+            timeframe_addrs: HashMap::new(),
             source: None,
             db_addr: None,
             notifications_enabled: false,
@@ -48,6 +51,13 @@ impl SetupFinderBuilder {
         self
     }
 
+    /// Adds the TimeSeries used for one of the additional timeframes required
+    /// by the strategy.
+    pub fn timeframe_addr(mut self, interval: Interval, ts_addr: Addr<TimeSeries>) -> Self {
+        self.timeframe_addrs.insert(interval, ts_addr);
+        self
+    }
+
     pub fn db_addr(mut self, ts: Addr<DB>) -> Self {
         self.db_addr = Some(ts);
         self
@@ -92,15 +102,26 @@ impl SetupFinderBuilder {
             .db_addr
             .context("DB is required to build SetupFinder")?;
 
-        Ok(SetupFinder::new(
+        for requirement in strategy.timeframe_requirements() {
+            if !self.timeframe_addrs.contains_key(&requirement.interval) {
+                return Err(anyhow!(
+                    "TimeSeries for interval {} is required to build SetupFinder",
+                    requirement.interval
+                ));
+            }
+        }
+
+        Ok(SetupFinder {
             strategy,
-            ts,
+            ts_addr: ts,
+            timeframe_addrs: self.timeframe_addrs,
             db_addr,
+            source,
             notifications_enabled,
             live_trading_enabled,
             only_trigger_once,
-            &spawned_trades,
-            source,
-        )?)
+            triggered: false,
+            spawned_trade_addrs: spawned_trades,
+        })
     }
 }
@@ -0,0 +1,35 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{interval::Interval, traits::has_min_length::HasMinLength},
+};
+
+/// # TimeframeRequirement
+///
+/// Describes a TimeSeries on another interval than the main interval of a
+/// TradingStrategy which the strategy needs access to when checking for
+/// setups, e.g. a daily EMA used as trend filter for 15 minute entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeframeRequirement {
+    pub interval: Interval,
+    pub indicators: Vec<IndicatorType>,
+    pub candles_needed: usize,
+}
+
+impl TimeframeRequirement {
+    pub fn new(interval: Interval, indicators: Vec<IndicatorType>, candles_needed: usize) -> Self {
+        Self {
+            interval,
+            indicators,
+            candles_needed,
+        }
+    }
+}
+
+impl HasMinLength for TimeframeRequirement {
+    fn min_length(&self) -> usize {
+        self.indicators
+            .iter()
+            .map(|indicator| indicator.min_length())
+            .fold(self.candles_needed, usize::max)
+    }
+}
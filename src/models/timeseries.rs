@@ -1,37 +1,68 @@
 use crate::{
     data_sources::{bybit::rest::bybit_rest_api::BybitRestApi, datasource::DataSource, local},
-    indicators::{indicator_type::IndicatorType, populates_candles::PopulatesCandlesWithSelf},
+    indicators::{
+        indicator::Indicator,
+        indicator_column::IndicatorColumn,
+        indicator_columns::{IndicatorColumns, IndicatorRow},
+        indicator_state::IndicatorState,
+        indicator_type::IndicatorType,
+        populates_candles::PopulatesCandlesWithSelf,
+    },
     models::{
         candle::Candle,
+        candle_window::CandleView,
         interval::Interval,
         message_payloads::{
+            add_candle_with_indicators_payload::AddCandleWithIndicatorsPayload,
             add_candles_payload::AddCandlesPayload, candle_added_payload::CandleAddedPayload,
+            candle_updated_payload::CandleUpdatedPayload,
             fill_historical_candles_payload::FillHistoricalCandlesPayload,
             latest_candles_payload::LatestCandleResponse,
             request_latest_candles_payload::RequestLatestCandlesPayload,
-            ts_subscribe_payload::TSSubscribePayload, websocket_payload::WebsocketPayload,
+            ts_subscribe_payload::TSSubscribePayload,
+            ts_update_subscribe_payload::TSUpdateSubscribePayload,
+            websocket_payload::WebsocketPayload,
         },
         net_version::NetVersion,
         timeseries_builder::TimeSeriesBuilder,
+        traits::has_min_length::HasMinLength,
     },
-    utils::constants::DEFAULT_SYMBOL,
+    utils::{constants::DEFAULT_SYMBOL, ring_buffer::RingBuffer},
 };
 use actix::{
     dev::ContextFutureSpawner, Actor, AsyncContext, Context as ActixContext, Handler, Recipient,
     WrapFuture,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use indexmap::IndexSet;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Values of a single indicator for every candle of a TimeSeries, see
+/// `TimeSeries::add_indicators`.
+struct IndicatorValues {
+    indicator_type: IndicatorType,
+    values: Vec<Option<Indicator>>,
+    state: Option<IndicatorState>,
+}
 
 #[derive(Debug, Clone)]
 pub struct TimeSeries {
     pub symbol: String,
     pub interval: Interval,
+    /// Number of candles kept, once reached the oldest candle is evicted for
+    /// every candle added
     pub max_length: usize,
-    pub candles: Vec<Candle>,
+    /// OHLCV of the candles, oldest first
+    pub candles: RingBuffer<Candle>,
+    /// Values of the indicators, one column per indicator aligned to candles
+    pub columns: IndicatorColumns,
     pub indicators: IndexSet<IndicatorType>,
+    /// Rolling state of indicators populated incrementally, see IndicatorState
+    pub indicator_states: HashMap<IndicatorType, IndicatorState>,
     pub observers: Vec<Recipient<CandleAddedPayload>>,
+    pub update_observers: Vec<Recipient<CandleUpdatedPayload>>,
     pub net: NetVersion,
     pub validate_candles_on_add: bool,
 }
@@ -55,6 +86,20 @@ impl Handler<AddCandlesPayload> for TimeSeries {
     }
 }
 
+impl Handler<AddCandleWithIndicatorsPayload> for TimeSeries {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: AddCandleWithIndicatorsPayload,
+        _ctx: &mut ActixContext<Self>,
+    ) -> Self::Result {
+        if let Err(e) = self.add_candle_with(&msg.candle, &msg.indicators) {
+            println!("Error: {:#?}", e);
+        }
+    }
+}
+
 impl Handler<WebsocketPayload> for TimeSeries {
     type Result = ();
 
@@ -75,6 +120,14 @@ impl Handler<WebsocketPayload> for TimeSeries {
             .candle
             .expect("No message passed although WebsocketPayload ok.");
 
+        if !msg.closed {
+            if let Err(e) = self.notify_candle_updated(candle) {
+                println!("Error: {:#?}", e);
+            }
+
+            return;
+        }
+
         let integrity_ok = if self.validate_candles_on_add {
             self.validate_timeseries_integrity(candle.timestamp)
         } else {
@@ -91,16 +144,17 @@ impl Handler<WebsocketPayload> for TimeSeries {
         } else {
             println!("GAP at: {}", candle.timestamp);
 
-            let interval_addition = self.interval.to_millis();
+            let last = self
+                .candles
+                .last()
+                .expect("Expected at least one candle")
+                .timestamp;
             let payload = FillHistoricalCandlesPayload {
-                from: self
-                    .candles
-                    .last()
-                    .expect("Expected at least one candle")
-                    .timestamp
-                    .timestamp_millis()
-                    + interval_addition,
-                to: candle.timestamp.timestamp_millis() + interval_addition,
+                from: self.interval.next_start(last).timestamp_millis(),
+                to: self
+                    .interval
+                    .next_start(candle.timestamp)
+                    .timestamp_millis(),
                 symbol: self.symbol.clone(),
                 interval: self.interval.clone(),
             };
@@ -150,13 +204,8 @@ impl Handler<RequestLatestCandlesPayload> for TimeSeries {
         msg: RequestLatestCandlesPayload,
         _ctx: &mut ActixContext<Self>,
     ) -> Self::Result {
-        let candles = if self.candles.len() < msg.n {
-            // Return what's available
-            self.candles.clone()
-        } else {
-            // Return requested number of candles
-            self.candles[self.candles.len() - msg.n..].to_vec()
-        };
+        // Returns what's available if fewer than requested
+        let candles = self.view().tail(msg.n).to_window();
 
         Ok(LatestCandleResponse {
             symbol: self.symbol.clone(),
@@ -175,10 +224,25 @@ impl Handler<TSSubscribePayload> for TimeSeries {
     }
 }
 
+impl Handler<TSUpdateSubscribePayload> for TimeSeries {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: TSUpdateSubscribePayload,
+        _ctx: &mut ActixContext<Self>,
+    ) -> Self::Result {
+        self.update_observers.push(msg.observer);
+    }
+}
+
 impl TimeSeries {
-    #[allow(dead_code)]
+    /// Sets the number of candles kept, evicting the oldest candles which no
+    /// longer fit.
     pub fn set_max_length(&mut self, max_length: usize) {
         self.max_length = max_length;
+        self.candles.set_capacity(max_length);
+        self.columns.set_capacity(max_length);
     }
 
     fn validate_timeseries_integrity(&mut self, new_candle: DateTime<Utc>) -> bool {
@@ -190,13 +254,68 @@ impl TimeSeries {
         let last_candle = &self.candles[self.candles.len() - 1];
         let diff = new_candle.signed_duration_since(last_candle.timestamp);
 
-        let step = self.interval.to_duration();
+        let step = self.interval.next_start(last_candle.timestamp) - last_candle.timestamp;
         let delta = self.interval.max_diff();
 
         // New is subsequent candle so timeseries integrity ok
         return diff >= step - delta && diff < step + delta;
     }
 
+    /// Notifies update observers of a candle which is still forming. The
+    /// candle gets its indicators populated as if it closed at its current
+    /// values but is not added to the timeseries. Updates of candles not
+    /// directly following the last candle are ignored.
+    fn notify_candle_updated(&mut self, candle: Candle) -> Result<()> {
+        if self.update_observers.is_empty() || !self.validate_timeseries_integrity(candle.timestamp)
+        {
+            return Ok(());
+        }
+
+        let (candle, indicators) = self.preview_candle(candle)?;
+        let payload = CandleUpdatedPayload { candle, indicators };
+
+        for observer in &self.update_observers {
+            observer.do_send(payload.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the candle with its indicators populated as if it was added.
+    /// Only the candles needed by the indicators and the rolling indicator
+    /// states are copied, indicators may update previous candles so they can
+    /// not be populated in place.
+    pub fn preview_candle(&self, candle: Candle) -> Result<(Candle, IndicatorRow)> {
+        let n = self
+            .indicators
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1);
+        let start = self.candles.len().saturating_sub(n);
+
+        let mut preview = TimeSeriesBuilder::new()
+            .symbol(self.symbol.clone())
+            .interval(self.interval.clone())
+            .candles(self.candles[start..].to_vec())
+            .build();
+        preview.columns = self.columns.slice(start, self.candles.len());
+        preview.set_max_length(self.candles.len() - start + 1);
+        preview.indicators = self.indicators.clone();
+        preview.indicator_states = self.indicator_states.clone();
+        preview.push_candle(candle);
+
+        for indicator_type in self.indicators.iter() {
+            indicator_type.populate_last_candle(&mut preview)?;
+        }
+
+        let i = preview.candles.len() - 1;
+        let indicators = preview.columns.row(i);
+        let candle = preview.candles.pop().context("Expected updated candle.")?;
+
+        Ok((candle, indicators))
+    }
+
     fn add_candles(&mut self, candles: &[Candle]) -> Result<()> {
         for candle in candles.iter() {
             self.add_candle(&candle)?;
@@ -206,7 +325,15 @@ impl TimeSeries {
     }
 
     pub fn add_candle(&mut self, candle: &Candle) -> Result<()> {
+        self.add_candle_with(candle, &IndicatorRow::new())
+    }
+
+    /// Adds a candle with preset indicators, see
+    /// `AddCandleWithIndicatorsPayload`.
+    pub fn add_candle_with(&mut self, candle: &Candle, indicators: &IndicatorRow) -> Result<()> {
+        // Once full the oldest candle is evicted along with its indicators
         self.candles.push(candle.clone());
+        self.columns.push_row_with(indicators);
 
         let indicator_types = self.indicators.clone();
 
@@ -228,26 +355,69 @@ impl TimeSeries {
             observer.do_send(payload.clone());
         }
 
-        // Remove oldest candle if max length is exceeded
-        if self.candles.len() > self.max_length {
-            self.candles.remove(0);
-        }
-
         Ok(())
     }
 
+    /// Appends a candle without populating its indicators.
+    pub fn push_candle(&mut self, candle: Candle) {
+        self.candles.push(candle);
+        self.columns.push_row();
+    }
+
+    /// Replaces the candles, the values and rolling states of the indicators
+    /// are dropped.
     pub fn set_candles(&mut self, candles: &[Candle]) {
-        self.candles = candles.to_vec();
+        self.candles = RingBuffer::from_vec(candles.to_vec(), self.max_length);
+        self.columns = IndicatorColumns::new(self.max_length, self.candles.len());
+        self.indicator_states.clear();
     }
 
     #[allow(dead_code)]
     pub fn get_candles(&self) -> Vec<Candle> {
-        self.candles.clone()
+        self.candles.to_vec()
     }
 
     #[allow(dead_code)]
     pub fn clear_candles(&mut self) {
-        self.candles.clear()
+        self.candles.clear();
+        self.columns.clear();
+        self.indicator_states.clear();
+    }
+
+    /// Returns a borrowed view of the candles and their indicators.
+    pub fn view(&self) -> CandleView<'_> {
+        CandleView::new(&self.candles, &self.columns)
+    }
+
+    /// Returns a borrowed view of the given indicator across all candles.
+    pub fn indicator_column(&self, indicator_type: &IndicatorType) -> IndicatorColumn<'_> {
+        self.view().column(indicator_type)
+    }
+
+    /// Returns the indicator of the candle at index `i`.
+    pub fn indicator(&self, i: usize, indicator_type: &IndicatorType) -> Option<&Indicator> {
+        self.columns.get(indicator_type, i)
+    }
+
+    /// Sets the indicator of the candle at index `i`.
+    pub fn set_indicator(&mut self, i: usize, indicator_type: IndicatorType, indicator: Indicator) {
+        self.columns.set(indicator_type, i, indicator);
+    }
+
+    /// Sets the indicator of the last candle.
+    pub fn set_last_indicator(
+        &mut self,
+        indicator_type: IndicatorType,
+        indicator: Indicator,
+    ) -> Result<()> {
+        let i = self
+            .candles
+            .len()
+            .checked_sub(1)
+            .context("Failed to get last candle")?;
+        self.set_indicator(i, indicator_type, indicator);
+
+        Ok(())
     }
 
     pub fn add_indicator(&mut self, indicator_type: IndicatorType) -> Result<()> {
@@ -262,6 +432,78 @@ impl TimeSeries {
         Ok(())
     }
 
+    /// Adds multiple indicators, populating them in parallel. Each indicator
+    /// is computed into its own column of values from copies of the OHLCV,
+    /// so the columns already populated are never cloned. The new columns are
+    /// then added in the given order, making the outcome identical to adding
+    /// the indicators one by one.
+    pub fn add_indicators(&mut self, indicator_types: &[IndicatorType]) -> Result<()> {
+        let mut new_types: Vec<IndicatorType> = vec![];
+
+        for indicator_type in indicator_types {
+            if !self.indicators.contains(indicator_type) && !new_types.contains(indicator_type) {
+                new_types.push(*indicator_type);
+            }
+        }
+
+        let (symbol, interval, candles) = (&self.symbol, &self.interval, &self.candles);
+
+        let populated = new_types
+            .par_iter()
+            .map(|indicator_type| {
+                let ohlcv = candles.to_vec();
+                let mut ts = TimeSeriesBuilder::new()
+                    .symbol(symbol.clone())
+                    .interval(interval.clone())
+                    .candles(ohlcv)
+                    .build();
+                ts.add_indicator(*indicator_type)?;
+
+                Ok(ts.into_indicator_columns())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for columns in populated {
+            self.write_indicator_columns(columns);
+        }
+
+        Ok(())
+    }
+
+    /// Takes the column of every indicator out along with its rolling state.
+    /// Dependencies populated along the way (e.g. the EMA of a PMARP) have
+    /// their own column.
+    fn into_indicator_columns(mut self) -> Vec<IndicatorValues> {
+        let indicator_types: Vec<IndicatorType> = self.indicators.iter().copied().collect();
+
+        indicator_types
+            .into_iter()
+            .map(|indicator_type| IndicatorValues {
+                indicator_type,
+                values: self.columns.remove(&indicator_type).unwrap_or_default(),
+                state: self.indicator_states.remove(&indicator_type),
+            })
+            .collect()
+    }
+
+    /// Adds indicator columns computed on a copy of the candles. Indicators
+    /// already present are kept as is.
+    fn write_indicator_columns(&mut self, columns: Vec<IndicatorValues>) {
+        for column in columns {
+            if self.indicators.contains(&column.indicator_type) {
+                continue;
+            }
+
+            self.columns.insert(column.indicator_type, column.values);
+
+            if let Some(state) = column.state {
+                self.indicator_states.insert(column.indicator_type, state);
+            }
+
+            self.indicators.insert(column.indicator_type);
+        }
+    }
+
     pub fn dummy() -> Self {
         TimeSeriesBuilder::new()
             .symbol(DEFAULT_SYMBOL.to_string())
@@ -274,3 +516,151 @@ impl TimeSeries {
         local::write(self, source).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        indicators::{
+            indicator_columns::IndicatorRow, indicator_type::IndicatorType,
+            is_indicator::IsIndicator, sma::SMA,
+        },
+        models::{
+            candle::Candle, fixed_point::FixedPoint, interval::Interval, ma_type::MAType,
+            timeseries_builder::TimeSeriesBuilder, traits::has_min_length::HasMinLength,
+        },
+        utils::data::dummy_data::PRICE_CHANGES,
+    };
+
+    #[test]
+    fn add_indicators_matches_add_indicator() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let indicator_types = [
+            IndicatorType::PMARP(20, 100, 20, MAType::EMA),
+            IndicatorType::BBWP(13, 100, 5, FixedPoint::new(1.0), MAType::SMA),
+            IndicatorType::RSI(14),
+            IndicatorType::BollingerBands(20, FixedPoint::new(2.0), MAType::EMA),
+            IndicatorType::EMA(20),
+        ];
+
+        let mut sequential = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles.clone())
+            .build();
+
+        for indicator_type in indicator_types {
+            sequential.add_indicator(indicator_type).unwrap();
+        }
+
+        let mut parallel = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        parallel.add_indicators(&indicator_types).unwrap();
+
+        assert_eq!(sequential.indicators, parallel.indicators);
+
+        for i in 0..sequential.candles.len() {
+            let (s, p) = (sequential.view().row(i), parallel.view().row(i));
+            assert_eq!(s.len(), p.len());
+
+            let get = |row: &IndicatorRow, indicator_type: IndicatorType| row[&indicator_type];
+
+            let pmarp = indicator_types[0];
+            assert_eq!(get(&s, pmarp).as_pmarp(), get(&p, pmarp).as_pmarp());
+
+            let bbwp = indicator_types[1];
+            assert_eq!(get(&s, bbwp).as_bbwp(), get(&p, bbwp).as_bbwp());
+
+            let rsi = indicator_types[2];
+            assert_eq!(get(&s, rsi).as_rsi(), get(&p, rsi).as_rsi());
+
+            let bb = indicator_types[3];
+            assert_eq!(
+                get(&s, bb).as_bollinger_bands(),
+                get(&p, bb).as_bollinger_bands()
+            );
+
+            let ema = indicator_types[4];
+            assert_eq!(get(&s, ema).as_ema(), get(&p, ema).as_ema());
+        }
+    }
+
+    #[test]
+    fn add_candle_evicts_oldest_candle() {
+        let candles = Candle::dummy_data(30, "positive", 100.0);
+        let indicator_type = IndicatorType::SMA(3);
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .max_length(16)
+            .validate_candles_on_add(false)
+            .build();
+        ts.add_indicator(indicator_type).unwrap();
+
+        for (i, candle) in candles.iter().enumerate() {
+            ts.add_candle(candle).unwrap();
+
+            // Always exactly max_length candles once full
+            assert_eq!(ts.candles.len(), (i + 1).min(ts.max_length));
+            assert_eq!(ts.columns.len(), ts.candles.len());
+            assert_eq!(
+                ts.indicator_column(&indicator_type).iter().count(),
+                ts.candles.len()
+            );
+            assert_eq!(ts.candles.last().unwrap().timestamp, candle.timestamp);
+            assert_eq!(
+                ts.candles[0].timestamp,
+                candles[(i + 1).saturating_sub(ts.max_length)].timestamp
+            );
+
+            // Indicators stay aligned to their candles after evictions
+            let last = ts.view().last_indicator(&indicator_type).unwrap();
+            let expected = SMA::calculate(&candles[(i + 1).saturating_sub(3)..=i]);
+            assert_eq!(last.as_sma(), expected.filter(|_| i >= 2));
+        }
+    }
+
+    #[test]
+    fn preview_candle_matches_added_candle() {
+        let candles = Candle::dummy_from_increments(PRICE_CHANGES);
+        let indicator_types = [
+            IndicatorType::PMARP(20, 100, 20, MAType::EMA),
+            IndicatorType::RSI(14),
+            IndicatorType::EMA(20),
+        ];
+        let split = candles.len() - 10;
+
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .validate_candles_on_add(false)
+            .candles(candles[..split].to_vec())
+            .build();
+        ts.add_indicators(&indicator_types).unwrap();
+
+        // The preview only copies the candles needed by the indicators
+        assert!(indicator_types[0].min_length() < split);
+
+        for candle in candles[split..].iter() {
+            let (_, preview) = ts.preview_candle(candle.clone()).unwrap();
+            ts.add_candle(candle).unwrap();
+            let added = ts.view().row(ts.candles.len() - 1);
+
+            let get = |row: &IndicatorRow, indicator_type: IndicatorType| row[&indicator_type];
+
+            let pmarp = indicator_types[0];
+            assert_eq!(
+                get(&preview, pmarp).as_pmarp(),
+                get(&added, pmarp).as_pmarp()
+            );
+
+            let rsi = indicator_types[1];
+            assert_eq!(get(&preview, rsi).as_rsi(), get(&added, rsi).as_rsi());
+
+            let ema = indicator_types[2];
+            assert_eq!(get(&preview, ema).as_ema(), get(&added, ema).as_ema());
+        }
+    }
+}
@@ -1,13 +1,19 @@
 use crate::{
-    indicators::indicator_type::IndicatorType,
+    indicators::{indicator_columns::IndicatorColumns, indicator_type::IndicatorType},
     models::{
-        candle::Candle, interval::Interval,
-        message_payloads::candle_added_payload::CandleAddedPayload, net_version::NetVersion,
+        candle::Candle,
+        interval::Interval,
+        message_payloads::{
+            candle_added_payload::CandleAddedPayload, candle_updated_payload::CandleUpdatedPayload,
+        },
+        net_version::NetVersion,
         timeseries::TimeSeries,
     },
+    utils::ring_buffer::RingBuffer,
 };
 use actix::Recipient;
 use indexmap::IndexSet;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct TimeSeriesBuilder {
@@ -17,6 +23,7 @@ pub struct TimeSeriesBuilder {
     candles: Vec<Candle>,
     indicators: IndexSet<IndicatorType>,
     observers: Vec<Recipient<CandleAddedPayload>>,
+    update_observers: Vec<Recipient<CandleUpdatedPayload>>,
     net: NetVersion,
     validate_candles_on_add: bool,
 }
@@ -31,6 +38,7 @@ impl TimeSeriesBuilder {
             candles: vec![],
             indicators: IndexSet::new(),
             observers: vec![],
+            update_observers: vec![],
             net: NetVersion::Mainnet,
             validate_candles_on_add: true,
         }
@@ -71,6 +79,11 @@ impl TimeSeriesBuilder {
         self
     }
 
+    pub fn add_update_observer(mut self, observer: Recipient<CandleUpdatedPayload>) -> Self {
+        self.update_observers.push(observer);
+        self
+    }
+
     pub fn net(mut self, net: NetVersion) -> Self {
         self.net = net;
         self
@@ -86,9 +99,15 @@ impl TimeSeriesBuilder {
             symbol: self.symbol.expect("Symbol is required"),
             interval: self.interval.expect("Interval is required"),
             max_length: self.max_length,
-            candles: self.candles,
+            columns: IndicatorColumns::new(
+                self.max_length,
+                self.candles.len().min(self.max_length),
+            ),
+            candles: RingBuffer::from_vec(self.candles, self.max_length),
             indicators: self.indicators,
+            indicator_states: HashMap::new(),
             observers: self.observers,
+            update_observers: self.update_observers,
             net: self.net,
             validate_candles_on_add: self.validate_candles_on_add,
         }
@@ -1,11 +1,14 @@
 use crate::{
     data_sources::datasource::DataSource,
+    indicators::indicator_columns::IndicatorRow,
     models::{
-        database::{db::DB, db_trade::DBTrade},
+        candle::Candle,
+        database::{db::DB, db_trade::DBTrade, db_trade_exit::DBTradeExit},
         interval::Interval,
         message_payloads::finish_db_trade_payload::FinishDBTradePayload,
         message_payloads::{
-            candle_added_payload::CandleAddedPayload,
+            add_db_trade_exit_payload::AddDBTradeExitPayload,
+            candle_added_payload::CandleAddedPayload, candle_updated_payload::CandleUpdatedPayload,
             create_db_trade_payload::CreateDBTradePayload, ping_payload::PingPayload,
             request_latest_candles_payload::RequestLatestCandlesPayload, stop_payload::StopPayload,
         },
@@ -13,11 +16,14 @@ use crate::{
         timeseries::TimeSeries,
     },
     resolution_strategies::{
-        is_resolution_strategy::IsResolutionStrategy, resolution_strategy::ResolutionStrategy,
+        exit_action::REMAINDER_TOLERANCE, is_resolution_strategy::IsResolutionStrategy,
+        resolution_strategy::ResolutionStrategy,
     },
     TradingStrategy,
 };
-use actix::{Actor, ActorContext, Addr, AsyncContext, Context, Handler, WrapFuture};
+use actix::{
+    Actor, ActorContext, ActorFutureExt, Addr, AsyncContext, Context, Handler, WrapFuture,
+};
 use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
 
@@ -34,6 +40,10 @@ pub struct Trade {
     pub trading_strategy: Box<dyn TradingStrategy>,
     pub timeseries: Addr<TimeSeries>,
     pub db_addr: Addr<DB>,
+    pub exited: bool,
+    /// Quantity still open, lower than `quantity` after partial exits
+    pub remaining_quantity: f64,
+    pub exits: Vec<DBTradeExit>,
 }
 
 impl Actor for Trade {
@@ -116,7 +126,37 @@ impl Handler<CandleAddedPayload> for Trade {
     type Result = ();
 
     fn handle(&mut self, msg: CandleAddedPayload, ctx: &mut Self::Context) -> Self::Result {
-        let resolution_strategy = self.resolution_strategy.clone();
+        self.check_resolution(msg.candle, None, ctx);
+    }
+}
+
+impl Handler<CandleUpdatedPayload> for Trade {
+    type Result = ();
+
+    fn handle(&mut self, msg: CandleUpdatedPayload, ctx: &mut Self::Context) -> Self::Result {
+        self.check_resolution(msg.candle, Some(msg.indicators), ctx);
+    }
+}
+
+impl Trade {
+    /// Checks whether (part of) the trade should be exited, and executes the
+    /// exits if so. Candles still forming come with their `indicators` and
+    /// are evaluated as the last candle in place of the not yet closed
+    /// candle.
+    fn check_resolution(
+        &mut self,
+        candle: Candle,
+        indicators: Option<IndicatorRow>,
+        ctx: &mut Context<Self>,
+    ) {
+        // Updates may arrive while an exit is in progress
+        if self.exited {
+            return;
+        }
+
+        let closed = indicators.is_none();
+
+        let mut resolution_strategy = self.resolution_strategy.clone();
         let tp_candles_needed = resolution_strategy.n_candles_take_profit();
         let sl_candles_needed = resolution_strategy.n_candles_stop_loss();
         let orientation = self.setup.orientation.clone();
@@ -128,48 +168,81 @@ impl Handler<CandleAddedPayload> for Trade {
         let entered_at = self.setup.candle.timestamp.clone();
         let interval = self.setup.interval.clone();
         let db_addr = self.db_addr.clone();
-        let candle = msg.candle.clone();
-
-        // Multiply to avoid scenarios where quantity is slightly larger than
-        // account balance (caused by sudden price changes in time between
-        // account balance is checked and initial buy is performed).
-        let quantity = self.quantity * 0.99;
+        let initial_quantity = self.quantity;
+        let mut remaining_quantity = self.remaining_quantity;
+        let mut exits = self.exits.clone();
 
+        let n = tp_candles_needed.max(sl_candles_needed);
         let payload = RequestLatestCandlesPayload {
-            n: tp_candles_needed.max(sl_candles_needed),
+            n: if closed { n } else { n.saturating_sub(1) },
         };
 
         let fut = async move {
-            let candle_response = ts_addr
+            let mut candles = ts_addr
                 .send(payload)
                 .await
                 .expect("Unable to fetch timeseries data in Trade.")
-                .expect("Unable to parse LatestCandleResponse in Trade.");
+                .expect("Unable to parse LatestCandleResponse in Trade.")
+                .candles;
 
-            let end = candle_response.candles.len();
+            if let Some(indicators) = &indicators {
+                candles.push(candle.clone(), indicators);
+            }
 
-            let tp_candles = &candle_response.candles[end - tp_candles_needed..end];
-            let take_profit_reached = resolution_strategy
-                .take_profit_reached(&orientation, tp_candles)
-                .expect("Unable to perform take-profit check in Active Trade");
+            let actions = resolution_strategy
+                .exit_actions(&orientation, candles.view())
+                .expect("Unable to perform exit checks in Active Trade");
+            let executed = !actions.is_empty();
 
-            let sl_candles = &candle_response.candles[end - sl_candles_needed..end];
-            let stop_loss_reached = resolution_strategy
-                .stop_loss_reached(&orientation, sl_candles)
-                .expect("Unable to perform stop-loss check in Active Trade");
+            for action in actions {
+                let quantity = action.quantity(initial_quantity, remaining_quantity);
 
-            if take_profit_reached || stop_loss_reached {
-                let res = source.exit_trade(&symbol, quantity).await;
+                if quantity <= 0.0 {
+                    continue;
+                }
+
+                remaining_quantity -= quantity;
+                let is_final = remaining_quantity <= initial_quantity * REMAINDER_TOLERANCE;
+
+                // Multiply to avoid scenarios where quantity is slightly
+                // larger than account balance (caused by sudden price changes
+                // in time between account balance is checked and initial buy
+                // is performed).
+                let order_quantity = if is_final { quantity * 0.99 } else { quantity };
+                let res = source.exit_trade(&symbol, order_quantity).await;
 
                 match res {
-                    Ok(_) => println!("Trade successfully exited!"),
+                    Ok(_) => println!("Trade successfully exited {} {}!", quantity, symbol),
                     Err(e) => println!("Trade exit failed with error: {:#?}", e),
                 }
 
-                let exited_at = candle.timestamp.clone();
+                let db_trade_exit = DBTradeExit {
+                    id: Uuid::new_v4(),
+                    trade_id: id,
+                    exited_at: candle.timestamp,
+                    exit_price: candle.close,
+                    quantity,
+                    exit_fee: 0.0,
+                    kind: action.kind.to_string(),
+                };
+
+                exits.push(db_trade_exit.clone());
+                db_addr.do_send(AddDBTradeExitPayload { db_trade_exit });
+
+                if is_final {
+                    break;
+                }
+            }
+
+            let exited = remaining_quantity <= initial_quantity * REMAINDER_TOLERANCE;
+
+            if exited {
+                let exited_at = candle.timestamp;
 
                 println!("Exited at: {:#?}", exited_at);
-                let exit_price = candle.close.clone();
+                // Average over all fills keeps the P&L of the trade correct
+                // when scaling out.
+                let exit_price = DBTradeExit::average_price(&exits).unwrap_or(candle.close);
                 let bars_in_trade = get_bars_in_trade(&entered_at, &exited_at, &interval);
 
                 let finish_payload = FinishDBTradePayload {
@@ -181,12 +254,38 @@ impl Handler<CandleAddedPayload> for Trade {
 
                 db_addr.do_send(finish_payload);
                 self_addr.do_send(StopPayload);
-
-                // TODO: Handle/notify user in case selling was unsuccessful.
             }
+
+            // Forming candles may still be revised, state updated on them is
+            // only kept when it led to exits which can not be undone.
+            let commit = closed || executed;
+
+            // TODO: Handle/notify user in case selling was unsuccessful.
+            (
+                exited,
+                commit,
+                resolution_strategy,
+                remaining_quantity,
+                exits,
+            )
         };
 
-        ctx.spawn(fut.into_actor(self));
+        // Wait for the check to finish before handling further candles so a
+        // trade is never exited twice. The resolution strategy is written
+        // back as it may track state between checks.
+        let fut = fut.into_actor(self).map(
+            |(exited, commit, resolution_strategy, remaining_quantity, exits), trade, _ctx| {
+                trade.exited = exited;
+                trade.remaining_quantity = remaining_quantity;
+                trade.exits = exits;
+
+                if commit {
+                    trade.resolution_strategy = resolution_strategy;
+                }
+            },
+        );
+
+        ctx.wait(fut);
     }
 }
 
@@ -197,21 +296,7 @@ fn get_bars_in_trade(
 ) -> i32 {
     // Add seconds to ensure proper number of bars in scenarios where difference
     // between entered_at and exited_at does not exactly match the interval.
-    let duration = (*exited_at - *entered_at) + Duration::seconds(10);
-    let total_seconds = duration.num_seconds();
-
-    let bars = match interval {
-        Interval::Minute1 => total_seconds / 60,
-        Interval::Minute5 => total_seconds / (5 * 60),
-        Interval::Minute15 => total_seconds / (15 * 60),
-        Interval::Minute30 => total_seconds / (30 * 60),
-        Interval::Hour1 => total_seconds / (60 * 60),
-        Interval::Hour4 => total_seconds / (4 * 60 * 60),
-        Interval::Hour12 => total_seconds / (12 * 60 * 60),
-        Interval::Day1 => total_seconds / (24 * 60 * 60),
-        Interval::Day5 => total_seconds / (5 * 24 * 60 * 60),
-        Interval::Week1 => total_seconds / (7 * 24 * 60 * 60),
-    };
-
-    bars as i32
+    let exited_at = *exited_at + Duration::seconds(10);
+
+    interval.bars_between(*entered_at, exited_at) as i32
 }
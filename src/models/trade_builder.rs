@@ -156,6 +156,9 @@ impl TradeBuilder {
             timeseries,
             trading_strategy,
             db_addr,
+            exited: false,
+            remaining_quantity: quantity,
+            exits: vec![],
         };
 
         Ok(trade)
@@ -0,0 +1,213 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Intraday window in UTC. Windows whose end lies before their start wrap
+/// around midnight, e.g. 22:00 - 02:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Start is inclusive, end exclusive.
+    pub fn contains(&self, time: &NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= *time && *time < self.end
+        } else {
+            *time >= self.start || *time < self.end
+        }
+    }
+}
+
+/// Period around a scheduled event, e.g. a rate decision, during which no
+/// setups should trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blackout {
+    pub event: DateTime<Utc>,
+    pub minutes_before: i64,
+    pub minutes_after: i64,
+}
+
+impl Blackout {
+    pub fn new(event: DateTime<Utc>, minutes_before: i64, minutes_after: i64) -> Self {
+        Self {
+            event,
+            minutes_before,
+            minutes_after,
+        }
+    }
+
+    pub fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        let start = self.event - Duration::minutes(self.minutes_before);
+        let end = self.event + Duration::minutes(self.minutes_after);
+
+        start <= *timestamp && *timestamp <= end
+    }
+}
+
+/// # TradingSchedule
+///
+/// Restricts when a TradingStrategy may trigger setups. A timestamp is
+/// active when it falls on one of the weekdays, inside one of the time
+/// windows (any time if there are none), is not on an excluded date and not
+/// inside a blackout. All times are UTC.
+///
+/// The schedule of a strategy is enforced by the SetupFinder and the
+/// StrategyTester, strategies don't need to check it themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TradingSchedule {
+    pub weekdays: HashSet<Weekday>,
+    pub time_windows: Vec<TimeWindow>,
+    pub excluded_dates: HashSet<NaiveDate>,
+    pub blackouts: Vec<Blackout>,
+}
+
+impl Default for TradingSchedule {
+    fn default() -> Self {
+        Self::from_weekdays(Self::all_weekdays())
+    }
+}
+
+impl TradingSchedule {
+    pub fn all_weekdays() -> HashSet<Weekday> {
+        HashSet::from([
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ])
+    }
+
+    pub fn from_weekdays(weekdays: HashSet<Weekday>) -> Self {
+        Self {
+            weekdays,
+            time_windows: vec![],
+            excluded_dates: HashSet::new(),
+            blackouts: vec![],
+        }
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn weekdays_only() -> Self {
+        Self::from_weekdays(HashSet::from([
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ]))
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn time_window(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.time_windows.push(TimeWindow::new(start, end));
+        self
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn exclude_date(mut self, date: NaiveDate) -> Self {
+        self.excluded_dates.insert(date);
+        self
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn blackout(
+        mut self,
+        event: DateTime<Utc>,
+        minutes_before: i64,
+        minutes_after: i64,
+    ) -> Self {
+        self.blackouts
+            .push(Blackout::new(event, minutes_before, minutes_after));
+        self
+    }
+
+    /// Returns whether setups may trigger at the given timestamp.
+    pub fn is_active(&self, timestamp: &DateTime<Utc>) -> bool {
+        let time = timestamp.time();
+
+        self.weekdays.contains(&timestamp.weekday())
+            && !self.excluded_dates.contains(&timestamp.date_naive())
+            && (self.time_windows.is_empty()
+                || self
+                    .time_windows
+                    .iter()
+                    .any(|window| window.contains(&time)))
+            && !self
+                .blackouts
+                .iter()
+                .any(|blackout| blackout.contains(timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TradingSchedule;
+    use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+
+    #[test]
+    fn trading_schedule_weekdays_and_dates() {
+        // 2024-01-05 is a Friday
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 12, 0, 0).unwrap();
+        let saturday = Utc.with_ymd_and_hms(2024, 1, 6, 12, 0, 0).unwrap();
+
+        assert!(TradingSchedule::default().is_active(&saturday));
+        assert!(TradingSchedule::weekdays_only().is_active(&friday));
+        assert!(!TradingSchedule::weekdays_only().is_active(&saturday));
+
+        let schedule =
+            TradingSchedule::default().exclude_date(NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+        assert!(!schedule.is_active(&friday));
+        assert!(schedule.is_active(&saturday));
+    }
+
+    #[test]
+    fn trading_schedule_time_windows() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let at = |h, m| Utc.with_ymd_and_hms(2024, 1, 5, h, m, 0).unwrap();
+
+        let schedule = TradingSchedule::default().time_window(time(13, 30), time(20, 0));
+        assert!(!schedule.is_active(&at(13, 0)));
+        assert!(schedule.is_active(&at(13, 30)));
+        assert!(!schedule.is_active(&at(20, 0)));
+
+        let overnight = TradingSchedule::default().time_window(time(22, 0), time(2, 0));
+        assert!(overnight.is_active(&at(23, 0)));
+        assert!(overnight.is_active(&at(1, 0)));
+        assert!(!overnight.is_active(&at(12, 0)));
+    }
+
+    #[test]
+    fn trading_schedule_blackouts() {
+        let event = Utc.with_ymd_and_hms(2024, 1, 5, 14, 0, 0).unwrap();
+        let schedule = TradingSchedule::default().blackout(event, 30, 60);
+
+        let at = |h, m| Utc.with_ymd_and_hms(2024, 1, 5, h, m, 0).unwrap();
+        assert!(schedule.is_active(&at(13, 29)));
+        assert!(!schedule.is_active(&at(13, 30)));
+        assert!(!schedule.is_active(&at(15, 0)));
+        assert!(schedule.is_active(&at(15, 1)));
+    }
+
+    #[test]
+    fn trading_schedule_deserialize_defaults() {
+        let schedule: TradingSchedule = serde_json::from_str(
+            r#"{ "weekdays": ["Mon", "Fri"], "time_windows": [{ "start": "08:00:00", "end": "16:00:00" }] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(schedule.weekdays.len(), 2);
+        assert_eq!(schedule.time_windows.len(), 1);
+        assert!(schedule.blackouts.is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     models::{
-        candle::Candle, interval::Interval, setups::setup_builder::SetupBuilder,
-        strategy_orientation::StrategyOrientation, traits::has_min_length::HasMinLength,
+        candle_window::CandleView, interval::Interval, multi_timeframe_view::MultiTimeframeView,
+        setups::setup_builder::SetupBuilder, timeframe_requirement::TimeframeRequirement,
+        trading_schedule::TradingSchedule, traits::has_min_length::HasMinLength,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::resolution_strategy::ResolutionStrategy,
@@ -27,7 +28,25 @@ pub trait TradingStrategy: Display + Debug + RequiresIndicators + HasMinLength {
 
     /// Checks whether a new Setup has arisen upon the closure of the last
     /// candle provided.
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder>;
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder>;
+
+    /// Checks whether a new Setup has arisen upon the closure of the last
+    /// candle provided, with access to the candles of the additional
+    /// timeframes returned by `timeframe_requirements`. Strategies which only
+    /// use their main interval do not need to implement this.
+    fn check_last_for_setup_with_timeframes(
+        &mut self,
+        candles: CandleView<'_>,
+        _timeframes: &MultiTimeframeView,
+    ) -> Option<SetupBuilder> {
+        self.check_last_for_setup(candles)
+    }
+
+    /// Returns the additional intervals, and the indicators needed on them,
+    /// that this strategy needs besides its main interval.
+    fn timeframe_requirements(&self) -> Vec<TimeframeRequirement> {
+        vec![]
+    }
 
     /// Returns a boxed clone of the current TradingStrategy
     fn clone_box(&self) -> Box<dyn TradingStrategy>;
@@ -43,12 +62,20 @@ pub trait TradingStrategy: Display + Debug + RequiresIndicators + HasMinLength {
     /// Sets the provided ResolutionStrategy for this strategy.
     // fn set_resolution_strategy(&mut self, strat: ResolutionStrategy);
 
-    /// Returns the StrategyOrientation for this TradingStrategy
-    fn orientation(&self) -> StrategyOrientation;
-
     /// Returns the Interval to be used with this TradingStrategy
     fn interval(&self) -> Interval;
 
-    /// Returns the TradingDays to be used with this TradingStrategy
-    fn trading_days(&self) -> HashSet<Weekday>;
+    /// Returns the TradingDays to be used with this TradingStrategy, all days
+    /// unless overridden. Prefer overriding `trading_schedule`.
+    fn trading_days(&self) -> HashSet<Weekday> {
+        TradingSchedule::all_weekdays()
+    }
+
+    /// Returns the TradingSchedule restricting when setups may trigger. It is
+    /// enforced by the SetupFinder and StrategyTester on the last candle, so
+    /// strategies don't need to check it in `check_last_for_setup`. Defaults
+    /// to the weekdays of `trading_days`.
+    fn trading_schedule(&self) -> TradingSchedule {
+        TradingSchedule::from_weekdays(self.trading_days())
+    }
 }
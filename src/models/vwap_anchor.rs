@@ -0,0 +1,29 @@
+use crate::models::interval::Interval;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Period after which an anchored VWAP resets its accumulated values.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VWAPAnchor {
+    Daily,
+    Weekly,
+    Monthly,
+    Custom(u32), // Anchor period in minutes
+}
+
+impl VWAPAnchor {
+    pub fn to_interval(self) -> Interval {
+        match self {
+            VWAPAnchor::Daily => Interval::Day1,
+            VWAPAnchor::Weekly => Interval::Week1,
+            VWAPAnchor::Monthly => Interval::Month1,
+            VWAPAnchor::Custom(minutes) => Interval::from_minutes(minutes),
+        }
+    }
+
+    /// Returns the start of the anchor period the timestamp belongs to.
+    pub fn period_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        self.to_interval().bucket_start(timestamp)
+    }
+}
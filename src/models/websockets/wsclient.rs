@@ -2,16 +2,17 @@ use crate::{
     data_sources::datasource::DataSource,
     models::{
         interval::Interval, message_payloads::websocket_payload::WebsocketPayload,
-        net_version::NetVersion, timeseries::TimeSeries,
+        net_version::NetVersion,
     },
 };
-use actix::{Actor, Addr, AsyncContext, Context, Handler, WrapFuture};
+use actix::{Actor, AsyncContext, Context, Handler, Recipient, WrapFuture};
 
 pub struct WebsocketClient {
     source: DataSource,
     interval: Interval,
-    observers: Vec<Addr<TimeSeries>>,
+    observers: Vec<Recipient<WebsocketPayload>>,
     net: NetVersion,
+    intra_candle: bool,
 }
 
 impl Actor for WebsocketClient {
@@ -22,8 +23,12 @@ impl Actor for WebsocketClient {
         let source = self.source.clone();
         let interval = self.interval.clone();
         let net = self.net.clone();
+        let intra_candle = self.intra_candle;
         let fut = async move {
-            if let Err(e) = source.connect_ws(client, interval, &net).await {
+            if let Err(e) = source
+                .connect_ws(client, interval, intra_candle, &net)
+                .await
+            {
                 // TODO: Add logic for error handling, restarting client etc.
                 println!("Error: {}", e);
             }
@@ -58,10 +63,18 @@ impl WebsocketClient {
             interval,
             net,
             observers: vec![],
+            intra_candle: false,
         }
     }
 
-    pub fn add_observer(&mut self, observer: Addr<TimeSeries>) {
+    /// Enables forwarding updates of candles which are still forming, e.g. to
+    /// allow trades to be resolved before the candle closes. Only supported
+    /// by Bybit.
+    pub fn enable_intra_candle(&mut self) {
+        self.intra_candle = true;
+    }
+
+    pub fn add_observer(&mut self, observer: Recipient<WebsocketPayload>) {
         self.observers.push(observer);
     }
 }
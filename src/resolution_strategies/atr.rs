@@ -0,0 +1,200 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// # AtrResolution
+///
+/// Volatility adjusted resolution strategy. The stop-loss is placed
+/// `stop_multiplier` ATRs away from the entry, using the ATR of the setup
+/// candle, and the take-profit `reward_ratio` times that distance (R
+/// multiple) in the other direction.
+///
+/// ## Trading orientations
+/// - Long & Short
+///
+/// ## Suggested values
+/// - atr_len = 14
+/// - stop_multiplier = 2.0
+/// - reward_ratio = 2.0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtrResolution {
+    pub atr_len: usize,
+    pub stop_multiplier: f64,
+    pub reward_ratio: f64,
+    #[serde(skip)]
+    pub entry_price: Option<f64>,
+    /// Distance between entry and stop-loss, 1R
+    #[serde(skip)]
+    pub risk: Option<f64>,
+}
+
+impl AtrResolution {
+    pub fn new(atr_len: usize, stop_multiplier: f64, reward_ratio: f64) -> Self {
+        Self {
+            atr_len,
+            stop_multiplier,
+            reward_ratio,
+            entry_price: None,
+            risk: None,
+        }
+    }
+
+    /// Returns the stop-loss and take-profit prices.
+    pub fn levels(&self, orientation: &StrategyOrientation) -> Result<(f64, f64)> {
+        let entry_price = self.entry_price.context("Expected entry price")?;
+        let risk = self.risk.context("Expected risk")?;
+        let reward = risk * self.reward_ratio;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => (entry_price - risk, entry_price + reward),
+            StrategyOrientation::Short => (entry_price + risk, entry_price - reward),
+        })
+    }
+}
+
+impl IsResolutionStrategy for AtrResolution {
+    fn n_candles_stop_loss(&self) -> usize {
+        1
+    }
+
+    fn n_candles_take_profit(&self) -> usize {
+        1
+    }
+
+    fn stop_loss_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for ATR resolution."))?;
+        let (stop_loss, _) = self.levels(orientation)?;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => candle.close < stop_loss,
+            StrategyOrientation::Short => candle.close > stop_loss,
+        })
+    }
+
+    fn take_profit_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for ATR resolution."))?;
+        let (_, take_profit) = self.levels(orientation)?;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => candle.close > take_profit,
+            StrategyOrientation::Short => candle.close < take_profit,
+        })
+    }
+
+    fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
+        let atr = setup
+            .indicators
+            .get(&IndicatorType::ATR(self.atr_len))
+            .and_then(|indicator| indicator.as_atr())
+            .context(format!(
+                "Unable to find ATR of length {} on setup candle",
+                self.atr_len
+            ))?;
+
+        self.entry_price = Some(setup.candle.close);
+        self.risk = Some(atr.value * self.stop_multiplier);
+
+        Ok(())
+    }
+}
+
+impl RequiresIndicators for AtrResolution {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        vec![IndicatorType::ATR(self.atr_len)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AtrResolution;
+    use crate::{
+        indicators::{atr::ATR, indicator::Indicator, indicator_type::IndicatorType},
+        models::{
+            candle::Candle, candle_window::CandleWindow, setups::setup::Setup,
+            strategy_orientation::StrategyOrientation,
+        },
+        resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
+    };
+
+    fn setup(orientation: StrategyOrientation) -> Setup {
+        let mut setup = Setup::dummy();
+        setup.orientation = orientation;
+        setup.candle = Candle::dummy_from_arr(&[100.0]).remove(0);
+        setup.indicators.insert(
+            IndicatorType::ATR(14),
+            Indicator::ATR(Some(ATR {
+                len: 14,
+                value: 2.5,
+            })),
+        );
+        setup
+    }
+
+    #[test]
+    fn atr_resolution_levels() {
+        let mut resolution = AtrResolution::new(14, 2.0, 1.5);
+
+        resolution
+            .set_initial_values(&setup(StrategyOrientation::Long))
+            .unwrap();
+        assert_eq!(
+            resolution.levels(&StrategyOrientation::Long).unwrap(),
+            (95.0, 107.5)
+        );
+        assert_eq!(
+            resolution.levels(&StrategyOrientation::Short).unwrap(),
+            (105.0, 92.5)
+        );
+    }
+
+    #[test]
+    fn atr_resolution_reached() {
+        let orientation = StrategyOrientation::Short;
+        let mut resolution = AtrResolution::new(14, 2.0, 1.5);
+        resolution.set_initial_values(&setup(orientation)).unwrap();
+
+        let candles = CandleWindow::new(Candle::dummy_from_arr(&[104.0, 106.0, 92.0]));
+        let candles = candles.view();
+
+        assert!(!resolution
+            .stop_loss_reached(&orientation, candles.window(0, 1))
+            .unwrap());
+        assert!(resolution
+            .stop_loss_reached(&orientation, candles.window(1, 2))
+            .unwrap());
+        assert!(!resolution
+            .take_profit_reached(&orientation, candles.window(0, 1))
+            .unwrap());
+        assert!(resolution
+            .take_profit_reached(&orientation, candles.window(2, 3))
+            .unwrap());
+    }
+
+    #[test]
+    fn atr_resolution_requires_atr_on_setup() {
+        let mut resolution = AtrResolution::new(21, 2.0, 1.5);
+
+        assert!(resolution
+            .set_initial_values(&setup(StrategyOrientation::Long))
+            .is_err());
+    }
+}
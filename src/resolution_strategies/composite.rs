@@ -0,0 +1,635 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle::Candle, candle_window::CandleView, interval::Interval, setups::setup::Setup,
+        strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::{
+        exit_action::{ExitAction, ExitKind, ExitSize},
+        is_resolution_strategy::IsResolutionStrategy,
+        resolution_strategy::ResolutionStrategy,
+    },
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// How the rules of a `CompositeResolution` are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Logic {
+    /// All rules need to fire
+    And,
+    /// Any rule firing is enough
+    Or,
+}
+
+impl Logic {
+    fn combine(&self, reached: &[bool]) -> bool {
+        if reached.is_empty() {
+            return false;
+        }
+
+        match self {
+            Self::And => reached.iter().all(|reached| *reached),
+            Self::Or => reached.iter().any(|reached| *reached),
+        }
+    }
+}
+
+impl Display for Logic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::And => write!(f, " & "),
+            Self::Or => write!(f, " | "),
+        }
+    }
+}
+
+/// Moves the stop to the entry price, plus `offset` in %, once price has
+/// closed `trigger` % in profit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Breakeven {
+    pub trigger: f64,
+    #[serde(default)]
+    pub offset: f64,
+}
+
+/// # CompositeResolution
+///
+/// Combines any resolution strategies, take-profit rules and stop-loss
+/// rules each joined with their own AND/OR logic. Only the take-profit of
+/// the take-profit rules and the stop-loss of the stop-loss rules are used.
+/// Rule lists which are empty never fire.
+///
+/// Every rule sees every candle, whether or not the composite exits. Exits
+/// keep the sizes of the take-profit rules, so rules scaling out of a
+/// position (e.g. `ScaleOutResolution`) still exit in parts. With OR logic
+/// the exits of every rule firing are executed in order, with AND logic a
+/// single exit of the smallest size asked for by the rules is executed once
+/// all rules fire on the same candle. Stop-losses always exit the whole
+/// position.
+///
+/// On top of the rules the stop-loss can be given a time stop, exiting after
+/// `max_bars` bars in the trade, and a breakeven move. Both exit through the
+/// stop-loss regardless of the stop-loss logic.
+///
+/// ## Example
+///
+/// Take-profit on PMARP or BBWP with a percentage drawdown stop, as in
+/// `PmarpOrBbwpVsPercentageResolution`, but with a 20 bar time stop:
+///
+/// ```text
+/// CompositeResolution::new(Logic::Or, Logic::Or)
+///     .take_profit(pmarp_rule)
+///     .take_profit(bbwp_rule)
+///     .stop_loss(drawdown_rule)
+///     .max_bars(20)
+/// ```
+///
+/// ## Trading orientations
+/// - Those supported by all rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompositeResolution {
+    #[serde(default)]
+    pub take_profit: Vec<ResolutionStrategy>,
+    pub take_profit_logic: Logic,
+    #[serde(default)]
+    pub stop_loss: Vec<ResolutionStrategy>,
+    pub stop_loss_logic: Logic,
+    #[serde(default)]
+    pub max_bars: Option<usize>,
+    #[serde(default)]
+    pub breakeven: Option<Breakeven>,
+    #[serde(skip)]
+    pub entry_price: Option<f64>,
+    #[serde(skip)]
+    pub entered_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub interval: Option<Interval>,
+    /// Whether the breakeven stop has been armed
+    #[serde(skip)]
+    pub breakeven_active: bool,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl CompositeResolution {
+    pub fn new(take_profit_logic: Logic, stop_loss_logic: Logic) -> Self {
+        Self {
+            take_profit: vec![],
+            take_profit_logic,
+            stop_loss: vec![],
+            stop_loss_logic,
+            max_bars: None,
+            breakeven: None,
+            entry_price: None,
+            entered_at: None,
+            interval: None,
+            breakeven_active: false,
+        }
+    }
+
+    pub fn take_profit(mut self, rule: ResolutionStrategy) -> Self {
+        self.take_profit.push(rule);
+        self
+    }
+
+    pub fn stop_loss(mut self, rule: ResolutionStrategy) -> Self {
+        self.stop_loss.push(rule);
+        self
+    }
+
+    pub fn max_bars(mut self, max_bars: usize) -> Self {
+        self.max_bars = Some(max_bars);
+        self
+    }
+
+    pub fn breakeven(mut self, trigger: f64, offset: f64) -> Self {
+        self.breakeven = Some(Breakeven { trigger, offset });
+        self
+    }
+
+    fn time_stop_reached(&self, candle: &Candle) -> Result<bool> {
+        let Some(max_bars) = self.max_bars else {
+            return Ok(false);
+        };
+
+        let entered_at = self.entered_at.context("Expected entry timestamp")?;
+        let interval = self.interval.as_ref().context("Expected interval")?;
+
+        Ok(interval.bars_between(entered_at, candle.timestamp) >= max_bars as i64)
+    }
+
+    fn breakeven_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candle: &Candle,
+    ) -> Result<bool> {
+        let Some(breakeven) = self.breakeven else {
+            return Ok(false);
+        };
+
+        let entry_price = self.entry_price.context("Expected entry price")?;
+        let (profit, level) = match orientation {
+            StrategyOrientation::Long => (
+                candle.close / entry_price - 1.0,
+                entry_price * (1.0 + breakeven.offset / 100.0),
+            ),
+            StrategyOrientation::Short => (
+                1.0 - candle.close / entry_price,
+                entry_price * (1.0 - breakeven.offset / 100.0),
+            ),
+        };
+
+        // Check against the stop armed by previous candles before arming it
+        let reached = self.breakeven_active
+            && match orientation {
+                StrategyOrientation::Long => candle.close < level,
+                StrategyOrientation::Short => candle.close > level,
+            };
+
+        if profit * 100.0 >= breakeven.trigger {
+            self.breakeven_active = true;
+        }
+
+        Ok(reached)
+    }
+
+    /// Returns the take-profit exits of the take-profit rules combined with
+    /// the take-profit logic. Every rule sees every candle, so its state, e.g.
+    /// filled scale out targets, is kept even if its exits are not executed.
+    fn take_profit_actions(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<Vec<ExitAction>> {
+        let actions = self
+            .take_profit
+            .iter_mut()
+            .map(|rule| {
+                let n = rule.n_candles_take_profit().max(rule.n_candles_stop_loss());
+                let actions = rule.exit_actions(orientation, candles.tail(n))?;
+
+                Ok(actions
+                    .into_iter()
+                    .filter(|action| action.kind == ExitKind::TakeProfit)
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<Vec<ExitAction>>>>()?;
+
+        let reached: Vec<bool> = actions.iter().map(|actions| !actions.is_empty()).collect();
+
+        if !self.take_profit_logic.combine(&reached) {
+            return Ok(vec![]);
+        }
+
+        Ok(match self.take_profit_logic {
+            Logic::And => vec![smallest_exit(&actions)],
+            Logic::Or => actions.into_iter().flatten().collect(),
+        })
+    }
+}
+
+/// Returns the smallest of the exits of the rules, the part of the position
+/// every rule agrees to exit. The exits of a rule are added up, a full exit
+/// of any of them closes the whole position.
+fn smallest_exit(actions: &[Vec<ExitAction>]) -> ExitAction {
+    actions
+        .iter()
+        .map(|actions| {
+            actions
+                .iter()
+                .try_fold(0.0, |total, action| match action.size {
+                    ExitSize::Fraction(fraction) => Some(total + fraction),
+                    ExitSize::Remaining => None,
+                })
+        })
+        .fold(None, |smallest: Option<f64>, total| {
+            match (smallest, total) {
+                (Some(smallest), Some(total)) => Some(smallest.min(total)),
+                (smallest, total) => smallest.or(total),
+            }
+        })
+        .map_or(ExitAction::full(ExitKind::TakeProfit), |fraction| {
+            ExitAction::partial(ExitKind::TakeProfit, fraction)
+        })
+}
+
+impl IsResolutionStrategy for CompositeResolution {
+    fn n_candles_stop_loss(&self) -> usize {
+        self.stop_loss
+            .iter()
+            .map(|rule| rule.n_candles_stop_loss())
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Take-profit rules also check their stop-loss, which might need more
+    /// candles, see `take_profit_actions`.
+    fn n_candles_take_profit(&self) -> usize {
+        self.take_profit
+            .iter()
+            .map(|rule| rule.n_candles_take_profit().max(rule.n_candles_stop_loss()))
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn stop_loss_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for composite resolution."))?;
+
+        // Every rule is checked, without short-circuiting, so stateful rules
+        // such as trailing stops see every candle.
+        let reached = self
+            .stop_loss
+            .iter_mut()
+            .map(|rule| {
+                let n = rule.n_candles_stop_loss();
+                rule.stop_loss_reached(orientation, candles.tail(n))
+            })
+            .collect::<Result<Vec<bool>>>()?;
+
+        let breakeven_reached = self.breakeven_reached(orientation, candle)?;
+
+        Ok(self.stop_loss_logic.combine(&reached)
+            || breakeven_reached
+            || self.time_stop_reached(candle)?)
+    }
+
+    fn take_profit_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        // Every rule is checked, as for stop-losses
+        let reached = self
+            .take_profit
+            .iter_mut()
+            .map(|rule| {
+                let n = rule.n_candles_take_profit();
+                rule.take_profit_reached(orientation, candles.tail(n))
+            })
+            .collect::<Result<Vec<bool>>>()?;
+
+        Ok(self.take_profit_logic.combine(&reached))
+    }
+
+    /// Take-profit exits of the rules, see `CompositeResolution`, followed by
+    /// a full stop-loss exit if the stop-loss is reached on the same candle.
+    fn exit_actions(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<Vec<ExitAction>> {
+        let n = self.n_candles_stop_loss();
+        let mut actions = self.take_profit_actions(orientation, candles)?;
+
+        // Checked even after a take-profit so stateful stops see every candle
+        if self.stop_loss_reached(orientation, candles.tail(n))? {
+            actions.push(ExitAction::full(ExitKind::StopLoss));
+        }
+
+        Ok(actions)
+    }
+
+    fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
+        for rule in self.take_profit.iter_mut().chain(self.stop_loss.iter_mut()) {
+            rule.set_initial_values(setup)?;
+        }
+
+        self.entry_price = Some(setup.candle.close);
+        self.entered_at = Some(setup.candle.timestamp);
+        self.interval = Some(setup.interval.clone());
+        self.breakeven_active = false;
+
+        Ok(())
+    }
+}
+
+impl RequiresIndicators for CompositeResolution {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![];
+
+        for rule in self.take_profit.iter().chain(self.stop_loss.iter()) {
+            for indicator_type in rule.required_indicators() {
+                if !indicators.contains(&indicator_type) {
+                    indicators.push(indicator_type);
+                }
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for CompositeResolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |rules: &[ResolutionStrategy], logic: &Logic| {
+            rules
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect::<Vec<_>>()
+                .join(&logic.to_string())
+        };
+
+        let mut parts = vec![];
+
+        if !self.take_profit.is_empty() {
+            parts.push(format!(
+                "TP: {}",
+                join(&self.take_profit, &self.take_profit_logic)
+            ));
+        }
+
+        if !self.stop_loss.is_empty() {
+            parts.push(format!(
+                "SL: {}",
+                join(&self.stop_loss, &self.stop_loss_logic)
+            ));
+        }
+
+        if let Some(max_bars) = self.max_bars {
+            parts.push(format!("max {} bars", max_bars));
+        }
+
+        if let Some(breakeven) = self.breakeven {
+            parts.push(format!("breakeven at {}%", breakeven.trigger));
+        }
+
+        write!(f, "Composite({})", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompositeResolution, Logic};
+    use crate::{
+        indicators::{atr::ATR, indicator::Indicator, indicator_type::IndicatorType},
+        models::{
+            candle::Candle, candle_window::CandleWindow, interval::Interval, setups::setup::Setup,
+            strategy_orientation::StrategyOrientation,
+        },
+        resolution_strategies::{
+            atr::AtrResolution,
+            dynamic_pivot::DynamicPivotResolution,
+            exit_action::{ExitAction, ExitKind},
+            is_resolution_strategy::IsResolutionStrategy,
+            resolution_strategy::ResolutionStrategy,
+            scale_out::ScaleOutResolution,
+            trailing_stop::TrailingStopResolution,
+        },
+    };
+
+    const LONG: StrategyOrientation = StrategyOrientation::Long;
+
+    // Daily candles with the setup on the first one, entry at 100 and 1R = 5
+    fn candles(prices: &[f64]) -> (Setup, CandleWindow) {
+        let mut candles = CandleWindow::new(Candle::dummy_from_arr(prices));
+        candles.set_indicator(
+            0,
+            IndicatorType::ATR(14),
+            Indicator::ATR(Some(ATR {
+                len: 14,
+                value: 5.0,
+            })),
+        );
+
+        let mut setup = Setup::dummy();
+        setup.candle = candles.candles[0].clone();
+        setup.indicators = candles.view().row(0);
+        setup.interval = Interval::Day1;
+
+        (setup, candles)
+    }
+
+    fn atr(reward_ratio: f64) -> ResolutionStrategy {
+        ResolutionStrategy::Atr(AtrResolution::new(14, 1.0, reward_ratio))
+    }
+
+    /// Returns the index of the first candle reaching take-profit and
+    /// stop-loss respectively.
+    fn reached_at(
+        resolution: &mut CompositeResolution,
+        prices: &[f64],
+    ) -> (Option<usize>, Option<usize>) {
+        let (setup, candles) = candles(prices);
+        resolution.set_initial_values(&setup).unwrap();
+
+        let mut take_profit = None;
+        let mut stop_loss = None;
+
+        for i in 1..candles.candles.len() {
+            let window = candles.view().window(0, i + 1);
+
+            if take_profit.is_none() && resolution.take_profit_reached(&LONG, window).unwrap() {
+                take_profit = Some(i);
+            }
+
+            if stop_loss.is_none() && resolution.stop_loss_reached(&LONG, window).unwrap() {
+                stop_loss = Some(i);
+            }
+        }
+
+        (take_profit, stop_loss)
+    }
+
+    #[test]
+    fn composite_and_or_take_profit() {
+        let prices = [100.0, 106.0, 111.0, 116.0];
+
+        let mut or = CompositeResolution::new(Logic::Or, Logic::Or)
+            .take_profit(atr(1.0))
+            .take_profit(atr(3.0));
+        let mut and = CompositeResolution::new(Logic::And, Logic::Or)
+            .take_profit(atr(1.0))
+            .take_profit(atr(3.0));
+
+        assert_eq!(reached_at(&mut or, &prices), (Some(1), None));
+        assert_eq!(reached_at(&mut and, &prices), (Some(3), None));
+    }
+
+    #[test]
+    fn composite_and_or_stop_loss() {
+        let trailing = || ResolutionStrategy::TrailingStop(TrailingStopResolution::percentage(5.0));
+        let prices = [100.0, 110.0, 103.0, 94.0];
+
+        let mut or = CompositeResolution::new(Logic::Or, Logic::Or)
+            .stop_loss(atr(1.0))
+            .stop_loss(trailing());
+        let mut and = CompositeResolution::new(Logic::Or, Logic::And)
+            .stop_loss(atr(1.0))
+            .stop_loss(trailing());
+
+        assert_eq!(reached_at(&mut or, &prices), (None, Some(2)));
+        assert_eq!(reached_at(&mut and, &prices), (None, Some(3)));
+    }
+
+    #[test]
+    fn composite_time_stop_and_breakeven() {
+        let mut time_stop = CompositeResolution::new(Logic::Or, Logic::Or).max_bars(3);
+        assert_eq!(
+            reached_at(&mut time_stop, &[100.0, 101.0, 102.0, 103.0, 104.0]),
+            (None, Some(3))
+        );
+
+        let mut breakeven = CompositeResolution::new(Logic::Or, Logic::Or)
+            .stop_loss(atr(1.0))
+            .breakeven(2.0, 0.5);
+        assert_eq!(
+            reached_at(&mut breakeven, &[100.0, 101.0, 100.2, 103.0, 100.4]),
+            (None, Some(4))
+        );
+        assert_eq!(
+            breakeven.to_string(),
+            "Composite(SL: Atr(14,1,1), breakeven at 2%)"
+        );
+        assert_eq!(
+            CompositeResolution::new(Logic::Or, Logic::Or)
+                .take_profit(atr(1.0))
+                .to_string(),
+            "Composite(TP: Atr(14,1,1))"
+        );
+    }
+
+    fn scale_out() -> ResolutionStrategy {
+        ResolutionStrategy::ScaleOut(
+            ScaleOutResolution::new(AtrResolution::new(14, 1.0, 1.0))
+                .target(1.0, 0.5)
+                .target(2.0, 0.5),
+        )
+    }
+
+    /// Returns the exits of each candle after the setup candle.
+    fn exits(resolution: &mut CompositeResolution, prices: &[f64]) -> Vec<Vec<ExitAction>> {
+        let (setup, candles) = candles(prices);
+        resolution.set_initial_values(&setup).unwrap();
+
+        (1..candles.candles.len())
+            .map(|i| {
+                resolution
+                    .exit_actions(&LONG, candles.view().window(0, i + 1))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn composite_keeps_partial_exits() {
+        let prices = [100.0, 106.0, 108.0, 111.0];
+        let half = ExitAction::partial(ExitKind::TakeProfit, 0.5);
+        let rest = ExitAction::full(ExitKind::TakeProfit);
+
+        let mut or = CompositeResolution::new(Logic::Or, Logic::Or)
+            .take_profit(scale_out())
+            .stop_loss(atr(1.0));
+        assert_eq!(
+            exits(&mut or, &prices),
+            vec![vec![half], vec![], vec![rest]]
+        );
+
+        // The first target of the scale out is filled without the 2R target,
+        // so only the rest is exited once all rules agree
+        let mut and = CompositeResolution::new(Logic::And, Logic::Or)
+            .take_profit(scale_out())
+            .take_profit(atr(2.0));
+        assert_eq!(exits(&mut and, &prices), vec![vec![], vec![], vec![rest]]);
+        match &and.take_profit[0] {
+            ResolutionStrategy::ScaleOut(scale_out) => assert_eq!(scale_out.n_filled, 2),
+            _ => unreachable!(),
+        }
+
+        // Both rules fire on the first target, only its half is exited
+        let mut and = CompositeResolution::new(Logic::And, Logic::Or)
+            .take_profit(scale_out())
+            .take_profit(atr(1.0));
+        assert_eq!(
+            exits(&mut and, &prices),
+            vec![vec![half], vec![], vec![rest]]
+        );
+
+        let mut stopped = CompositeResolution::new(Logic::Or, Logic::Or)
+            .take_profit(scale_out())
+            .stop_loss(atr(1.0));
+        assert_eq!(
+            exits(&mut stopped, &[100.0, 106.0, 94.0]),
+            vec![vec![half], vec![ExitAction::full(ExitKind::StopLoss)]]
+        );
+    }
+
+    #[test]
+    fn composite_counts_stop_loss_candles_of_take_profit_rules() {
+        let rule = CompositeResolution::new(Logic::Or, Logic::Or)
+            .take_profit(atr(1.0))
+            .stop_loss(ResolutionStrategy::DynamicPivot(DynamicPivotResolution {
+                len: 10,
+            }));
+        let resolution = CompositeResolution::new(Logic::Or, Logic::Or)
+            .take_profit(ResolutionStrategy::Composite(rule))
+            .stop_loss(atr(1.0));
+
+        assert_eq!(resolution.n_candles_take_profit(), 11);
+        assert_eq!(resolution.n_candles_stop_loss(), 1);
+    }
+
+    #[test]
+    fn composite_skips_runtime_state() {
+        let mut resolution = CompositeResolution::new(Logic::Or, Logic::Or)
+            .stop_loss(atr(1.0))
+            .breakeven(2.0, 0.5);
+        reached_at(&mut resolution, &[100.0, 103.0]);
+        assert!(resolution.breakeven_active);
+
+        let json = serde_json::to_string(&resolution).unwrap();
+        assert!(!json.contains("breakeven_active"));
+        assert!(!json.contains("entry_price\":100"));
+
+        let resolution: CompositeResolution = serde_json::from_str(&json).unwrap();
+        assert!(!resolution.breakeven_active);
+        assert!(resolution.entry_price.is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -24,9 +24,9 @@ impl IsResolutionStrategy for DynamicPivotResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -36,9 +36,8 @@ impl IsResolutionStrategy for DynamicPivotResolution {
         }
 
         let ind_type = IndicatorType::DynamicPivot(self.len);
-        let pivots = candles[len - (self.len + 1)]
-            .indicators
-            .get(&ind_type)
+        let pivots = candles
+            .indicator(len - (self.len + 1), &ind_type)
             .context(format!(
                 "Unable to find DynamicPivots indicator of length {}",
                 self.len
@@ -59,9 +58,9 @@ impl IsResolutionStrategy for DynamicPivotResolution {
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -71,9 +70,8 @@ impl IsResolutionStrategy for DynamicPivotResolution {
         }
 
         let ind_type = IndicatorType::DynamicPivot(self.len);
-        let pivots = candles[len - (self.len + 1)]
-            .indicators
-            .get(&ind_type)
+        let pivots = candles
+            .indicator(len - (self.len + 1), &ind_type)
             .context(format!(
                 "Unable to find DynamicPivots indicator of length {}",
                 self.len
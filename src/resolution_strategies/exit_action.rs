@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Share of the initial quantity below which a position counts as closed,
+/// absorbs rounding of partial exits.
+pub const REMAINDER_TOLERANCE: f64 = 1e-9;
+
+/// Whether an exit takes profit or cuts losses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitKind {
+    TakeProfit,
+    StopLoss,
+}
+
+impl Display for ExitKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::TakeProfit => write!(f, "Take-profit"),
+            Self::StopLoss => write!(f, "Stop-loss"),
+        }
+    }
+}
+
+/// Portion of a position to exit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExitSize {
+    /// Fraction of the initial quantity, capped at what remains open.
+    Fraction(f64),
+    /// Everything still open, closing the trade.
+    Remaining,
+}
+
+/// Exit to be executed on the last candle, as returned by
+/// `IsResolutionStrategy::exit_actions`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExitAction {
+    pub kind: ExitKind,
+    pub size: ExitSize,
+}
+
+impl ExitAction {
+    pub fn full(kind: ExitKind) -> Self {
+        Self {
+            kind,
+            size: ExitSize::Remaining,
+        }
+    }
+
+    pub fn partial(kind: ExitKind, fraction: f64) -> Self {
+        Self {
+            kind,
+            size: ExitSize::Fraction(fraction),
+        }
+    }
+
+    /// Returns the quantity to exit given the initial and remaining quantity
+    /// of the position.
+    pub fn quantity(&self, initial: f64, remaining: f64) -> f64 {
+        match self.size {
+            ExitSize::Fraction(fraction) => (initial * fraction).min(remaining),
+            ExitSize::Remaining => remaining,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExitAction, ExitKind};
+
+    #[test]
+    fn exit_action_quantity() {
+        let half = ExitAction::partial(ExitKind::TakeProfit, 0.5);
+        let rest = ExitAction::full(ExitKind::StopLoss);
+
+        assert_eq!(half.quantity(10.0, 10.0), 5.0);
+        assert_eq!(half.quantity(10.0, 2.0), 2.0);
+        assert_eq!(rest.quantity(10.0, 2.5), 2.5);
+    }
+}
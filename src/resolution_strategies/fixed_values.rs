@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -25,9 +25,9 @@ impl IsResolutionStrategy for FixedValuesResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         if candles.len() < 1 {
             return Err(anyhow!("No candle provided for fixed value resolution."));
@@ -42,9 +42,9 @@ impl IsResolutionStrategy for FixedValuesResolution {
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         if candles.len() < 1 {
             return Err(anyhow!("No candle provided for fixed value resolution."));
@@ -1,6 +1,8 @@
 use crate::models::traits::requires_indicators::RequiresIndicators;
 use crate::{
-    models::{candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation},
+    models::{
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+    },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
 };
 use serde::{Deserialize, Serialize};
@@ -28,17 +30,17 @@ impl IsResolutionStrategy for InstantResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         _orientation: &StrategyOrientation,
-        _candles: &[Candle],
+        _candles: CandleView<'_>,
     ) -> anyhow::Result<bool> {
         Ok(true)
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         _orientation: &StrategyOrientation,
-        _candles: &[Candle],
+        _candles: CandleView<'_>,
     ) -> anyhow::Result<bool> {
         Ok(true)
     }
@@ -1,6 +1,9 @@
-use crate::models::{
-    candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
-    traits::requires_indicators::RequiresIndicators,
+use crate::{
+    models::{
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::exit_action::{ExitAction, ExitKind},
 };
 use anyhow::Result;
 
@@ -11,20 +14,46 @@ pub trait IsResolutionStrategy: RequiresIndicators {
     /// Number of candles needed to check whether take-profit has been reached
     fn n_candles_take_profit(&self) -> usize;
 
-    /// Check whether stop-loss has been reached for the last candle
+    /// Check whether stop-loss has been reached for the last candle. Called
+    /// for every new or updated candle while the trade is open, which allows
+    /// stateful stops such as trailing stops to track price between calls.
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool>;
 
-    /// Check whether take-profit has been reached for the last candle
+    /// Check whether take-profit has been reached for the last candle. Called
+    /// on the same candles as `stop_loss_reached`, so stateful targets may
+    /// track price between calls as well.
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool>;
 
     /// Set initial values from setup if applicable
     fn set_initial_values(&mut self, setup: &Setup) -> Result<()>;
+
+    /// Returns the exits to execute for the last candle, in order. Candles
+    /// should cover the larger of `n_candles_take_profit` and
+    /// `n_candles_stop_loss`. By default the full position is exited once
+    /// take-profit or stop-loss is reached, strategies scaling out of
+    /// positions return partial exits instead.
+    fn exit_actions(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<Vec<ExitAction>> {
+        let tp_candles = candles.tail(self.n_candles_take_profit());
+        let sl_candles = candles.tail(self.n_candles_stop_loss());
+
+        if self.take_profit_reached(orientation, tp_candles)? {
+            Ok(vec![ExitAction::full(ExitKind::TakeProfit)])
+        } else if self.stop_loss_reached(orientation, sl_candles)? {
+            Ok(vec![ExitAction::full(ExitKind::StopLoss)])
+        } else {
+            Ok(vec![])
+        }
+    }
 }
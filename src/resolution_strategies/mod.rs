@@ -1,4 +1,7 @@
+pub mod atr;
+pub mod composite;
 pub mod dynamic_pivot;
+pub mod exit_action;
 pub mod fixed_values;
 pub mod instant_resolution;
 pub mod is_resolution_strategy;
@@ -6,3 +9,5 @@ pub mod percentage;
 pub mod pmarp_or_bbwp_vs_percentage;
 pub mod pmarp_vs_percentage;
 pub mod resolution_strategy;
+pub mod scale_out;
+pub mod trailing_stop;
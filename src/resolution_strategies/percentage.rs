@@ -1,18 +1,40 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+/// # PercentageResolution
+///
+/// Fixed percentage targets relative to the close of the setup candle. The
+/// take-profit is reached when the high (low for shorts) moves `take_profit`
+/// percent in favour of the trade, the stop-loss when the close moves
+/// `drawdown` percent against it.
+///
+/// ## Trading orientations
+/// - Long & Short
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PercentageResolution {
-    initial_value: f64,
-    take_profit: f64,
-    drawdown: f64,
+    pub take_profit: f64,
+    pub drawdown: f64,
+    #[serde(skip)]
+    pub initial_value: Option<f64>,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl PercentageResolution {
+    pub fn new(take_profit: f64, drawdown: f64) -> Self {
+        Self {
+            take_profit,
+            drawdown,
+            initial_value: None,
+        }
+    }
 }
 
 impl IsResolutionStrategy for PercentageResolution {
@@ -25,49 +47,47 @@ impl IsResolutionStrategy for PercentageResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
-        if candles.is_empty() {
-            return Err(anyhow!("No candle provided for percentage resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for percentage resolution."))?;
+        let initial_value = self.initial_value.context("Expected initial value")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => {
-                candle.close < self.initial_value * (1.0 - self.drawdown / 100.0)
+                candle.close < initial_value * (1.0 - self.drawdown / 100.0)
             }
             StrategyOrientation::Short => {
-                candle.close > self.initial_value * (1.0 + self.drawdown / 100.0)
+                candle.close > initial_value * (1.0 + self.drawdown / 100.0)
             }
         })
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
-        if candles.is_empty() {
-            return Err(anyhow!("No candle provided for percentage resolution."));
-        }
-
-        let candle = &candles[0];
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for percentage resolution."))?;
+        let initial_value = self.initial_value.context("Expected initial value")?;
 
         Ok(match orientation {
             StrategyOrientation::Long => {
-                candle.high > self.initial_value * (1.0 + self.take_profit / 100.0)
+                candle.high > initial_value * (1.0 + self.take_profit / 100.0)
             }
             StrategyOrientation::Short => {
-                candle.low < self.initial_value * (1.0 - self.take_profit / 100.0)
+                candle.low < initial_value * (1.0 - self.take_profit / 100.0)
             }
         })
     }
 
     fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
-        self.initial_value = setup.candle.close;
+        self.initial_value = Some(setup.candle.close);
 
         Ok(())
     }
@@ -78,3 +98,68 @@ impl RequiresIndicators for PercentageResolution {
         vec![]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PercentageResolution;
+    use crate::{
+        models::{
+            candle::Candle, candle_window::CandleWindow, setups::setup::Setup,
+            strategy_orientation::StrategyOrientation,
+        },
+        resolution_strategies::{
+            is_resolution_strategy::IsResolutionStrategy, resolution_strategy::ResolutionStrategy,
+        },
+    };
+
+    fn setup() -> Setup {
+        let mut setup = Setup::dummy();
+        setup.candle = Candle::dummy_from_arr(&[100.0]).remove(0);
+        setup
+    }
+
+    fn candles(prices: &[f64]) -> CandleWindow {
+        CandleWindow::new(Candle::dummy_from_arr(prices))
+    }
+
+    #[test]
+    fn percentage_resolution_reached() {
+        for orientation in [StrategyOrientation::Long, StrategyOrientation::Short] {
+            let mut resolution = PercentageResolution::new(10.0, 5.0);
+
+            assert!(resolution
+                .stop_loss_reached(&orientation, candles(&[100.0]).view())
+                .is_err());
+
+            resolution.set_initial_values(&setup()).unwrap();
+
+            let (target, stop) = match orientation {
+                StrategyOrientation::Long => (111.0, 94.0),
+                StrategyOrientation::Short => (89.0, 106.0),
+            };
+            let flat = candles(&[100.0]);
+
+            assert!(resolution
+                .take_profit_reached(&orientation, candles(&[target]).view())
+                .unwrap());
+            assert!(resolution
+                .stop_loss_reached(&orientation, candles(&[stop]).view())
+                .unwrap());
+            assert!(!resolution
+                .take_profit_reached(&orientation, flat.view())
+                .unwrap());
+            assert!(!resolution
+                .stop_loss_reached(&orientation, flat.view())
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn percentage_resolution_strategy() {
+        let resolution: ResolutionStrategy =
+            serde_json::from_str(r#"{ "Percentage": { "take_profit": 10.0, "drawdown": 5.0 } }"#)
+                .unwrap();
+
+        assert_eq!(resolution.to_string(), "Percentage(10%,5%)");
+    }
+}
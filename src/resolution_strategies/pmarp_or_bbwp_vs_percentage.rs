@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, ma_type::MAType, setups::setup::Setup,
+        candle_window::CandleView, fixed_point::FixedPoint, ma_type::MAType, setups::setup::Setup,
         strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -9,6 +9,9 @@ use crate::{
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
+// The BBWP does not depend on the number of standard deviations
+const BBWP_STD_N: f64 = 1.0;
+
 /// # PmarpOrBbwpVsPercentageResolution
 ///
 /// Resolution strategy which utilizes pmarp or bbwp for take-profit resolution
@@ -48,9 +51,9 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -68,9 +71,9 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
         if len == 0 {
@@ -79,19 +82,27 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
             ));
         }
 
-        let ind_type =
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type);
-        let pmarp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let ind_type = IndicatorType::PMARP(
+            self.pmarp_len,
+            self.pmarp_lookback,
+            self.pmarp_len,
+            self.pmarp_ma_type,
+        );
+        let pmarp = candles
+            .indicator(len - 1, &ind_type)
             .context("Unable to get pmarp for pmarp vs % resolution.")?
             .as_pmarp()
             .context("Unable to convert indicator to pmarp in pmarp or bbwp vs % resolution")?;
 
-        let ind_type = IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback);
-        let bbwp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let ind_type = IndicatorType::BBWP(
+            self.bbwp_len,
+            self.bbwp_lookback,
+            self.bbwp_sma_len,
+            FixedPoint::new(BBWP_STD_N),
+            MAType::SMA,
+        );
+        let bbwp = candles
+            .indicator(len - 1, &ind_type)
             .context("Unable to get bbwp for pmarp or bbwp vs % resolution.")?
             .as_bbwp()
             .context("Unable to convert indicator to bbwp in pmarp or bbwp vs % resolution")?;
@@ -114,8 +125,19 @@ impl IsResolutionStrategy for PmarpOrBbwpVsPercentageResolution {
 impl RequiresIndicators for PmarpOrBbwpVsPercentageResolution {
     fn required_indicators(&self) -> Vec<IndicatorType> {
         vec![
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type),
-            IndicatorType::BBWP(self.bbwp_len, self.bbwp_lookback),
+            IndicatorType::PMARP(
+                self.pmarp_len,
+                self.pmarp_lookback,
+                self.pmarp_len,
+                self.pmarp_ma_type,
+            ),
+            IndicatorType::BBWP(
+                self.bbwp_len,
+                self.bbwp_lookback,
+                self.bbwp_sma_len,
+                FixedPoint::new(BBWP_STD_N),
+                MAType::SMA,
+            ),
         ]
     }
 }
@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, ma_type::MAType, setups::setup::Setup,
+        candle_window::CandleView, ma_type::MAType, setups::setup::Setup,
         strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
@@ -42,9 +42,9 @@ impl IsResolutionStrategy for PmarpVsPercentageResolution {
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
 
@@ -62,21 +62,24 @@ impl IsResolutionStrategy for PmarpVsPercentageResolution {
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         let len = candles.len();
         if len == 0 {
             return Err(anyhow!("No candle passed for pmarp vs % resolution."));
         }
 
-        let ind_type =
-            IndicatorType::PMARP(self.pmarp_len, self.pmarp_lookback, self.pmarp_ma_type);
+        let ind_type = IndicatorType::PMARP(
+            self.pmarp_len,
+            self.pmarp_lookback,
+            self.pmarp_len,
+            self.pmarp_ma_type,
+        );
 
-        let pmarp = candles[len - 1]
-            .indicators
-            .get(&ind_type)
+        let pmarp = candles
+            .indicator(len - 1, &ind_type)
             .context("Unable to get pmarp for pmarp vs % resolution.")?
             .as_pmarp()
             .context("Unable to convert indicator to pmarp in pmarp vs % resolution")?;
@@ -99,6 +102,7 @@ impl RequiresIndicators for PmarpVsPercentageResolution {
         vec![IndicatorType::PMARP(
             self.pmarp_len,
             self.pmarp_lookback,
+            self.pmarp_len,
             self.pmarp_ma_type,
         )]
     }
@@ -1,14 +1,17 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
         traits::requires_indicators::RequiresIndicators,
     },
     resolution_strategies::{
-        dynamic_pivot::DynamicPivotResolution, fixed_values::FixedValuesResolution,
+        atr::AtrResolution, composite::CompositeResolution, dynamic_pivot::DynamicPivotResolution,
+        exit_action::ExitAction, fixed_values::FixedValuesResolution,
         instant_resolution::InstantResolution, is_resolution_strategy::IsResolutionStrategy,
+        percentage::PercentageResolution,
         pmarp_or_bbwp_vs_percentage::PmarpOrBbwpVsPercentageResolution,
-        pmarp_vs_percentage::PmarpVsPercentageResolution,
+        pmarp_vs_percentage::PmarpVsPercentageResolution, scale_out::ScaleOutResolution,
+        trailing_stop::TrailingStopResolution,
     },
 };
 use anyhow::Result;
@@ -22,6 +25,11 @@ pub enum ResolutionStrategy {
     PmarpVsPercentage(PmarpVsPercentageResolution),
     PmarpOrBbwpVsPercentage(PmarpOrBbwpVsPercentageResolution),
     Instant(InstantResolution),
+    TrailingStop(TrailingStopResolution),
+    Atr(AtrResolution),
+    Percentage(PercentageResolution),
+    ScaleOut(ScaleOutResolution),
+    Composite(CompositeResolution),
 }
 
 impl IsResolutionStrategy for ResolutionStrategy {
@@ -32,6 +40,11 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.n_candles_stop_loss(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pbvp) => pbvp.n_candles_stop_loss(),
             ResolutionStrategy::Instant(i) => i.n_candles_stop_loss(),
+            ResolutionStrategy::TrailingStop(ts) => ts.n_candles_stop_loss(),
+            ResolutionStrategy::Atr(atr) => atr.n_candles_stop_loss(),
+            ResolutionStrategy::Percentage(p) => p.n_candles_stop_loss(),
+            ResolutionStrategy::ScaleOut(so) => so.n_candles_stop_loss(),
+            ResolutionStrategy::Composite(c) => c.n_candles_stop_loss(),
         }
     }
 
@@ -42,13 +55,18 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.n_candles_take_profit(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.n_candles_take_profit(),
             ResolutionStrategy::Instant(i) => i.n_candles_take_profit(),
+            ResolutionStrategy::TrailingStop(ts) => ts.n_candles_take_profit(),
+            ResolutionStrategy::Atr(atr) => atr.n_candles_take_profit(),
+            ResolutionStrategy::Percentage(p) => p.n_candles_take_profit(),
+            ResolutionStrategy::ScaleOut(so) => so.n_candles_take_profit(),
+            ResolutionStrategy::Composite(c) => c.n_candles_take_profit(),
         }
     }
 
     fn stop_loss_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         match self {
             ResolutionStrategy::DynamicPivot(dp) => dp.stop_loss_reached(orientation, candles),
@@ -60,13 +78,18 @@ impl IsResolutionStrategy for ResolutionStrategy {
                 pvp.stop_loss_reached(orientation, candles)
             }
             ResolutionStrategy::Instant(i) => i.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::TrailingStop(ts) => ts.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::Atr(atr) => atr.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::Percentage(p) => p.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::ScaleOut(so) => so.stop_loss_reached(orientation, candles),
+            ResolutionStrategy::Composite(c) => c.stop_loss_reached(orientation, candles),
         }
     }
 
     fn take_profit_reached(
-        &self,
+        &mut self,
         orientation: &StrategyOrientation,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<bool> {
         match self {
             ResolutionStrategy::DynamicPivot(dp) => dp.take_profit_reached(orientation, candles),
@@ -78,6 +101,11 @@ impl IsResolutionStrategy for ResolutionStrategy {
                 pvp.take_profit_reached(orientation, candles)
             }
             ResolutionStrategy::Instant(i) => i.take_profit_reached(orientation, candles),
+            ResolutionStrategy::TrailingStop(ts) => ts.take_profit_reached(orientation, candles),
+            ResolutionStrategy::Atr(atr) => atr.take_profit_reached(orientation, candles),
+            ResolutionStrategy::Percentage(p) => p.take_profit_reached(orientation, candles),
+            ResolutionStrategy::ScaleOut(so) => so.take_profit_reached(orientation, candles),
+            ResolutionStrategy::Composite(c) => c.take_profit_reached(orientation, candles),
         }
     }
 
@@ -88,6 +116,32 @@ impl IsResolutionStrategy for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.set_initial_values(setup),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.set_initial_values(setup),
             ResolutionStrategy::Instant(i) => i.set_initial_values(setup),
+            ResolutionStrategy::TrailingStop(ts) => ts.set_initial_values(setup),
+            ResolutionStrategy::Atr(atr) => atr.set_initial_values(setup),
+            ResolutionStrategy::Percentage(p) => p.set_initial_values(setup),
+            ResolutionStrategy::ScaleOut(so) => so.set_initial_values(setup),
+            ResolutionStrategy::Composite(c) => c.set_initial_values(setup),
+        }
+    }
+
+    fn exit_actions(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<Vec<ExitAction>> {
+        match self {
+            ResolutionStrategy::DynamicPivot(dp) => dp.exit_actions(orientation, candles),
+            ResolutionStrategy::FixedValues(fv) => fv.exit_actions(orientation, candles),
+            ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.exit_actions(orientation, candles),
+            ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => {
+                pvp.exit_actions(orientation, candles)
+            }
+            ResolutionStrategy::Instant(i) => i.exit_actions(orientation, candles),
+            ResolutionStrategy::TrailingStop(ts) => ts.exit_actions(orientation, candles),
+            ResolutionStrategy::Atr(atr) => atr.exit_actions(orientation, candles),
+            ResolutionStrategy::Percentage(p) => p.exit_actions(orientation, candles),
+            ResolutionStrategy::ScaleOut(so) => so.exit_actions(orientation, candles),
+            ResolutionStrategy::Composite(c) => c.exit_actions(orientation, candles),
         }
     }
 }
@@ -100,6 +154,11 @@ impl RequiresIndicators for ResolutionStrategy {
             ResolutionStrategy::PmarpVsPercentage(pvp) => pvp.required_indicators(),
             ResolutionStrategy::PmarpOrBbwpVsPercentage(pvp) => pvp.required_indicators(),
             ResolutionStrategy::Instant(i) => i.required_indicators(),
+            ResolutionStrategy::TrailingStop(ts) => ts.required_indicators(),
+            ResolutionStrategy::Atr(atr) => atr.required_indicators(),
+            ResolutionStrategy::Percentage(p) => p.required_indicators(),
+            ResolutionStrategy::ScaleOut(so) => so.required_indicators(),
+            ResolutionStrategy::Composite(c) => c.required_indicators(),
         }
     }
 }
@@ -132,6 +191,30 @@ impl Display for ResolutionStrategy {
                 )
             }
             Self::Instant(_) => write!(f, "Instant"),
+            Self::TrailingStop(ts) => match ts.activation {
+                Some(activation) => write!(f, "TrailingStop({},{}%)", ts.distance, activation),
+                None => write!(f, "TrailingStop({})", ts.distance),
+            },
+            Self::Atr(atr) => write!(
+                f,
+                "Atr({},{},{})",
+                atr.atr_len, atr.stop_multiplier, atr.reward_ratio
+            ),
+            Self::Percentage(p) => write!(f, "Percentage({}%,{}%)", p.take_profit, p.drawdown),
+            Self::ScaleOut(so) => {
+                let targets = so
+                    .targets
+                    .iter()
+                    .map(|target| format!("{}@{}R", target.fraction, target.r_multiple))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                match so.trailing_stop.as_ref() {
+                    Some(ts) => write!(f, "ScaleOut({},trail {})", targets, ts.distance),
+                    None => write!(f, "ScaleOut({})", targets),
+                }
+            }
+            Self::Composite(c) => write!(f, "{}", c),
         }
     }
 }
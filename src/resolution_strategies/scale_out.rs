@@ -0,0 +1,304 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle::Candle, candle_window::CandleView, setups::setup::Setup,
+        strategy_orientation::StrategyOrientation, traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::{
+        atr::AtrResolution,
+        exit_action::{ExitAction, ExitKind},
+        is_resolution_strategy::IsResolutionStrategy,
+        trailing_stop::TrailingStopResolution,
+    },
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Partial take-profit at a multiple of the initial risk (R).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScaleOutTarget {
+    pub r_multiple: f64,
+    /// Fraction of the initial quantity to exit
+    pub fraction: f64,
+}
+
+/// # ScaleOutResolution
+///
+/// Scales out of a position in steps, e.g. 50% at 1R, 25% at 2R and trail
+/// the rest. Risk (1R) and the initial stop-loss are given by an
+/// `AtrResolution`. Once the first target is filled the remaining position
+/// is protected by the trailing stop if one is set, otherwise the initial
+/// stop stays in place and the last target exits everything left.
+///
+/// ## Trading orientations
+/// - Long & Short
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleOutResolution {
+    pub risk: AtrResolution,
+    pub targets: Vec<ScaleOutTarget>,
+    #[serde(default)]
+    pub trailing_stop: Option<TrailingStopResolution>,
+    /// Number of targets filled so far
+    #[serde(skip)]
+    pub n_filled: usize,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl ScaleOutResolution {
+    pub fn new(risk: AtrResolution) -> Self {
+        Self {
+            risk,
+            targets: vec![],
+            trailing_stop: None,
+            n_filled: 0,
+        }
+    }
+
+    /// Adds a target, targets should be added in increasing order.
+    pub fn target(mut self, r_multiple: f64, fraction: f64) -> Self {
+        self.targets.push(ScaleOutTarget {
+            r_multiple,
+            fraction,
+        });
+        self
+    }
+
+    pub fn trail_rest(mut self, trailing_stop: TrailingStopResolution) -> Self {
+        self.trailing_stop = Some(trailing_stop);
+        self
+    }
+
+    fn target_reached(
+        &self,
+        target: &ScaleOutTarget,
+        orientation: &StrategyOrientation,
+        candle: &Candle,
+    ) -> Result<bool> {
+        let entry_price = self
+            .risk
+            .entry_price
+            .ok_or(anyhow!("Expected entry price"))?;
+        let risk = self.risk.risk.ok_or(anyhow!("Expected risk"))?;
+
+        Ok(match orientation {
+            StrategyOrientation::Long => candle.close > entry_price + target.r_multiple * risk,
+            StrategyOrientation::Short => candle.close < entry_price - target.r_multiple * risk,
+        })
+    }
+}
+
+impl IsResolutionStrategy for ScaleOutResolution {
+    fn n_candles_stop_loss(&self) -> usize {
+        1
+    }
+
+    fn n_candles_take_profit(&self) -> usize {
+        1
+    }
+
+    /// Trailing stop once the first target is filled, initial stop before.
+    fn stop_loss_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        // The trailing stop is updated on every candle so it trails the best
+        // close since entry once it takes over.
+        let trailing_reached = match self.trailing_stop.as_mut() {
+            Some(trailing_stop) => Some(trailing_stop.stop_loss_reached(orientation, candles)?),
+            None => None,
+        };
+
+        match trailing_reached {
+            Some(reached) if self.n_filled > 0 => Ok(reached),
+            _ => self.risk.stop_loss_reached(orientation, candles),
+        }
+    }
+
+    /// Whether the last target has been reached.
+    fn take_profit_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for scale out resolution."))?;
+
+        match self.targets.last() {
+            Some(target) => self.target_reached(target, orientation, candle),
+            None => Ok(false),
+        }
+    }
+
+    fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
+        let total: f64 = self.targets.iter().map(|target| target.fraction).sum();
+
+        if total > 1.0 + f64::EPSILON {
+            return Err(anyhow!(
+                "Scale out targets exit {}% of the position, at most 100% allowed",
+                total * 100.0
+            ));
+        }
+
+        self.n_filled = 0;
+        self.risk.set_initial_values(setup)?;
+
+        if let Some(trailing_stop) = self.trailing_stop.as_mut() {
+            trailing_stop.set_initial_values(setup)?;
+        }
+
+        Ok(())
+    }
+
+    fn exit_actions(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<Vec<ExitAction>> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for scale out resolution."))?;
+
+        if self.stop_loss_reached(orientation, candles)? {
+            return Ok(vec![ExitAction::full(ExitKind::StopLoss)]);
+        }
+
+        let mut actions = vec![];
+
+        while let Some(target) = self.targets.get(self.n_filled).copied() {
+            if !self.target_reached(&target, orientation, candle)? {
+                break;
+            }
+
+            self.n_filled += 1;
+
+            let is_last = self.n_filled == self.targets.len();
+            if is_last && self.trailing_stop.is_none() {
+                actions.push(ExitAction::full(ExitKind::TakeProfit));
+            } else {
+                actions.push(ExitAction::partial(ExitKind::TakeProfit, target.fraction));
+            }
+        }
+
+        Ok(actions)
+    }
+}
+
+impl RequiresIndicators for ScaleOutResolution {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = self.risk.required_indicators();
+
+        if let Some(trailing_stop) = self.trailing_stop.as_ref() {
+            for indicator_type in trailing_stop.required_indicators() {
+                if !indicators.contains(&indicator_type) {
+                    indicators.push(indicator_type);
+                }
+            }
+        }
+
+        indicators
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScaleOutResolution;
+    use crate::{
+        indicators::{atr::ATR, indicator::Indicator, indicator_type::IndicatorType},
+        models::{
+            candle::Candle, candle_window::CandleWindow, setups::setup::Setup,
+            strategy_orientation::StrategyOrientation,
+        },
+        resolution_strategies::{
+            atr::AtrResolution,
+            exit_action::{ExitAction, ExitKind},
+            is_resolution_strategy::IsResolutionStrategy,
+            trailing_stop::TrailingStopResolution,
+        },
+    };
+
+    // Entry at 100 with an ATR of 5, 1R = 5
+    fn setup() -> Setup {
+        let mut setup = Setup::dummy();
+        setup.candle = Candle::dummy_from_arr(&[100.0]).remove(0);
+        setup.indicators.insert(
+            IndicatorType::ATR(14),
+            Indicator::ATR(Some(ATR {
+                len: 14,
+                value: 5.0,
+            })),
+        );
+        setup
+    }
+
+    fn exit_actions(resolution: &mut ScaleOutResolution, prices: &[f64]) -> Vec<Vec<ExitAction>> {
+        resolution.set_initial_values(&setup()).unwrap();
+
+        let candles = CandleWindow::new(Candle::dummy_from_arr(prices));
+
+        (0..candles.candles.len())
+            .map(|i| {
+                resolution
+                    .exit_actions(&StrategyOrientation::Long, candles.view().window(i, i + 1))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scale_out_targets_and_trailing_rest() {
+        let mut resolution = ScaleOutResolution::new(AtrResolution::new(14, 1.0, 1.0))
+            .target(1.0, 0.5)
+            .target(2.0, 0.25)
+            .trail_rest(TrailingStopResolution::percentage(5.0));
+
+        let actions = exit_actions(&mut resolution, &[102.0, 106.0, 111.0, 120.0, 113.0]);
+
+        assert!(actions[0].is_empty());
+        assert_eq!(
+            actions[1],
+            vec![ExitAction::partial(ExitKind::TakeProfit, 0.5)]
+        );
+        assert_eq!(
+            actions[2],
+            vec![ExitAction::partial(ExitKind::TakeProfit, 0.25)]
+        );
+        assert!(actions[3].is_empty());
+        // Trailing 5% below the close of 120
+        assert_eq!(actions[4], vec![ExitAction::full(ExitKind::StopLoss)]);
+    }
+
+    #[test]
+    fn scale_out_without_trailing_stop() {
+        let mut resolution = ScaleOutResolution::new(AtrResolution::new(14, 1.0, 1.0))
+            .target(1.0, 0.5)
+            .target(2.0, 0.5);
+
+        let actions = exit_actions(&mut resolution, &[111.0]);
+
+        assert_eq!(
+            actions[0],
+            vec![
+                ExitAction::partial(ExitKind::TakeProfit, 0.5),
+                ExitAction::full(ExitKind::TakeProfit)
+            ]
+        );
+
+        let mut resolution = ScaleOutResolution::new(AtrResolution::new(14, 1.0, 1.0))
+            .target(1.0, 0.5)
+            .target(2.0, 0.5);
+
+        let actions = exit_actions(&mut resolution, &[101.0, 94.0]);
+        assert_eq!(actions[1], vec![ExitAction::full(ExitKind::StopLoss)]);
+    }
+
+    #[test]
+    fn scale_out_rejects_oversized_targets() {
+        let mut resolution = ScaleOutResolution::new(AtrResolution::new(14, 1.0, 1.0))
+            .target(1.0, 0.75)
+            .target(2.0, 0.5);
+
+        assert!(resolution.set_initial_values(&setup()).is_err());
+    }
+}
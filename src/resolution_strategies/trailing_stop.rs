@@ -0,0 +1,265 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView, setups::setup::Setup, strategy_orientation::StrategyOrientation,
+        traits::requires_indicators::RequiresIndicators,
+    },
+    resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
+};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Distance between the best close since entry and the trailing stop.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TrailingDistance {
+    /// Given in %
+    Percentage(f64),
+    /// Multiple of the ATR of the last candle
+    Atr { len: usize, multiplier: f64 },
+}
+
+impl Display for TrailingDistance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Percentage(percentage) => write!(f, "{}%", percentage),
+            Self::Atr { len, multiplier } => write!(f, "{}xATR({})", multiplier, len),
+        }
+    }
+}
+
+/// # TrailingStopResolution
+///
+/// Stop-loss which trails the best close since entry, the high-water mark
+/// for longs and low-water mark for shorts, by a fixed percentage or a
+/// multiple of the ATR. The stop only ever moves in favour of the trade.
+///
+/// With an activation threshold the stop only starts trailing once price
+/// has closed the given percentage in profit, until then it never fires.
+/// Never takes profit by itself, exits happen through the stop.
+///
+/// ## Trading orientations
+/// - Long & Short
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrailingStopResolution {
+    pub distance: TrailingDistance,
+    /// Profit in % required before the stop becomes active
+    #[serde(default)]
+    pub activation: Option<f64>,
+    #[serde(skip)]
+    pub entry_price: Option<f64>,
+    /// Best close since entry
+    #[serde(skip)]
+    pub extreme: Option<f64>,
+    /// Current stop price, only set once the stop is active
+    #[serde(skip)]
+    pub stop: Option<f64>,
+}
+
+impl TrailingStopResolution {
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn percentage(percentage: f64) -> Self {
+        Self::new(TrailingDistance::Percentage(percentage))
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn atr(len: usize, multiplier: f64) -> Self {
+        Self::new(TrailingDistance::Atr { len, multiplier })
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn with_activation(mut self, activation: f64) -> Self {
+        self.activation = Some(activation);
+        self
+    }
+
+    fn new(distance: TrailingDistance) -> Self {
+        Self {
+            distance,
+            activation: None,
+            entry_price: None,
+            extreme: None,
+            stop: None,
+        }
+    }
+
+    fn distance(&self, candles: CandleView<'_>, extreme: f64) -> Result<f64> {
+        match self.distance {
+            TrailingDistance::Percentage(percentage) => Ok(extreme * percentage / 100.0),
+            TrailingDistance::Atr { len, multiplier } => {
+                let atr = candles
+                    .last_indicator(&IndicatorType::ATR(len))
+                    .and_then(|indicator| indicator.as_atr())
+                    .context(format!("Unable to find ATR of length {}", len))?;
+
+                Ok(atr.value * multiplier)
+            }
+        }
+    }
+
+    fn is_activated(&self, orientation: &StrategyOrientation, extreme: f64) -> Result<bool> {
+        let Some(activation) = self.activation else {
+            return Ok(true);
+        };
+
+        let entry_price = self.entry_price.context("Expected entry price")?;
+        let profit = match orientation {
+            StrategyOrientation::Long => extreme / entry_price - 1.0,
+            StrategyOrientation::Short => 1.0 - extreme / entry_price,
+        };
+
+        Ok(profit * 100.0 >= activation)
+    }
+}
+
+impl IsResolutionStrategy for TrailingStopResolution {
+    fn n_candles_stop_loss(&self) -> usize {
+        1
+    }
+
+    fn n_candles_take_profit(&self) -> usize {
+        1
+    }
+
+    fn stop_loss_reached(
+        &mut self,
+        orientation: &StrategyOrientation,
+        candles: CandleView<'_>,
+    ) -> Result<bool> {
+        let candle = candles
+            .last()
+            .ok_or(anyhow!("No candle provided for trailing stop resolution."))?;
+
+        // Check against the stop set by previous candles before moving it
+        let reached = match (orientation, self.stop) {
+            (StrategyOrientation::Long, Some(stop)) => candle.close < stop,
+            (StrategyOrientation::Short, Some(stop)) => candle.close > stop,
+            (_, None) => false,
+        };
+
+        let extreme = match (orientation, self.extreme) {
+            (_, None) => candle.close,
+            (StrategyOrientation::Long, Some(extreme)) => extreme.max(candle.close),
+            (StrategyOrientation::Short, Some(extreme)) => extreme.min(candle.close),
+        };
+        self.extreme = Some(extreme);
+
+        if self.is_activated(orientation, extreme)? {
+            let distance = self.distance(candles, extreme)?;
+            let stop = match orientation {
+                StrategyOrientation::Long => extreme - distance,
+                StrategyOrientation::Short => extreme + distance,
+            };
+
+            // Stops only move in favour of the trade
+            self.stop = Some(match (orientation, self.stop) {
+                (_, None) => stop,
+                (StrategyOrientation::Long, Some(prev)) => prev.max(stop),
+                (StrategyOrientation::Short, Some(prev)) => prev.min(stop),
+            });
+        }
+
+        Ok(reached)
+    }
+
+    fn take_profit_reached(
+        &mut self,
+        _orientation: &StrategyOrientation,
+        _candles: CandleView<'_>,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn set_initial_values(&mut self, setup: &Setup) -> Result<()> {
+        self.entry_price = Some(setup.candle.close);
+        self.extreme = Some(setup.candle.close);
+        self.stop = None;
+
+        Ok(())
+    }
+}
+
+impl RequiresIndicators for TrailingStopResolution {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        match self.distance {
+            TrailingDistance::Percentage(_) => vec![],
+            TrailingDistance::Atr { len, .. } => vec![IndicatorType::ATR(len)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrailingStopResolution;
+    use crate::{
+        models::{
+            candle::Candle, candle_window::CandleWindow, setups::setup::Setup,
+            strategy_orientation::StrategyOrientation,
+        },
+        resolution_strategies::is_resolution_strategy::IsResolutionStrategy,
+    };
+
+    /// Returns the index of the candle on which the stop was reached.
+    fn stopped_at(
+        resolution: &mut TrailingStopResolution,
+        orientation: StrategyOrientation,
+        prices: &[f64],
+    ) -> Option<usize> {
+        let candles = CandleWindow::new(Candle::dummy_from_arr(prices));
+        let candles = candles.view();
+        let mut setup = Setup::dummy();
+        setup.candle = candles[0].clone();
+        setup.orientation = orientation;
+        resolution.set_initial_values(&setup).unwrap();
+
+        (1..candles.len()).find(|&i| {
+            resolution
+                .stop_loss_reached(&orientation, candles.window(i, i + 1))
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn trailing_stop_percentage_long() {
+        let mut resolution = TrailingStopResolution::percentage(10.0);
+        let prices = [100.0, 105.0, 120.0, 110.0, 107.0, 130.0, 116.0];
+
+        // Stop at 108 after the close at 120
+        assert_eq!(
+            stopped_at(&mut resolution, StrategyOrientation::Long, &prices),
+            Some(4)
+        );
+        assert_eq!(resolution.extreme, Some(120.0));
+    }
+
+    #[test]
+    fn trailing_stop_percentage_short() {
+        let mut resolution = TrailingStopResolution::percentage(10.0);
+        let prices = [100.0, 95.0, 80.0, 85.0, 89.0];
+
+        // Stop at 88 after the close at 80
+        assert_eq!(
+            stopped_at(&mut resolution, StrategyOrientation::Short, &prices),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn trailing_stop_activation() {
+        let prices = [100.0, 95.0, 89.0, 104.0, 100.0];
+
+        let mut resolution = TrailingStopResolution::percentage(10.0);
+        assert_eq!(
+            stopped_at(&mut resolution, StrategyOrientation::Long, &prices),
+            Some(2)
+        );
+
+        // Inactive until 4% in profit, then trailing from 104 with a 93.6 stop
+        let mut resolution = TrailingStopResolution::percentage(10.0).with_activation(4.0);
+        assert_eq!(
+            stopped_at(&mut resolution, StrategyOrientation::Long, &prices),
+            None
+        );
+        assert!((resolution.stop.unwrap() - 93.6).abs() < 1e-9);
+    }
+}
@@ -26,9 +26,7 @@ pub async fn true_once() -> Result<()> {
         .await?;
     ts.validate_candles_on_add = false;
 
-    for indicator_type in strategy.required_indicators() {
-        ts.add_indicator(indicator_type)?;
-    }
+    ts.add_indicators(&strategy.required_indicators())?;
 
     let ts_addr = ts.start();
 
@@ -50,7 +48,7 @@ pub async fn true_once() -> Result<()> {
 
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
-    wsclient.add_observer(ts_addr);
+    wsclient.add_observer(ts_addr.recipient());
     wsclient.start();
 
     loop {
@@ -71,9 +69,7 @@ pub async fn true_always() -> Result<()> {
         .await?;
     ts.validate_candles_on_add = false;
 
-    for indicator_type in strategy.required_indicators() {
-        ts.add_indicator(indicator_type)?;
-    }
+    ts.add_indicators(&strategy.required_indicators())?;
 
     let ts_addr = ts.start();
 
@@ -95,7 +91,7 @@ pub async fn true_always() -> Result<()> {
 
     // Start websocket client
     let mut wsclient = WebsocketClient::new(source, interval, net);
-    wsclient.add_observer(ts_addr);
+    wsclient.add_observer(ts_addr.recipient());
     wsclient.start();
 
     loop {
@@ -1,12 +1,14 @@
 use crate::{
     data_sources::datasource::DataSource,
     indicators::{
-        bbwp::BBWP, ema::EMA, indicator::Indicator, indicator_type::IndicatorType,
-        is_indicator::IsIndicator, pmar::PMAR, pmarp::PMARP, sma::SMA,
+        bbwp::BBWP, ema::EMA, indicator::Indicator, indicator_columns::IndicatorRow,
+        indicator_type::IndicatorType, is_indicator::IsIndicator, pmar::PMAR, pmarp::PMARP,
+        sma::SMA,
     },
     models::{
-        candle::Candle, database::db::DB, interval::Interval, ma_type::MAType,
-        message_payloads::websocket_payload::WebsocketPayload,
+        candle::Candle, database::db::DB, fixed_point::FixedPoint, interval::Interval,
+        ma_type::MAType,
+        message_payloads::add_candle_with_indicators_payload::AddCandleWithIndicatorsPayload,
         setups::setup_finder_builder::SetupFinderBuilder, timeseries_builder::TimeSeriesBuilder,
         traits::trading_strategy::TradingStrategy,
     },
@@ -19,7 +21,7 @@ use std::time::Duration;
 use tokio::time::sleep;
 
 pub async fn run() -> Result<()> {
-    let (base_candle, entry_candle) = get_initial_candles();
+    let ((base_candle, base_indicators), (entry_candle, entry_indicators)) = get_initial_candles();
 
     let mut ts = TimeSeriesBuilder::new()
         .symbol(DEFAULT_SYMBOL.to_string())
@@ -27,7 +29,7 @@ pub async fn run() -> Result<()> {
         .validate_candles_on_add(false)
         .build();
 
-    ts.add_candle(&base_candle)?;
+    ts.add_candle_with(&base_candle, &base_indicators)?;
     let ts_addr = ts.start();
 
     let db = DB::new().await?;
@@ -44,76 +46,80 @@ pub async fn run() -> Result<()> {
 
     sleep(Duration::new(1, 0)).await;
 
-    ts_addr.do_send(candle_payload(&entry_candle));
+    ts_addr.do_send(candle_payload(&entry_candle, entry_indicators));
 
     sleep(Duration::new(2, 0)).await;
 
-    let (len, lookback, ma_type) = PMARP::default_args().pmarp_res()?;
-    let pmarp_type = IndicatorType::PMARP(len, lookback, ma_type);
+    let (len, lookback, sma_len, ma_type) = PMARP::default_args().pmarp_res()?;
+    let pmarp_type = IndicatorType::PMARP(len, lookback, sma_len, ma_type);
 
-    let mut next_candle = Candle::dyn_dummy_from_prev(&entry_candle, Interval::Day1);
+    let next_candle = Candle::dyn_dummy_from_prev(&entry_candle, Interval::Day1);
+    let mut next_indicators = IndicatorRow::new();
     let next_pmarp = Indicator::PMARP(Some(PMARP::new(0.65, len, lookback)));
-    next_candle
-        .indicators
-        .insert(pmarp_type.clone(), next_pmarp);
-    let next_candle = fill_candle(next_candle);
+    next_indicators.insert(pmarp_type.clone(), next_pmarp);
+    let next_indicators = fill_indicators(next_indicators);
 
-    ts_addr.do_send(candle_payload(&next_candle));
+    ts_addr.do_send(candle_payload(&next_candle, next_indicators));
 
-    let mut exit_candle = Candle::dyn_dummy_from_prev(&next_candle, Interval::Day1);
+    let exit_candle = Candle::dyn_dummy_from_prev(&next_candle, Interval::Day1);
+    let mut exit_indicators = IndicatorRow::new();
     let exit_pmarp = Indicator::PMARP(Some(PMARP::new(0.8, len, lookback)));
-    exit_candle.indicators.insert(pmarp_type, exit_pmarp);
+    exit_indicators.insert(pmarp_type, exit_pmarp);
 
     sleep(Duration::new(2, 0)).await;
 
-    ts_addr.do_send(candle_payload(&exit_candle));
+    ts_addr.do_send(candle_payload(&exit_candle, exit_indicators));
 
     loop {
         sleep(Duration::from_secs(1)).await;
     }
 }
 
-fn candle_payload(candle: &Candle) -> WebsocketPayload {
-    WebsocketPayload {
-        candle: Some(candle.clone()),
-        ok: true,
-        message: None,
+fn candle_payload(candle: &Candle, indicators: IndicatorRow) -> AddCandleWithIndicatorsPayload {
+    AddCandleWithIndicatorsPayload {
+        candle: candle.clone(),
+        indicators,
     }
 }
 
-fn get_initial_candles() -> (Candle, Candle) {
+fn get_initial_candles() -> ((Candle, IndicatorRow), (Candle, IndicatorRow)) {
     let (ema_21_type, ema_55_type, pmar_type, bbwp_type) = get_indicator_types();
     let (ema_21, ema_55, pmar, bbwp_prev, bbwp_curr) = get_indicators();
 
     let base_candle = Candle::dummy_data(1, "alternating", 1000.0);
-    let mut base_candle = base_candle[0].clone();
+    let base_candle = base_candle[0].clone();
+    let mut base_indicators = IndicatorRow::new();
 
-    base_candle.indicators.insert(
+    base_indicators.insert(
         bbwp_type.clone(),
         Indicator::BBWP(Some(bbwp_prev.as_bbwp().expect("Expected bbwp."))),
     );
 
     let entry_candle = Candle::dummy_data(1, "alternating", 1000.0);
-    let mut entry_candle = entry_candle[0].clone();
+    let entry_candle = entry_candle[0].clone();
+    let mut entry_indicators = IndicatorRow::new();
 
-    entry_candle.indicators.insert(
+    entry_indicators.insert(
         ema_21_type,
         Indicator::EMA(Some(ema_21.as_ema().expect("Expected ema."))),
     );
-    entry_candle.indicators.insert(
+    entry_indicators.insert(
         ema_55_type,
         Indicator::EMA(Some(ema_55.as_ema().expect("Expected ema."))),
     );
-    entry_candle.indicators.insert(
+    entry_indicators.insert(
         pmar_type,
         Indicator::PMAR(Some(pmar.as_pmar().expect("Expected pmar."))),
     );
-    entry_candle.indicators.insert(
+    entry_indicators.insert(
         bbwp_type,
         Indicator::BBWP(Some(bbwp_curr.as_bbwp().expect("Expected bbwp."))),
     );
 
-    (base_candle, entry_candle)
+    (
+        (base_candle, base_indicators),
+        (entry_candle, entry_indicators),
+    )
 }
 
 fn get_indicator_types() -> (IndicatorType, IndicatorType, IndicatorType, IndicatorType) {
@@ -123,7 +129,7 @@ fn get_indicator_types() -> (IndicatorType, IndicatorType, IndicatorType, Indica
 
     let pmar_type = IndicatorType::PMAR(55, MAType::EMA);
 
-    let bbwp_type = IndicatorType::BBWP(13, 252);
+    let bbwp_type = IndicatorType::BBWP(13, 252, 5, FixedPoint::new(1.0), MAType::SMA);
 
     (ema_21_type, ema_55_type, pmar_type, bbwp_type)
 }
@@ -167,26 +173,26 @@ fn get_indicators() -> (Indicator, Indicator, Indicator, Indicator, Indicator) {
     )
 }
 
-fn fill_candle(mut candle: Candle) -> Candle {
+fn fill_indicators(mut indicators: IndicatorRow) -> IndicatorRow {
     let (ema_21_type, ema_55_type, pmar_type, bbwp_type) = get_indicator_types();
     let (ema_21, ema_55, pmar, _bbwp_prev, bbwp_curr) = get_indicators();
 
-    candle.indicators.insert(
+    indicators.insert(
         ema_21_type,
         Indicator::EMA(Some(ema_21.as_ema().expect("Expected ema."))),
     );
-    candle.indicators.insert(
+    indicators.insert(
         ema_55_type,
         Indicator::EMA(Some(ema_55.as_ema().expect("Expected ema."))),
     );
-    candle.indicators.insert(
+    indicators.insert(
         pmar_type,
         Indicator::PMAR(Some(pmar.as_pmar().expect("Expected pmar."))),
     );
-    candle.indicators.insert(
+    indicators.insert(
         bbwp_type,
         Indicator::BBWP(Some(bbwp_curr.as_bbwp().expect("Expected bbwp."))),
     );
 
-    candle
+    indicators
 }
@@ -2,9 +2,14 @@ use crate::{
     data_sources::datasource::DataSource,
     indicators::indicator_type::IndicatorType,
     models::{
-        database::db::DB, interval::Interval, net_version::NetVersion,
-        setups::setup_finder_builder::SetupFinderBuilder, timeseries::TimeSeries,
-        traits::trading_strategy::TradingStrategy, websockets::wsclient::WebsocketClient,
+        database::db::DB,
+        interval::Interval,
+        net_version::NetVersion,
+        resampler::Resampler,
+        setups::setup_finder_builder::SetupFinderBuilder,
+        timeseries::TimeSeries,
+        traits::{has_min_length::HasMinLength, trading_strategy::TradingStrategy},
+        websockets::wsclient::WebsocketClient,
     },
     trading_strategies::private::{kq_12::KQ12, kq_14::KQ14},
     utils::constants::DEFAULT_SYMBOL,
@@ -13,21 +18,29 @@ use actix::{Actor, Addr};
 use anyhow::Result;
 use futures_util::future::try_join_all;
 use indexmap::IndexSet;
-use std::{collections::HashMap, time::Duration};
+use rayon::prelude::*;
+use std::{collections::HashMap, env, time::Duration};
 use tokio::time::sleep;
 
 pub async fn run() -> Result<()> {
     // Define active strategies, this should be the only input
     let strats: Vec<Box<dyn TradingStrategy>> = vec![Box::new(KQ14::new()), Box::new(KQ12::new())];
 
-    start(strats).await?;
+    // Optionally let trades exit on forming candles instead of waiting for
+    // the candle to close
+    let intra_candle = env::var("INTRA_CANDLE_EXITS").is_ok_and(|enabled| enabled == "true");
+
+    start(strats, intra_candle).await?;
 
     loop {
         sleep(Duration::from_secs(1)).await;
     }
 }
 
-async fn start(mut strats: Vec<Box<dyn TradingStrategy>>) -> Result<Vec<Addr<TimeSeries>>> {
+async fn start(
+    mut strats: Vec<Box<dyn TradingStrategy>>,
+    intra_candle: bool,
+) -> Result<Vec<Addr<TimeSeries>>> {
     let source = DataSource::Bybit;
     let net = NetVersion::Mainnet;
 
@@ -43,6 +56,17 @@ async fn start(mut strats: Vec<Box<dyn TradingStrategy>>) -> Result<Vec<Addr<Tim
                 min_len = strat.min_length();
             }
 
+            // Include additional timeframes required by the strategy
+            for requirement in strat.timeframe_requirements() {
+                if !intervals.contains(&requirement.interval) {
+                    intervals.insert(requirement.interval.clone());
+                }
+
+                if requirement.min_length() > min_len {
+                    min_len = requirement.min_length();
+                }
+            }
+
             (intervals, min_len)
         },
     );
@@ -59,33 +83,32 @@ async fn start(mut strats: Vec<Box<dyn TradingStrategy>>) -> Result<Vec<Addr<Tim
     let mut indicator_map: HashMap<Interval, IndexSet<IndicatorType>> = HashMap::new();
 
     for strat in strats.iter() {
-        let interval = strat.interval();
+        let mut required = vec![(strat.interval(), strat.required_indicators())];
 
-        for indicator in strat.required_indicators().iter() {
-            if let Some(set) = indicator_map.get_mut(&interval) {
+        for requirement in strat.timeframe_requirements() {
+            required.push((requirement.interval, requirement.indicators));
+        }
+
+        for (interval, indicators) in required {
+            let set = indicator_map.entry(interval).or_default();
+
+            for indicator in indicators.iter() {
                 if !set.contains(indicator) {
-                    set.insert(indicator.clone());
+                    set.insert(*indicator);
                 }
-            } else {
-                let mut set = IndexSet::new();
-                set.insert(indicator.clone());
-                indicator_map.insert(interval.clone(), set);
             }
         }
     }
 
-    // TODO: Parallelize here
-    // Populate indicators for TimeSeries using the indicator map
-    for ts in timeseries.iter_mut() {
+    // Populate indicators for TimeSeries using the indicator map, each
+    // TimeSeries and its indicators are populated in parallel
+    timeseries.par_iter_mut().try_for_each(|ts| {
         let indicators = indicator_map
             .get(&ts.interval)
             .expect("Expected interval to have matching indicators.");
 
-        for indicator_type in indicators {
-            ts.add_indicator(*indicator_type)
-                .expect("Unable to add indicator to TimeSeries.");
-        }
-    }
+        ts.add_indicators(&indicators.iter().copied().collect::<Vec<_>>())
+    })?;
 
     // Find indexes matching strategies to now populated timeseries
     let strat_indices: Vec<(&mut Box<dyn TradingStrategy>, usize)> = strats
@@ -101,19 +124,34 @@ async fn start(mut strats: Vec<Box<dyn TradingStrategy>>) -> Result<Vec<Addr<Tim
         })
         .collect();
 
-    // Start timeseries listening via websockets for new data
+    // Start timeseries listening to a single 1 minute websocket stream,
+    // higher intervals are built from it by resamplers. When enabled, forming
+    // candles are forwarded as well so trades can exit before a candle closes.
+    let mut wsclient = WebsocketClient::new(source.clone(), Interval::Minute1, net);
+
+    if intra_candle {
+        wsclient.enable_intra_candle();
+    }
+
     let ts_addrs: Vec<Addr<TimeSeries>> = timeseries
         .into_iter()
         .map(|ts| {
-            let mut wsclient =
-                WebsocketClient::new(source.clone(), ts.interval.clone(), net.clone());
+            let interval = ts.interval.clone();
             let ts_addr = ts.start();
 
-            wsclient.add_observer(ts_addr.clone());
-            wsclient.start();
+            if interval == Interval::Minute1 {
+                wsclient.add_observer(ts_addr.clone().recipient());
+            } else {
+                let mut resampler = Resampler::new(Interval::Minute1, interval)
+                    .expect("Expected interval to be resamplable from 1 minute candles.");
+                resampler.add_observer(ts_addr.clone().recipient());
+                wsclient.add_observer(resampler.start().recipient());
+            }
+
             ts_addr
         })
         .collect();
+    wsclient.start();
 
     // Start DB connection
     let db = DB::new().await?;
@@ -126,13 +164,22 @@ async fn start(mut strats: Vec<Box<dyn TradingStrategy>>) -> Result<Vec<Addr<Tim
             strat,
             strat.interval()
         );
-        let sf = SetupFinderBuilder::new()
+        let mut sfb = SetupFinderBuilder::new()
             .strategy(strat.clone_box())
             .ts_addr(ts_addrs[*i].clone())
             .db_addr(db_addr.clone())
             .notifications_enabled(true)
             .live_trading_enabled(true)
-            .source(source.clone())
+            .source(source.clone());
+
+        for requirement in strat.timeframe_requirements() {
+            let j = intervals
+                .get_index_of(&requirement.interval)
+                .expect("Expected there to exist a timeseries with correct interval.");
+            sfb = sfb.timeframe_addr(requirement.interval, ts_addrs[j].clone());
+        }
+
+        let sf = sfb
             .build()
             .expect("Expected to successfully build SetupFinder.");
 
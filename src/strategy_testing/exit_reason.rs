@@ -0,0 +1,25 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// The reason a setup played out during a strategy test was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    /// The setup was held for the maximum number of bars allowed and was
+    /// force-closed on the close of the last bar.
+    MaxBars,
+    /// The candles ran out before the setup was resolved, the setup is marked
+    /// to market on the close of the last available candle.
+    EndOfData,
+}
+
+impl Display for ExitReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match *self {
+            Self::TakeProfit => write!(f, "Take-profit"),
+            Self::StopLoss => write!(f, "Stop-loss"),
+            Self::MaxBars => write!(f, "Max bars"),
+            Self::EndOfData => write!(f, "End of data"),
+        }
+    }
+}
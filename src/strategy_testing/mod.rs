@@ -1,3 +1,4 @@
+pub mod exit_reason;
 pub mod strategy_test_result;
 pub mod strategy_test_result_builder;
 pub mod strategy_tester;
@@ -3,6 +3,13 @@
 pub struct StrategyTestResult {
     pub accuracy: f64,
     pub n_setups: usize,
+    pub n_take_profits: usize,
+    pub n_stop_losses: usize,
+    pub n_max_bars_exits: usize,
+    pub n_open_at_end: usize,
+    // Setups that could not be played out, e.g. due to too few candles for the
+    // resolution strategy. These are not part of n_setups or the accuracy.
+    pub n_skipped: usize,
     pub avg_profitability: f64,
     // TODO: Add normalized average profitability that takes the time Interval
     // into consideration. A 0.5% gain on the 15min chart may actually be much
@@ -1,5 +1,5 @@
 use crate::{
-    strategy_testing::strategy_test_result::StrategyTestResult,
+    strategy_testing::{exit_reason::ExitReason, strategy_test_result::StrategyTestResult},
     utils::math::{sma, std},
 };
 
@@ -10,6 +10,11 @@ pub struct StrategyTestResultBuilder {
     pub n_setups: usize,
     pub n_wins: usize,
     pub n_losses: usize,
+    pub n_take_profits: usize,
+    pub n_stop_losses: usize,
+    pub n_max_bars_exits: usize,
+    pub n_open_at_end: usize,
+    pub n_skipped: usize,
     pub account_size: f64,
     pub wins: Vec<f64>,
     pub win_bars: Vec<usize>,
@@ -23,6 +28,11 @@ impl StrategyTestResultBuilder {
             n_setups: 0,
             n_wins: 0,
             n_losses: 0,
+            n_take_profits: 0,
+            n_stop_losses: 0,
+            n_max_bars_exits: 0,
+            n_open_at_end: 0,
+            n_skipped: 0,
             account_size: INITIAL_ACCOUNT_SIZE,
             wins: Vec::new(),
             win_bars: Vec::new(),
@@ -31,9 +41,16 @@ impl StrategyTestResultBuilder {
         }
     }
 
-    pub fn add_outcome(&mut self, outcome: f64, n_bars: usize) {
+    pub fn add_outcome(&mut self, outcome: f64, n_bars: usize, reason: ExitReason) {
         self.n_setups += 1;
 
+        match reason {
+            ExitReason::TakeProfit => self.n_take_profits += 1,
+            ExitReason::StopLoss => self.n_stop_losses += 1,
+            ExitReason::MaxBars => self.n_max_bars_exits += 1,
+            ExitReason::EndOfData => self.n_open_at_end += 1,
+        }
+
         if outcome > 0.0 {
             self.n_wins += 1;
             self.wins.push(outcome);
@@ -47,6 +64,11 @@ impl StrategyTestResultBuilder {
         self.account_size += self.account_size * outcome;
     }
 
+    /// Registers a setup that was found but could not be played out.
+    pub fn add_skipped(&mut self) {
+        self.n_skipped += 1;
+    }
+
     pub fn build(self) -> StrategyTestResult {
         let total_wins = self.wins.iter().sum::<f64>();
         let total_losses = self.losses.iter().sum::<f64>();
@@ -78,7 +100,11 @@ impl StrategyTestResultBuilder {
         } else {
             0.0
         };
-        let avg_profitability = (total_wins + total_losses) / self.n_setups as f64;
+        let avg_profitability = if self.n_setups > 0 {
+            (total_wins + total_losses) / self.n_setups as f64
+        } else {
+            0.0
+        };
 
         let f_win_bars: Vec<f64> = self.win_bars.iter().map(|b| *b as f64).collect();
         let f_loss_bars: Vec<f64> = self.loss_bars.iter().map(|b| *b as f64).collect();
@@ -86,6 +112,11 @@ impl StrategyTestResultBuilder {
         StrategyTestResult {
             accuracy,
             n_setups: self.n_setups,
+            n_take_profits: self.n_take_profits,
+            n_stop_losses: self.n_stop_losses,
+            n_max_bars_exits: self.n_max_bars_exits,
+            n_open_at_end: self.n_open_at_end,
+            n_skipped: self.n_skipped,
             avg_profitability,
             avg_win,
             avg_loss,
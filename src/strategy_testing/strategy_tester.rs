@@ -1,33 +1,79 @@
 use crate::{
-    models::{candle::Candle, interval::Interval, traits::trading_strategy::TradingStrategy},
+    models::{
+        candle_window::{CandleView, CandleWindow},
+        interval::Interval,
+        multi_timeframe_view::MultiTimeframeView,
+        strategy_orientation::StrategyOrientation,
+        traits::trading_strategy::TradingStrategy,
+    },
     resolution_strategies::{
-        is_resolution_strategy::IsResolutionStrategy, resolution_strategy::ResolutionStrategy,
+        exit_action::{ExitKind, REMAINDER_TOLERANCE},
+        is_resolution_strategy::IsResolutionStrategy,
+        resolution_strategy::ResolutionStrategy,
     },
     strategy_testing::{
-        strategy_test_result::StrategyTestResult,
+        exit_reason::ExitReason, strategy_test_result::StrategyTestResult,
         strategy_test_result_builder::StrategyTestResultBuilder,
     },
 };
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 
-pub struct StrategyTester;
+/// # StrategyTester
+///
+/// Plays out a TradingStrategy over historical candles. Every setup found is
+/// followed until its resolution strategy fires, the optional max holding
+/// period is reached or the candles run out. Setups still open at the end of
+/// the data are marked to market on the last close.
+///
+/// Strategies with additional timeframe requirements need the candles of
+/// those intervals to be provided through `timeframe`.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyTester {
+    max_bars: Option<usize>,
+    timeframes: HashMap<Interval, CandleWindow>,
+}
 
 impl StrategyTester {
+    pub fn new() -> Self {
+        Self {
+            max_bars: None,
+            timeframes: HashMap::new(),
+        }
+    }
+
+    /// Provides the candles of an additional interval required by the tested
+    /// strategy. The candles should cover the same period as the candles the
+    /// strategy is tested on and have their indicators populated.
+    pub fn timeframe(mut self, interval: Interval, candles: CandleWindow) -> Self {
+        self.timeframes.insert(interval, candles);
+        self
+    }
+
+    /// Sets the maximum number of bars a setup may be held. Once reached the
+    /// setup is force-closed on the close of that bar.
+    pub fn max_bars(mut self, max_bars: usize) -> Self {
+        self.max_bars = Some(max_bars);
+        self
+    }
+
     pub fn test_strategy(
+        &self,
         strat: &mut Box<dyn TradingStrategy>,
-        candles: &[Candle],
+        candles: CandleView<'_>,
     ) -> Result<StrategyTestResult> {
-        let orientation = strat.orientation();
+        let interval = strat.interval();
         let needed_candles = strat.candles_needed_for_setup();
+        let requirements = strat.timeframe_requirements();
+        let schedule = strat.trading_schedule();
         let mut result_builder = StrategyTestResultBuilder::new();
         let mut next_i = 0;
 
         println!("Starting Strategy test for {}", strat);
 
         // Loop over the needed candles to determine a setup and gather results.
-        for (i, window) in candles.windows(needed_candles).enumerate() {
-            // Bump index up to compensate for window size
-            let i = i + needed_candles;
+        for i in needed_candles..=candles.len() {
+            let window = candles.window(i - needed_candles, i);
 
             if i % 1000 == 0 {
                 println!("Testing Iteration {:#?}", i);
@@ -37,56 +83,107 @@ impl StrategyTester {
                 continue;
             }
 
-            let sb = strat.check_last_for_setup(&window);
+            let last = window.last().context("Expected candle in window.")?;
+
+            if !schedule.is_active(&last.timestamp) {
+                continue;
+            }
+
+            let sb = if requirements.is_empty() {
+                strat.check_last_for_setup(window)
+            } else {
+                let mut view = MultiTimeframeView::new();
+
+                for requirement in requirements.iter() {
+                    let tf_candles = self.timeframes.get(&requirement.interval).context(
+                        format!("No candles provided for interval {}", requirement.interval),
+                    )?;
+
+                    view.insert_aligned(
+                        &requirement.interval,
+                        tf_candles.view(),
+                        last,
+                        &interval,
+                        requirement.candles_needed,
+                    );
+                }
+
+                strat.check_last_for_setup_with_timeframes(window, &view)
+            };
 
             if sb.is_none() {
                 continue;
             };
 
             let sb = sb.context("Expected SetupBuilder.")?;
-            let setup = sb.symbol("TESTING").interval(&Interval::Day1).build()?;
+            let setup = sb
+                .indicators(window.row(window.len() - 1))
+                .symbol("TESTING")
+                .interval(&interval)
+                .build()?;
+            let orientation = setup.orientation;
 
             // Initialize resolution strategy
             let mut resolution_strategy = strat.default_resolution_strategy();
             let tp_candles_needed = resolution_strategy.n_candles_take_profit();
             let sl_candles_needed = resolution_strategy.n_candles_stop_loss();
-            resolution_strategy.set_initial_values(&setup)?;
+
+            // Setups which can not be played out are counted as skipped so
+            // they are visible in the result without affecting the accuracy.
+            if resolution_strategy.set_initial_values(&setup).is_err()
+                || i + 1 < tp_candles_needed.max(sl_candles_needed)
+                || i >= candles.len()
+            {
+                result_builder.add_skipped();
+                continue;
+            }
+
+            let n_candles_needed = tp_candles_needed.max(sl_candles_needed);
             let mut n_bars = 0;
+            // Share of the position still open and outcome realized by
+            // partial exits so far.
+            let mut remaining = 1.0;
+            let mut realized = 0.0;
 
             // Loop over upcoming candles to determine outcome of setup
             loop {
                 n_bars += 1;
-
-                // Max number of bars in a setup to avoid infinite loops
-                if n_bars > 100 {
-                    break;
-                };
-
                 let end = i + n_bars;
-                if end > candles.len() {
-                    break;
-                };
+                let last = &candles[end - 1];
+                let outcome = Self::outcome(&orientation, setup.candle.close, last.close);
 
-                let tp_candles = &candles[end - tp_candles_needed..end];
-                let take_profit_reached =
-                    resolution_strategy.take_profit_reached(&orientation, tp_candles)?;
+                let actions = resolution_strategy
+                    .exit_actions(&orientation, candles.window(end - n_candles_needed, end))?;
+                let mut exit_reason = None;
 
-                if take_profit_reached {
-                    let increase =
-                        tp_candles[tp_candles.len() - 1].close / setup.candle.close - 1.0;
-                    result_builder.add_outcome(increase, n_bars);
+                for action in actions {
+                    let fraction = action.quantity(1.0, remaining);
+                    realized += fraction * outcome;
+                    remaining -= fraction;
 
+                    if remaining <= REMAINDER_TOLERANCE {
+                        exit_reason = Some(match action.kind {
+                            ExitKind::TakeProfit => ExitReason::TakeProfit,
+                            ExitKind::StopLoss => ExitReason::StopLoss,
+                        });
+                        break;
+                    }
+                }
+
+                if let Some(exit_reason) = exit_reason {
+                    result_builder.add_outcome(realized, n_bars, exit_reason);
                     break;
                 }
 
-                let sl_candles = &candles[end - sl_candles_needed..end];
-                let stop_loss_reached =
-                    resolution_strategy.stop_loss_reached(&orientation, sl_candles)?;
+                if self.max_bars.is_some_and(|max_bars| n_bars >= max_bars) {
+                    let outcome = realized + remaining * outcome;
+                    result_builder.add_outcome(outcome, n_bars, ExitReason::MaxBars);
+                    break;
+                }
 
-                if stop_loss_reached {
-                    let decrease =
-                        sl_candles[sl_candles.len() - 1].close / setup.candle.close - 1.0;
-                    result_builder.add_outcome(decrease, n_bars);
+                if end >= candles.len() {
+                    let outcome = realized + remaining * outcome;
+                    result_builder.add_outcome(outcome, n_bars, ExitReason::EndOfData);
                     break;
                 }
             }
@@ -100,12 +197,232 @@ impl StrategyTester {
         Ok(result_builder.build())
     }
 
+    /// Returns the relative outcome of a setup entered at `entry` and exited
+    /// at `exit`, positive values being profitable for the given orientation.
+    fn outcome(orientation: &StrategyOrientation, entry: f64, exit: f64) -> f64 {
+        match orientation {
+            StrategyOrientation::Long => exit / entry - 1.0,
+            StrategyOrientation::Short => 1.0 - exit / entry,
+        }
+    }
+
     #[allow(dead_code)]
     pub fn by_strategies(
         _trading_strat: &Box<dyn TradingStrategy>,
         _resolution_strat: &ResolutionStrategy,
-        _candles: &[Candle],
+        _candles: CandleView<'_>,
     ) -> StrategyTestResult {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::StrategyTester;
+    use crate::{
+        indicators::{atr::ATR, indicator::Indicator, indicator_type::IndicatorType},
+        models::{
+            candle::Candle,
+            candle_window::CandleWindow,
+            interval::Interval,
+            ma_type::MAType,
+            orientation_mode::OrientationMode,
+            strategy_orientation::StrategyOrientation,
+            timeseries::TimeSeries,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+        },
+        resolution_strategies::{
+            atr::AtrResolution, fixed_values::FixedValuesResolution,
+            resolution_strategy::ResolutionStrategy, scale_out::ScaleOutResolution,
+            trailing_stop::TrailingStopResolution,
+        },
+        trading_strategies::public::{
+            ma_crossover::MACrossover, true_once_strategy::TrueOnceStrategy,
+            true_twice_strategy::TrueTwiceStrategy,
+        },
+    };
+    use chrono::{TimeZone, Utc};
+
+    // Wide enough to never fire on the test data
+    fn trailing() -> ResolutionStrategy {
+        ResolutionStrategy::TrailingStop(TrailingStopResolution::percentage(50.0))
+    }
+
+    #[test]
+    fn strategy_tester_max_bars() {
+        let mut strat: Box<dyn TradingStrategy> =
+            Box::new(TrueOnceStrategy::new().with_resolution_strategy(trailing()));
+        let candles = CandleWindow::new(Candle::dummy_from_arr(&[
+            100.0, 101.0, 102.0, 103.0, 104.0, 105.0,
+        ]));
+
+        let result = StrategyTester::new()
+            .max_bars(3)
+            .test_strategy(&mut strat, candles.view())
+            .unwrap();
+
+        assert_eq!(result.n_setups, 1);
+        assert_eq!(result.n_max_bars_exits, 1);
+        assert_eq!(result.n_open_at_end, 0);
+        assert!((result.avg_profitability - 0.03).abs() < 1e-9);
+        assert_eq!(result.avg_win_bars, 3.0);
+    }
+
+    #[test]
+    fn strategy_tester_end_of_data() {
+        let mut strat: Box<dyn TradingStrategy> =
+            Box::new(TrueTwiceStrategy::new().with_resolution_strategy(trailing()));
+        let candles = CandleWindow::new(Candle::dummy_from_arr(&[120.0, 100.0, 98.0, 95.0]));
+
+        let result = StrategyTester::new()
+            .max_bars(10)
+            .test_strategy(&mut strat, candles.view())
+            .unwrap();
+
+        // Entered on the second candle, marked to market on the last close
+        assert_eq!(result.n_setups, 1);
+        assert_eq!(result.n_open_at_end, 1);
+        assert_eq!(result.n_max_bars_exits, 0);
+        assert!((result.avg_loss + 0.05).abs() < 1e-9);
+        assert_eq!(result.avg_loss_bars, 2.0);
+    }
+
+    #[test]
+    fn strategy_tester_skipped_setups() {
+        // FixedValues can not be initialized from a setup
+        let resolution = ResolutionStrategy::FixedValues(FixedValuesResolution {
+            high: 200.0,
+            low: 50.0,
+        });
+        let mut strat: Box<dyn TradingStrategy> =
+            Box::new(TrueOnceStrategy::new().with_resolution_strategy(resolution));
+        let candles = CandleWindow::new(Candle::dummy_from_arr(&[100.0, 101.0, 102.0]));
+
+        let result = StrategyTester::new()
+            .test_strategy(&mut strat, candles.view())
+            .unwrap();
+
+        assert_eq!(result.n_skipped, 1);
+        assert_eq!(result.n_setups, 0);
+        assert_eq!(result.accuracy, 0.0);
+    }
+
+    #[test]
+    fn strategy_tester_short_outcomes() {
+        let mut strat: Box<dyn TradingStrategy> = Box::new(
+            TrueOnceStrategy::new()
+                .with_orientation(StrategyOrientation::Short)
+                .with_resolution_strategy(trailing()),
+        );
+        let candles = CandleWindow::new(Candle::dummy_from_arr(&[100.0, 90.0, 80.0, 120.0]));
+
+        let result = StrategyTester::new()
+            .max_bars(2)
+            .test_strategy(&mut strat, candles.view())
+            .unwrap();
+
+        assert_eq!(result.n_max_bars_exits, 1);
+        assert_eq!(result.accuracy, 1.0);
+        assert!((result.avg_win - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strategy_tester_scale_out() {
+        // 1R is 2 ATRs of 5, half the position exits at 1R and the rest at 2R
+        let resolution = ResolutionStrategy::ScaleOut(
+            ScaleOutResolution::new(AtrResolution::new(14, 2.0, 1.0))
+                .target(1.0, 0.5)
+                .target(2.0, 0.5),
+        );
+        let mut strat: Box<dyn TradingStrategy> =
+            Box::new(TrueOnceStrategy::new().with_resolution_strategy(resolution));
+        let mut candles =
+            CandleWindow::new(Candle::dummy_from_arr(&[100.0, 111.0, 115.0, 121.0, 130.0]));
+        candles.set_indicator(
+            0,
+            IndicatorType::ATR(14),
+            Indicator::ATR(Some(ATR {
+                len: 14,
+                value: 5.0,
+            })),
+        );
+
+        let result = StrategyTester::new()
+            .test_strategy(&mut strat, candles.view())
+            .unwrap();
+
+        assert_eq!(result.n_setups, 1);
+        assert_eq!(result.n_take_profits, 1);
+        assert!((result.avg_win - (0.5 * 0.11 + 0.5 * 0.21)).abs() < 1e-9);
+        assert_eq!(result.avg_win_bars, 3.0);
+    }
+
+    // Candles aligned to the interval starting at midnight
+    fn aligned_candles(interval: &Interval, prices: &[f64]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                Candle::from_val(start + interval.to_duration() * i as i32, *price, 1000.0)
+            })
+            .collect()
+    }
+
+    fn timeseries(interval: Interval, prices: &[f64]) -> TimeSeries {
+        TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(interval.clone())
+            .candles(aligned_candles(&interval, prices))
+            .build()
+    }
+
+    #[test]
+    fn strategy_tester_multiple_timeframes() {
+        // SMA 2/4 crosses up on the candle opening 07:00, at which point the
+        // 00:00 and 04:00 four hour candles have closed.
+        let prices = [
+            10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 6.0, 8.0, 10.0, 12.0, 14.0, 13.0,
+        ];
+        let strategy = MACrossover::new_args(
+            MAType::SMA,
+            2,
+            4,
+            OrientationMode::LongOnly,
+            Interval::Hour1,
+        )
+        .with_trend_filter(Interval::Hour4, 2)
+        .with_resolution_strategy(trailing());
+
+        let mut hourly = timeseries(Interval::Hour1, &prices);
+        hourly
+            .add_indicators(&strategy.required_indicators())
+            .unwrap();
+
+        let n_setups = |higher_prices: &[f64]| {
+            let mut higher = timeseries(Interval::Hour4, higher_prices);
+            higher
+                .add_indicators(&[MAType::SMA.indicator_type(2)])
+                .unwrap();
+
+            let mut strat: Box<dyn TradingStrategy> = Box::new(strategy.clone());
+            StrategyTester::new()
+                .timeframe(Interval::Hour4, higher.view().to_window())
+                .test_strategy(&mut strat, hourly.view())
+                .unwrap()
+                .n_setups
+        };
+
+        assert_eq!(n_setups(&[1.0, 2.0, 3.0]), 1);
+        // The unfinished 08:00 candle is not taken into account
+        assert_eq!(n_setups(&[2.0, 1.0, 3.0]), 0);
+
+        // Without the candles of the higher interval the test can not run
+        let mut strat: Box<dyn TradingStrategy> = Box::new(strategy.clone());
+        assert!(StrategyTester::new()
+            .test_strategy(&mut strat, hourly.view())
+            .is_err());
+    }
+}
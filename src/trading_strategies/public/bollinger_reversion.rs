@@ -0,0 +1,265 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView,
+        fixed_point::FixedPoint,
+        interval::Interval,
+        ma_type::MAType,
+        orientation_mode::OrientationMode,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        trading_schedule::TradingSchedule,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use std::fmt::{Display, Formatter};
+
+/// # Bollinger Band Mean Reversion
+///
+/// Enters once price closes back inside the Bollinger Bands after having
+/// closed outside of them, betting on a return towards the basis.
+///
+/// ## Directionality
+/// - Long, short or both depending on the `OrientationMode`
+///
+/// ## Interval
+/// - Configurable, daily by default
+///
+/// ## Entry Conditions
+/// - Long: previous close below the lower band, last close above it.
+/// - Short: previous close above the upper band, last close below it.
+///
+/// ## Take-profit & Stop-loss
+/// - DynamicPivot resolution by default, see `with_resolution_strategy`.
+///
+/// ## Trading days
+/// - All by default, see `with_schedule`
+#[derive(Debug, Clone)]
+pub struct BollingerReversion {
+    pub len: usize,
+    pub std_n: f64,
+    pub ma_type: MAType,
+    pub mode: OrientationMode,
+    pub interval: Interval,
+    pub resolution_strategy: ResolutionStrategy,
+    pub schedule: TradingSchedule,
+}
+
+const DEFAULT_PIVOT_LEN: usize = 15;
+
+#[allow(dead_code)] // TODO: Remove once used
+impl BollingerReversion {
+    pub fn new_args(
+        len: usize,
+        std_n: f64,
+        ma_type: MAType,
+        mode: OrientationMode,
+        interval: Interval,
+    ) -> Self {
+        BollingerReversion {
+            len,
+            std_n,
+            ma_type,
+            mode,
+            interval,
+            resolution_strategy: ResolutionStrategy::DynamicPivot(DynamicPivotResolution {
+                len: DEFAULT_PIVOT_LEN,
+            }),
+            schedule: TradingSchedule::default(),
+        }
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: TradingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::BollingerBands(self.len, FixedPoint::new(self.std_n), self.ma_type)
+    }
+
+    /// Returns the orientation of a reversion between the last two candles.
+    fn get_orientation(&self, candles: CandleView<'_>) -> Option<StrategyOrientation> {
+        let len = candles.len();
+        let (prev, current) = (&candles[len - 2], &candles[len - 1]);
+
+        let key = self.indicator_type();
+        let prev_bb = candles.indicator(len - 2, &key)?.as_bollinger_bands()?;
+        let current_bb = candles.indicator(len - 1, &key)?.as_bollinger_bands()?;
+
+        let orientation = if prev.close < prev_bb.lower && current.close > current_bb.lower {
+            StrategyOrientation::Long
+        } else if prev.close > prev_bb.upper && current.close < current_bb.upper {
+            StrategyOrientation::Short
+        } else {
+            return None;
+        };
+
+        self.mode.allows(&orientation).then_some(orientation)
+    }
+}
+
+impl HasMinLength for BollingerReversion {
+    fn min_length(&self) -> usize {
+        self.required_indicators()
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1)
+            + 1
+    }
+}
+
+impl TradingStrategy for BollingerReversion {
+    /// 20 period SMA based bands at 2 standard deviations, both directions,
+    /// daily.
+    fn new() -> Self {
+        Self::new_args(20, 2.0, MAType::SMA, OrientationMode::Both, Interval::Day1)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+        let orientation = self.get_orientation(candles)?;
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.resolution_strategy.clone()
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.schedule.clone()
+    }
+}
+
+impl RequiresIndicators for BollingerReversion {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![self.indicator_type()];
+
+        for indicator_type in self.resolution_strategy.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for BollingerReversion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Bollinger Reversion {} {} {} ({})",
+            self.ma_type, self.len, self.std_n, self.mode
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BollingerReversion;
+    use crate::models::{
+        candle::Candle,
+        interval::Interval,
+        ma_type::MAType,
+        orientation_mode::OrientationMode,
+        strategy_orientation::StrategyOrientation,
+        timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+        traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+    };
+
+    const PRICES: &[f64] = &[
+        10.0, 10.2, 9.9, 10.1, 10.0, 10.1, 9.9, 10.0, 8.0, 10.0, 10.1, 9.9, 10.0, 10.1, 12.0, 10.0,
+        10.1,
+    ];
+
+    fn setups(strategy: &mut BollingerReversion) -> Vec<(usize, StrategyOrientation)> {
+        let mut ts: TimeSeries = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_from_arr(PRICES))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        (2..=ts.candles.len())
+            .filter_map(|end| {
+                let sb = strategy.check_last_for_setup(ts.view().window(end - 2, end))?;
+                let setup = sb.symbol("DUMMY").interval(&Interval::Day1).build().ok()?;
+                Some((end - 1, setup.orientation))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bollinger_reversion_both_directions() {
+        let mut strategy = BollingerReversion::new_args(
+            5,
+            1.5,
+            MAType::SMA,
+            OrientationMode::Both,
+            Interval::Day1,
+        );
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [
+                (9, StrategyOrientation::Long),
+                (15, StrategyOrientation::Short)
+            ]
+        ));
+    }
+
+    #[test]
+    fn bollinger_reversion_respects_orientation_mode() {
+        let mut strategy = BollingerReversion::new_args(
+            5,
+            1.5,
+            MAType::SMA,
+            OrientationMode::ShortOnly,
+            Interval::Day1,
+        );
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [(15, StrategyOrientation::Short)]
+        ));
+    }
+}
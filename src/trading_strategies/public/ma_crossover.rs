@@ -0,0 +1,381 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView,
+        interval::Interval,
+        ma_type::MAType,
+        multi_timeframe_view::MultiTimeframeView,
+        orientation_mode::OrientationMode,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        timeframe_requirement::TimeframeRequirement,
+        trading_schedule::TradingSchedule,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use std::fmt::{Display, Formatter};
+
+/// # Moving Average Crossover Strategy
+///
+/// Strategy triggering when a fast moving average crosses a slow moving
+/// average. Presets exist for the well known crosses:
+///
+/// - Golden cross: 50 crossing above 200, long
+/// - Silver cross: 21 crossing 55, any `OrientationMode`
+/// - Death cross: 50 crossing below 200, short
+///
+/// ## Directionality
+/// - Long when the fast average crosses above the slow average
+/// - Short when the fast average crosses below the slow average
+/// - Either or both depending on the `OrientationMode`
+///
+/// ## Interval
+/// - Configurable, daily by default
+///
+/// ## Entry Conditions
+/// - Fast average was at or below (above for shorts) the slow average on the
+///   previous candle and is above (below) it on the last candle.
+/// - Optionally the last closed candle of a higher interval closes above
+///   (below for shorts) its moving average, see `with_trend_filter`.
+///
+/// ## Take-profit & Stop-loss
+/// - DynamicPivot resolution by default, see `with_resolution_strategy`.
+///
+/// ## Trading days
+/// - All by default, see `with_schedule`
+#[derive(Debug, Clone)]
+pub struct MACrossover {
+    pub ma_type: MAType,
+    pub fast_len: usize,
+    pub slow_len: usize,
+    pub mode: OrientationMode,
+    pub interval: Interval,
+    pub resolution_strategy: ResolutionStrategy,
+    pub schedule: TradingSchedule,
+    pub trend_filter: Option<TrendFilter>,
+}
+
+/// Moving average of the same type on a higher interval which setups need to
+/// be on the right side of.
+#[derive(Debug, Clone)]
+pub struct TrendFilter {
+    pub interval: Interval,
+    pub len: usize,
+}
+
+const DEFAULT_PIVOT_LEN: usize = 15;
+
+#[allow(dead_code)] // TODO: Remove once used
+impl MACrossover {
+    pub fn new_args(
+        ma_type: MAType,
+        fast_len: usize,
+        slow_len: usize,
+        mode: OrientationMode,
+        interval: Interval,
+    ) -> Self {
+        MACrossover {
+            ma_type,
+            fast_len,
+            slow_len,
+            mode,
+            interval,
+            resolution_strategy: ResolutionStrategy::DynamicPivot(DynamicPivotResolution {
+                len: DEFAULT_PIVOT_LEN,
+            }),
+            schedule: TradingSchedule::default(),
+            trend_filter: None,
+        }
+    }
+
+    /// 50 crossing above the 200 moving average.
+    pub fn golden_cross(ma_type: MAType, interval: Interval) -> Self {
+        Self::new_args(ma_type, 50, 200, OrientationMode::LongOnly, interval)
+    }
+
+    /// 21 crossing the 55 moving average.
+    pub fn silver_cross(ma_type: MAType, mode: OrientationMode, interval: Interval) -> Self {
+        Self::new_args(ma_type, 21, 55, mode, interval)
+    }
+
+    /// 50 crossing below the 200 moving average.
+    pub fn death_cross(ma_type: MAType, interval: Interval) -> Self {
+        Self::new_args(ma_type, 50, 200, OrientationMode::ShortOnly, interval)
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: TradingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Only takes setups in the direction of the trend on `interval`, given
+    /// by the close of its last closed candle relative to its moving average
+    /// of length `len`.
+    pub fn with_trend_filter(mut self, interval: Interval, len: usize) -> Self {
+        self.trend_filter = Some(TrendFilter { interval, len });
+        self
+    }
+
+    fn with_trend(
+        &self,
+        orientation: StrategyOrientation,
+        timeframes: &MultiTimeframeView,
+    ) -> Option<bool> {
+        let filter = match &self.trend_filter {
+            Some(filter) => filter,
+            None => return Some(true),
+        };
+
+        let candles = timeframes.candles(&filter.interval)?;
+        let candle = candles.last()?;
+        let ma = self.ma_value(candles, candles.len().checked_sub(1)?, filter.len)?;
+
+        Some(match orientation {
+            StrategyOrientation::Long => candle.close > ma,
+            StrategyOrientation::Short => candle.close < ma,
+        })
+    }
+
+    /// Returns the moving average of length `len` of the candle at index `i`.
+    fn ma_value(&self, candles: CandleView<'_>, i: usize, len: usize) -> Option<f64> {
+        candles
+            .indicator(i, &self.ma_type.indicator_type(len))?
+            .as_ma_value()
+    }
+
+    /// Returns the orientation of a cross between the last two candles if the
+    /// mode allows it.
+    fn crossed(&self, candles: CandleView<'_>) -> Option<StrategyOrientation> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let (prev, current) = (candles.len() - 2, candles.len() - 1);
+        let (prev_fast, prev_slow) = (
+            self.ma_value(candles, prev, self.fast_len)?,
+            self.ma_value(candles, prev, self.slow_len)?,
+        );
+        let (fast, slow) = (
+            self.ma_value(candles, current, self.fast_len)?,
+            self.ma_value(candles, current, self.slow_len)?,
+        );
+
+        let orientation = if prev_fast <= prev_slow && fast > slow {
+            StrategyOrientation::Long
+        } else if prev_fast >= prev_slow && fast < slow {
+            StrategyOrientation::Short
+        } else {
+            return None;
+        };
+
+        self.mode.allows(&orientation).then_some(orientation)
+    }
+}
+
+impl HasMinLength for MACrossover {
+    fn min_length(&self) -> usize {
+        self.required_indicators()
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1)
+            + 1
+    }
+}
+
+impl TradingStrategy for MACrossover {
+    /// Silver cross on the SMA, both directions, daily.
+    fn new() -> Self {
+        Self::silver_cross(MAType::SMA, OrientationMode::Both, Interval::Day1)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
+        let orientation = self.crossed(candles)?;
+
+        let sb = SetupBuilder::new()
+            .candle(candles.last()?)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn check_last_for_setup_with_timeframes(
+        &mut self,
+        candles: CandleView<'_>,
+        timeframes: &MultiTimeframeView,
+    ) -> Option<SetupBuilder> {
+        let orientation = self.crossed(candles)?;
+        let sb = self.check_last_for_setup(candles)?;
+
+        self.with_trend(orientation, timeframes)?.then_some(sb)
+    }
+
+    fn timeframe_requirements(&self) -> Vec<TimeframeRequirement> {
+        self.trend_filter
+            .iter()
+            .map(|filter| {
+                TimeframeRequirement::new(
+                    filter.interval.clone(),
+                    vec![self.ma_type.indicator_type(filter.len)],
+                    1,
+                )
+            })
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.resolution_strategy.clone()
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.schedule.clone()
+    }
+}
+
+impl RequiresIndicators for MACrossover {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![
+            self.ma_type.indicator_type(self.fast_len),
+            self.ma_type.indicator_type(self.slow_len),
+        ];
+
+        for indicator_type in self.resolution_strategy.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for MACrossover {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match (self.fast_len, self.slow_len, self.mode) {
+            (50, 200, OrientationMode::LongOnly) => "Golden Cross",
+            (50, 200, OrientationMode::ShortOnly) => "Death Cross",
+            (21, 55, _) => "Silver Cross",
+            _ => "Crossover",
+        };
+
+        write!(
+            f,
+            "{} {} {}/{} {}",
+            self.ma_type, name, self.fast_len, self.slow_len, self.mode
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MACrossover;
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            interval::Interval,
+            ma_type::MAType,
+            orientation_mode::OrientationMode,
+            strategy_orientation::StrategyOrientation,
+            timeseries::TimeSeries,
+            timeseries_builder::TimeSeriesBuilder,
+            traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+        },
+    };
+
+    const PRICES: &[f64] = &[
+        10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 6.0, 8.0, 10.0, 12.0, 14.0, 13.0, 11.0, 9.0, 7.0, 5.0,
+    ];
+
+    fn setups(strategy: &mut MACrossover) -> Vec<(usize, StrategyOrientation)> {
+        let mut ts: TimeSeries = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_from_arr(PRICES))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        (2..=ts.candles.len())
+            .filter_map(|end| {
+                let sb = strategy.check_last_for_setup(ts.view().window(end - 2, end))?;
+
+                Some((end - 1, sb.orientation?))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ma_crossover_long_and_short() {
+        for ma_type in [MAType::SMA, MAType::EMA, MAType::VWMA] {
+            let mut long =
+                MACrossover::new_args(ma_type, 2, 4, OrientationMode::LongOnly, Interval::Day1);
+            let mut short =
+                MACrossover::new_args(ma_type, 2, 4, OrientationMode::ShortOnly, Interval::Day1);
+            let mut both =
+                MACrossover::new_args(ma_type, 2, 4, OrientationMode::Both, Interval::Day1);
+
+            let long_setups = setups(&mut long);
+            let short_setups = setups(&mut short);
+
+            assert_eq!(long_setups.len(), 1, "{}", ma_type);
+            assert_eq!(short_setups.len(), 1, "{}", ma_type);
+            assert!(long_setups[0].0 > 5 && long_setups[0].0 < 10, "{}", ma_type);
+            assert!(short_setups[0].0 > 11, "{}", ma_type);
+            assert_eq!(long_setups[0].1, StrategyOrientation::Long);
+            assert_eq!(short_setups[0].1, StrategyOrientation::Short);
+
+            // A single instance trading both directions finds both setups
+            let both_setups = setups(&mut both);
+            assert_eq!(
+                both_setups,
+                [long_setups[0], short_setups[0]],
+                "{}",
+                ma_type
+            );
+        }
+    }
+
+    #[test]
+    fn ma_crossover_presets() {
+        let golden = MACrossover::golden_cross(MAType::EMA, Interval::Hour4);
+        assert_eq!(golden.to_string(), "EMA Golden Cross 50/200 Long only");
+        assert_eq!(golden.interval(), Interval::Hour4);
+
+        let death = MACrossover::death_cross(MAType::SMA, Interval::Day1);
+        assert_eq!(death.mode, OrientationMode::ShortOnly);
+
+        let silver = MACrossover::new();
+        assert_eq!(silver.to_string(), "SMA Silver Cross 21/55 Long & Short");
+        assert_eq!(
+            silver.required_indicators(),
+            vec![
+                IndicatorType::SMA(21),
+                IndicatorType::SMA(55),
+                IndicatorType::DynamicPivot(15),
+            ]
+        );
+    }
+}
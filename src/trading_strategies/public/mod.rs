@@ -1,4 +1,8 @@
+pub mod bollinger_reversion;
+pub mod ma_crossover;
+pub mod pivot_breakout;
 pub mod rsi_basic;
-pub mod silver_cross;
+pub mod rule_strategy;
+pub mod stochastic_cross;
 pub mod true_once_strategy;
 pub mod true_twice_strategy;
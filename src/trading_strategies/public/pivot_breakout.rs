@@ -0,0 +1,346 @@
+use crate::{
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView,
+        fixed_point::FixedPoint,
+        interval::Interval,
+        ma_type::MAType,
+        orientation_mode::OrientationMode,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        trading_schedule::TradingSchedule,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{atr::AtrResolution, resolution_strategy::ResolutionStrategy},
+};
+use std::fmt::{Display, Formatter};
+
+/// Requires the volume of the breakout candle to be at least
+/// `min_relative_volume` times its `len` period average.
+#[derive(Debug, Clone, Copy)]
+pub struct VolumeConfirmation {
+    pub len: usize,
+    pub min_relative_volume: f64,
+}
+
+/// Requires the Bollinger Band Width to be rising on the breakout candle.
+#[derive(Debug, Clone, Copy)]
+pub struct BbwExpansion {
+    pub len: usize,
+    pub std_n: f64,
+    pub ma_type: MAType,
+}
+
+impl BbwExpansion {
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::BBW(self.len, FixedPoint::new(self.std_n), self.ma_type)
+    }
+}
+
+/// # Pivot Breakout
+///
+/// Enters when the close breaks the latest confirmed DynamicPivots level,
+/// resistance for longs and support for shorts. Pivots are only confirmed
+/// `pivot_len` candles after they formed, the level used is the one known at
+/// the time of the last candle.
+///
+/// ## Directionality
+/// - Long, short or both depending on the `OrientationMode`
+///
+/// ## Interval
+/// - Configurable, daily by default
+///
+/// ## Entry Conditions
+/// - Long: previous close at or below the pivot high, last close above it.
+/// - Short: previous close at or above the pivot low, last close below it.
+/// - Optionally the breakout candle needs above average volume, see
+///   `VolumeConfirmation`.
+/// - Optionally the BBW needs to be expanding, see `BbwExpansion`.
+///
+/// ## Take-profit & Stop-loss
+/// - ATR resolution by default, 2 ATR stop with a 2R target, see
+///   `with_resolution_strategy`. Pivot based targets tend to fire right after
+///   the breakout as the broken level is still the latest pivot.
+///
+/// ## Trading days
+/// - All by default, see `with_schedule`
+#[derive(Debug, Clone)]
+pub struct PivotBreakout {
+    pub pivot_len: usize,
+    pub mode: OrientationMode,
+    pub interval: Interval,
+    pub volume_confirmation: Option<VolumeConfirmation>,
+    pub bbw_expansion: Option<BbwExpansion>,
+    pub resolution_strategy: ResolutionStrategy,
+    pub schedule: TradingSchedule,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl PivotBreakout {
+    pub fn new_args(pivot_len: usize, mode: OrientationMode, interval: Interval) -> Self {
+        PivotBreakout {
+            pivot_len,
+            mode,
+            interval,
+            volume_confirmation: None,
+            bbw_expansion: None,
+            resolution_strategy: ResolutionStrategy::Atr(AtrResolution::new(14, 2.0, 2.0)),
+            schedule: TradingSchedule::default(),
+        }
+    }
+
+    pub fn with_volume_confirmation(mut self, len: usize, min_relative_volume: f64) -> Self {
+        self.volume_confirmation = Some(VolumeConfirmation {
+            len,
+            min_relative_volume,
+        });
+        self
+    }
+
+    pub fn with_bbw_expansion(mut self, len: usize, std_n: f64, ma_type: MAType) -> Self {
+        self.bbw_expansion = Some(BbwExpansion {
+            len,
+            std_n,
+            ma_type,
+        });
+        self
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: TradingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    fn get_orientation(&self, candles: CandleView<'_>) -> Option<StrategyOrientation> {
+        let len = candles.len();
+        let current = &candles[len - 1];
+        let prev = &candles[len - 2];
+
+        // Pivots are stored on the candle they formed on, which is
+        // `pivot_len` candles before the candle confirming them.
+        let pivots = candles
+            .indicator(
+                len - 1 - self.pivot_len,
+                &IndicatorType::DynamicPivot(self.pivot_len),
+            )?
+            .as_dynamic_pivots()?;
+
+        let broke_high = pivots
+            .high
+            .is_some_and(|high| prev.close <= high && current.close > high);
+        let broke_low = pivots
+            .low
+            .is_some_and(|low| prev.close >= low && current.close < low);
+
+        let orientation = if broke_high {
+            StrategyOrientation::Long
+        } else if broke_low {
+            StrategyOrientation::Short
+        } else {
+            return None;
+        };
+
+        self.mode.allows(&orientation).then_some(orientation)
+    }
+
+    fn volume_confirmed(&self, candles: CandleView<'_>) -> Option<bool> {
+        let Some(confirmation) = self.volume_confirmation else {
+            return Some(true);
+        };
+
+        let volume_sma = candles
+            .last_indicator(&IndicatorType::VolumeSMA(confirmation.len))?
+            .as_volume_sma()?;
+
+        Some(volume_sma.relative_volume >= confirmation.min_relative_volume)
+    }
+
+    fn bbw_expanding(&self, candles: CandleView<'_>) -> Option<bool> {
+        let Some(expansion) = self.bbw_expansion else {
+            return Some(true);
+        };
+
+        let key = expansion.indicator_type();
+        let column = candles.column(&key);
+        let prev_bbw = column.nth_last(2)?.as_bbw()?;
+        let current_bbw = column.last()?.as_bbw()?;
+
+        Some(current_bbw.value > prev_bbw.value)
+    }
+}
+
+impl HasMinLength for PivotBreakout {
+    fn min_length(&self) -> usize {
+        self.required_indicators()
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1)
+            .max(2 * self.pivot_len + 1)
+            + self.pivot_len
+            + 1
+    }
+}
+
+impl TradingStrategy for PivotBreakout {
+    /// 15 candle pivots, both directions, daily, without filters.
+    fn new() -> Self {
+        Self::new_args(15, OrientationMode::Both, Interval::Day1)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.pivot_len + 2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
+        if candles.len() < self.candles_needed_for_setup() {
+            return None;
+        }
+
+        let current = candles.last()?;
+        let orientation = self.get_orientation(candles)?;
+
+        if !self.volume_confirmed(candles)? || !self.bbw_expanding(candles)? {
+            return None;
+        }
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.resolution_strategy.clone()
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.schedule.clone()
+    }
+}
+
+impl RequiresIndicators for PivotBreakout {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![IndicatorType::DynamicPivot(self.pivot_len)];
+
+        if let Some(confirmation) = self.volume_confirmation {
+            indicators.push(IndicatorType::VolumeSMA(confirmation.len));
+        }
+
+        if let Some(expansion) = self.bbw_expansion {
+            indicators.push(expansion.indicator_type());
+        }
+
+        for indicator_type in self.resolution_strategy.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for PivotBreakout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Pivot Breakout {} ({})", self.pivot_len, self.mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PivotBreakout;
+    use crate::models::{
+        candle::Candle,
+        interval::Interval,
+        ma_type::MAType,
+        orientation_mode::OrientationMode,
+        strategy_orientation::StrategyOrientation,
+        timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+        traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+    };
+
+    const PRICES: &[f64] = &[
+        10.0, 11.0, 12.0, 11.0, 10.0, 10.5, 11.0, 11.5, 13.0, 12.5, 12.0, 10.2, 10.1, 9.9, 9.8,
+    ];
+
+    fn setups(strategy: &mut PivotBreakout) -> Vec<(usize, StrategyOrientation)> {
+        let mut candles = Candle::dummy_from_arr(PRICES);
+        candles[8].volume = 3000.0;
+
+        let mut ts: TimeSeries = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(candles)
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        let needed = strategy.candles_needed_for_setup();
+        (needed..=ts.candles.len())
+            .filter_map(|end| {
+                let sb = strategy.check_last_for_setup(ts.view().window(end - needed, end))?;
+                let setup = sb.symbol("DUMMY").interval(&Interval::Day1).build().ok()?;
+                Some((end - 1, setup.orientation))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pivot_breakout_both_directions() {
+        let mut strategy = PivotBreakout::new_args(2, OrientationMode::Both, Interval::Day1);
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [
+                (8, StrategyOrientation::Long),
+                (13, StrategyOrientation::Short)
+            ]
+        ));
+    }
+
+    #[test]
+    fn pivot_breakout_volume_confirmation() {
+        let mut strategy = PivotBreakout::new_args(2, OrientationMode::Both, Interval::Day1)
+            .with_volume_confirmation(3, 1.5);
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [(8, StrategyOrientation::Long)]
+        ));
+    }
+
+    #[test]
+    fn pivot_breakout_bbw_expansion() {
+        let mut strategy = PivotBreakout::new_args(2, OrientationMode::Both, Interval::Day1)
+            .with_bbw_expansion(3, 2.0, MAType::SMA);
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [(8, StrategyOrientation::Long)]
+        ));
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
     indicators::{indicator_type::IndicatorType, rsi::RSI},
     models::{
-        candle::Candle,
+        candle_window::CandleView,
         interval::Interval,
+        orientation_mode::OrientationMode,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
+        trading_schedule::TradingSchedule,
         traits::{
             has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
             trading_strategy::TradingStrategy,
@@ -12,11 +14,7 @@ use crate::{
     },
     resolution_strategies::resolution_strategy::ResolutionStrategy,
 };
-use chrono::{Datelike, Weekday};
-use std::{
-    collections::HashSet,
-    fmt::{Display, Formatter},
-};
+use std::fmt::{Display, Formatter};
 
 /// # RSIBasic
 ///
@@ -27,57 +25,50 @@ use std::{
 ///
 /// If the upper band is set to 70.0 and the current RSI is above 70.0, a short
 /// setup will be triggered when the RSI goes below 70.
+///
+/// ## Directionality
+/// - Long, short or both depending on the `OrientationMode`. A single
+///   instance trading both directions reports the orientation on each setup.
 #[derive(Debug, Clone)]
 pub struct RsiBasic {
     pub len: usize,
     pub upper_band: f64,
     pub lower_band: f64,
-    pub orientation: StrategyOrientation,
-    pub trading_days: HashSet<Weekday>,
+    pub mode: OrientationMode,
+    pub schedule: TradingSchedule,
 }
 
 impl RsiBasic {
     #[allow(dead_code)] // TODO: Remove once used
-    pub fn new_args(
-        len: usize,
-        upper_band: f64,
-        lower_band: f64,
-        orientation: StrategyOrientation,
-    ) -> Self {
+    pub fn new_args(len: usize, upper_band: f64, lower_band: f64, mode: OrientationMode) -> Self {
         RsiBasic {
             len,
             upper_band,
             lower_band,
-            orientation,
-            trading_days: Self::build_trading_days(),
+            mode,
+            schedule: TradingSchedule::default(),
         }
     }
 
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn with_schedule(mut self, schedule: TradingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
     fn get_orientation(&self, prev: &RSI, current: &RSI) -> Option<StrategyOrientation> {
         let long_condition = prev.value < self.lower_band && current.value > self.lower_band;
-        let _short_condition = prev.value > self.upper_band && current.value < self.upper_band;
+        let short_condition = prev.value > self.upper_band && current.value < self.upper_band;
 
-        if long_condition {
-            Some(StrategyOrientation::Long)
-        // } else if short_condition {
-        //     Some(StrategyOrientation::Short)
+        let orientation = if long_condition {
+            StrategyOrientation::Long
+        } else if short_condition {
+            StrategyOrientation::Short
         } else {
-            None
-        }
-    }
-
-    fn build_trading_days() -> HashSet<Weekday> {
-        let mut set = HashSet::new();
-
-        set.insert(Weekday::Mon);
-        set.insert(Weekday::Tue);
-        set.insert(Weekday::Wed);
-        set.insert(Weekday::Thu);
-        set.insert(Weekday::Fri);
-        set.insert(Weekday::Sat);
-        set.insert(Weekday::Sun);
+            return None;
+        };
 
-        set
+        self.mode.allows(&orientation).then_some(orientation)
     }
 }
 
@@ -93,8 +84,8 @@ impl TradingStrategy for RsiBasic {
             len: 14,
             upper_band: 70.0,
             lower_band: 30.0,
-            orientation: StrategyOrientation::Long,
-            trading_days: Self::build_trading_days(),
+            mode: OrientationMode::LongOnly,
+            schedule: TradingSchedule::default(),
         }
     }
 
@@ -103,23 +94,17 @@ impl TradingStrategy for RsiBasic {
         self.len
     }
 
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder> {
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
         if candles.len() < 2 {
             return None;
         }
 
         let current = candles.last()?;
 
-        let is_active_day = self.trading_days.contains(&current.timestamp.weekday());
-        if !is_active_day {
-            return None;
-        }
-
-        let prev = candles.get(candles.len() - 2)?;
-
         let key = IndicatorType::RSI(self.len);
-        let prev_rsi = prev.clone_indicator(&key).ok()?.as_rsi()?;
-        let current_rsi = current.clone_indicator(&key).ok()?.as_rsi()?;
+        let column = candles.column(&key);
+        let prev_rsi = column.nth_last(2)?.as_rsi()?;
+        let current_rsi = column.last()?.as_rsi()?;
 
         let orientation = self.get_orientation(&prev_rsi, &current_rsi)?;
 
@@ -138,16 +123,12 @@ impl TradingStrategy for RsiBasic {
         todo!()
     }
 
-    fn orientation(&self) -> StrategyOrientation {
-        todo!()
-    }
-
     fn interval(&self) -> Interval {
         Interval::Minute1
     }
 
-    fn trading_days(&self) -> HashSet<Weekday> {
-        self.trading_days.clone()
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.schedule.clone()
     }
 }
 
@@ -159,6 +140,68 @@ impl RequiresIndicators for RsiBasic {
 
 impl Display for RsiBasic {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "RSI Basic")
+        write!(f, "RSI Basic ({})", self.mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RsiBasic;
+    use crate::models::{
+        candle::Candle,
+        interval::Interval,
+        orientation_mode::OrientationMode,
+        strategy_orientation::StrategyOrientation,
+        timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+        traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+    };
+
+    const PRICES: &[f64] = &[
+        10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 14.0, 13.0, 12.0, 11.0, 10.0, 9.0, 8.0, 9.0, 10.0,
+    ];
+
+    fn setup_orientations(strategy: &mut RsiBasic) -> Vec<StrategyOrientation> {
+        let mut ts: TimeSeries = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_from_arr(PRICES))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        (2..=ts.candles.len())
+            .filter_map(|end| strategy.check_last_for_setup(ts.view().window(end - 2, end)))
+            .map(|sb| {
+                sb.symbol("DUMMY")
+                    .interval(&Interval::Day1)
+                    .build()
+                    .unwrap()
+            })
+            .map(|setup| setup.orientation)
+            .collect()
+    }
+
+    #[test]
+    fn rsi_basic_orientation_modes() {
+        let mut both = RsiBasic::new_args(3, 70.0, 30.0, OrientationMode::Both);
+        let mut long = RsiBasic::new_args(3, 70.0, 30.0, OrientationMode::LongOnly);
+        let mut short = RsiBasic::new_args(3, 70.0, 30.0, OrientationMode::ShortOnly);
+
+        let both_setups = setup_orientations(&mut both);
+        let long_setups = setup_orientations(&mut long);
+        let short_setups = setup_orientations(&mut short);
+
+        assert!(matches!(
+            both_setups.as_slice(),
+            [StrategyOrientation::Short, StrategyOrientation::Long]
+        ));
+        assert!(matches!(
+            long_setups.as_slice(),
+            [StrategyOrientation::Long]
+        ));
+        assert!(matches!(
+            short_setups.as_slice(),
+            [StrategyOrientation::Short]
+        ));
     }
 }
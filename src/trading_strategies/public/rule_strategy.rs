@@ -0,0 +1,486 @@
+use crate::{
+    expressions::expression::{Expression, ValueKind},
+    indicators::indicator_type::IndicatorType,
+    models::{
+        candle_window::CandleView,
+        interval::Interval,
+        orientation_mode::OrientationMode,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        trading_schedule::TradingSchedule,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::Weekday;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    fs,
+    path::Path,
+};
+
+/// Declarative definition of a RuleStrategy as read from TOML or JSON.
+///
+/// ## Example (TOML):
+///
+/// ```toml
+/// name = "RSI oversold above 200 SMA"
+/// interval = "1h"
+/// orientation = "LongOnly"
+/// entry = ["crossover(rsi(14), 30)", "close > sma(200)"]
+///
+/// [schedule]
+/// weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+/// time_windows = [{ start = "13:30:00", end = "20:00:00" }]
+///
+/// [resolution.DynamicPivot]
+/// len = 15
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleStrategyDefinition {
+    pub name: String,
+    pub interval: Interval,
+    /// `LongOnly` (or `Long`), `ShortOnly` (or `Short`) or `Both`.
+    pub orientation: OrientationMode,
+    /// Conditions which all have to be true for the last candle to trigger a
+    /// setup. When trading `Both` directions these trigger long setups.
+    pub entry: Vec<Expression>,
+    /// Conditions triggering short setups when trading `Both` directions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub short_entry: Vec<Expression>,
+    /// When setups may trigger, defaults to any time.
+    #[serde(default)]
+    pub schedule: TradingSchedule,
+    /// Days on which setups may trigger, kept for definitions written before
+    /// `schedule`. Turned into a weekday only schedule when loading.
+    #[serde(default, skip_serializing)]
+    pub trading_days: Option<HashSet<Weekday>>,
+    pub resolution: ResolutionStrategy,
+}
+
+/// # Rule Strategy
+///
+/// Generic strategy built from a `RuleStrategyDefinition` so strategies can
+/// be added without writing Rust. Entry conditions are written in the
+/// expression language, see `Expression`. Required indicators and minimum
+/// length are derived from the conditions and the resolution strategy.
+///
+/// ## Directionality
+/// - As defined, a single instance trading `Both` directions checks the
+///   short entry conditions when the entry conditions don't trigger.
+///
+/// ## Interval
+/// - As defined
+///
+/// ## Entry Conditions
+/// - All entry (or short entry) conditions evaluate to true on the last
+///   candle.
+///
+/// ## Take-profit & Stop-loss
+/// - Given by the defined resolution strategy.
+///
+/// ## Trading days
+/// - As defined by the schedule, all by default
+#[derive(Debug, Clone)]
+pub struct RuleStrategy {
+    pub definition: RuleStrategyDefinition,
+}
+
+impl RuleStrategy {
+    pub fn from_definition(mut definition: RuleStrategyDefinition) -> Result<Self> {
+        if let Some(trading_days) = definition.trading_days.take() {
+            if definition.schedule != TradingSchedule::default() {
+                return Err(anyhow!(
+                    "Rule strategy '{}' defines both trading_days and schedule, use schedule only",
+                    definition.name
+                ));
+            }
+
+            definition.schedule = TradingSchedule::from_weekdays(trading_days);
+        }
+
+        if definition.entry.is_empty() {
+            return Err(anyhow!(
+                "Rule strategy '{}' needs at least one entry condition",
+                definition.name
+            ));
+        }
+
+        let both = definition.orientation == OrientationMode::Both;
+        if both == definition.short_entry.is_empty() {
+            return Err(anyhow!(
+                "Rule strategy '{}' needs short entry conditions if and only if trading both directions",
+                definition.name
+            ));
+        }
+
+        for condition in definition.entry.iter().chain(definition.short_entry.iter()) {
+            if condition.kind() != ValueKind::Bool {
+                return Err(anyhow!(
+                    "Entry condition '{}' of strategy '{}' is not a boolean expression",
+                    condition,
+                    definition.name
+                ));
+            }
+        }
+
+        Ok(Self { definition })
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn from_toml(s: &str) -> Result<Self> {
+        let definition = toml::from_str(s).context("Unable to parse rule strategy from TOML")?;
+        Self::from_definition(definition)
+    }
+
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn from_json(s: &str) -> Result<Self> {
+        let definition =
+            serde_json::from_str(s).context("Unable to parse rule strategy from JSON")?;
+        Self::from_definition(definition)
+    }
+
+    /// Loads a definition from a `.toml` or `.json` file.
+    #[allow(dead_code)] // TODO: Remove once used
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read rule strategy file {}", path.display()))?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            Some("json") => Self::from_json(&content),
+            _ => Err(anyhow!(
+                "Unsupported rule strategy file {}, expected .toml or .json",
+                path.display()
+            )),
+        }
+    }
+}
+
+impl HasMinLength for RuleStrategy {
+    fn min_length(&self) -> usize {
+        let entry_len = self
+            .definition
+            .entry
+            .iter()
+            .chain(self.definition.short_entry.iter())
+            .map(|condition| condition.min_length());
+
+        let resolution_len = self
+            .definition
+            .resolution
+            .required_indicators()
+            .into_iter()
+            .map(|indicator_type| indicator_type.min_length());
+
+        entry_len.chain(resolution_len).max().unwrap_or(1)
+    }
+}
+
+impl TradingStrategy for RuleStrategy {
+    /// Returns a strategy without entry conditions which never triggers, use
+    /// one of the loaders to create a usable RuleStrategy.
+    fn new() -> Self {
+        let definition = RuleStrategyDefinition {
+            name: "Empty rule strategy".to_string(),
+            interval: Interval::Minute1,
+            orientation: OrientationMode::LongOnly,
+            entry: vec![],
+            short_entry: vec![],
+            schedule: TradingSchedule::default(),
+            trading_days: None,
+            resolution: ResolutionStrategy::Instant(InstantResolution),
+        };
+
+        Self { definition }
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        self.definition
+            .entry
+            .iter()
+            .chain(self.definition.short_entry.iter())
+            .map(|condition| condition.candles_needed())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
+        let current = candles.last()?;
+
+        if self.definition.entry.is_empty() {
+            return None;
+        }
+
+        let triggered = |conditions: &[Expression]| conditions.iter().all(|c| c.is_true(candles));
+
+        let orientation = match self.definition.orientation {
+            OrientationMode::LongOnly => {
+                triggered(&self.definition.entry).then_some(StrategyOrientation::Long)
+            }
+            OrientationMode::ShortOnly => {
+                triggered(&self.definition.entry).then_some(StrategyOrientation::Short)
+            }
+            OrientationMode::Both => {
+                if triggered(&self.definition.entry) {
+                    Some(StrategyOrientation::Long)
+                } else {
+                    triggered(&self.definition.short_entry).then_some(StrategyOrientation::Short)
+                }
+            }
+        }?;
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.definition.resolution.clone()
+    }
+
+    fn interval(&self) -> Interval {
+        self.definition.interval.clone()
+    }
+
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.definition.schedule.clone()
+    }
+}
+
+impl RequiresIndicators for RuleStrategy {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![];
+
+        let entry = self
+            .definition
+            .entry
+            .iter()
+            .chain(self.definition.short_entry.iter())
+            .flat_map(|condition| condition.required_indicators());
+        let resolution = self.definition.resolution.required_indicators();
+
+        for indicator_type in entry.chain(resolution) {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for RuleStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.definition.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuleStrategy;
+    use crate::{
+        indicators::indicator_type::IndicatorType,
+        models::{
+            candle::Candle,
+            interval::Interval,
+            strategy_orientation::StrategyOrientation,
+            timeseries_builder::TimeSeriesBuilder,
+            trading_schedule::TradingSchedule,
+            traits::{
+                has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+                trading_strategy::TradingStrategy,
+            },
+        },
+    };
+    use chrono::Weekday;
+    use std::collections::HashSet;
+
+    const TOML_DEFINITION: &str = r#"
+        name = "Falling RSI"
+        interval = "1d"
+        orientation = "Long"
+        entry = ["rsi(14) < 30", "close < prev(close)"]
+
+        [resolution.DynamicPivot]
+        len = 5
+    "#;
+
+    #[test]
+    fn rule_strategy_from_toml() {
+        let strategy = RuleStrategy::from_toml(TOML_DEFINITION).unwrap();
+
+        assert_eq!(strategy.to_string(), "Falling RSI");
+        assert_eq!(strategy.interval(), Interval::Day1);
+        assert_eq!(strategy.trading_schedule().weekdays.len(), 7);
+        assert_eq!(strategy.candles_needed_for_setup(), 2);
+        assert_eq!(
+            strategy.required_indicators(),
+            vec![IndicatorType::RSI(14), IndicatorType::DynamicPivot(5)]
+        );
+        assert_eq!(strategy.min_length(), IndicatorType::RSI(14).min_length());
+    }
+
+    #[test]
+    fn rule_strategy_from_json() {
+        let json = r#"{
+            "name": "Weekday crossover",
+            "interval": "1h",
+            "orientation": "Short",
+            "entry": ["crossunder(ema(21), ema(55))"],
+            "schedule": { "weekdays": ["Mon", "Fri"] },
+            "resolution": { "Instant": null }
+        }"#;
+
+        let strategy = RuleStrategy::from_json(json).unwrap();
+
+        assert_eq!(strategy.trading_schedule().weekdays.len(), 2);
+        assert_eq!(
+            strategy.required_indicators(),
+            vec![IndicatorType::EMA(21), IndicatorType::EMA(55)]
+        );
+    }
+
+    #[test]
+    fn rule_strategy_schedule_from_toml() {
+        let toml = TOML_DEFINITION.replace(
+            "[resolution.DynamicPivot]",
+            r#"[schedule]
+            weekdays = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+            time_windows = [{ start = "13:30:00", end = "20:00:00" }]
+            excluded_dates = ["2024-12-25"]
+
+            [resolution.DynamicPivot]"#,
+        );
+
+        let schedule = RuleStrategy::from_toml(&toml).unwrap().trading_schedule();
+
+        assert_eq!(schedule.weekdays.len(), 5);
+        assert_eq!(schedule.time_windows.len(), 1);
+        assert_eq!(schedule.excluded_dates.len(), 1);
+    }
+
+    #[test]
+    fn rule_strategy_legacy_trading_days() {
+        let toml = TOML_DEFINITION.replace(
+            "[resolution.DynamicPivot]",
+            r#"trading_days = ["Mon", "Wed"]
+
+            [resolution.DynamicPivot]"#,
+        );
+
+        let strategy = RuleStrategy::from_toml(&toml).unwrap();
+        let schedule = strategy.trading_schedule();
+
+        assert_eq!(
+            schedule,
+            TradingSchedule::from_weekdays(HashSet::from([Weekday::Mon, Weekday::Wed]))
+        );
+        assert!(strategy.definition.trading_days.is_none());
+
+        let both = toml.replace(
+            "[resolution.DynamicPivot]",
+            r#"[schedule]
+            weekdays = ["Fri"]
+
+            [resolution.DynamicPivot]"#,
+        );
+        assert!(RuleStrategy::from_toml(&both).is_err());
+    }
+
+    #[test]
+    fn rule_strategy_rejects_unknown_fields() {
+        let toml = TOML_DEFINITION.replace("entry =", "entries = [\"close > 0\"]\n        entry =");
+
+        let err = RuleStrategy::from_toml(&toml).unwrap_err();
+        assert!(format!("{:#}", err).contains("entries"));
+    }
+
+    #[test]
+    fn rule_strategy_invalid_definitions() {
+        let not_boolean = TOML_DEFINITION.replace("\"rsi(14) < 30\"", "\"rsi(14)\"");
+        assert!(RuleStrategy::from_toml(&not_boolean).is_err());
+
+        let unknown_indicator = TOML_DEFINITION.replace("rsi(14)", "foo(14)");
+        assert!(RuleStrategy::from_toml(&unknown_indicator).is_err());
+
+        let no_entry = TOML_DEFINITION.replace(
+            "entry = [\"rsi(14) < 30\", \"close < prev(close)\"]",
+            "entry = []",
+        );
+        assert!(RuleStrategy::from_toml(&no_entry).is_err());
+
+        let both_without_short_entry =
+            TOML_DEFINITION.replace("orientation = \"Long\"", "orientation = \"Both\"");
+        assert!(RuleStrategy::from_toml(&both_without_short_entry).is_err());
+
+        let short_entry_without_both = TOML_DEFINITION.replace(
+            "orientation = \"Long\"",
+            "short_entry = [\"rsi(14) > 70\"]\n        orientation = \"Long\"",
+        );
+        assert!(RuleStrategy::from_toml(&short_entry_without_both).is_err());
+    }
+
+    #[test]
+    fn rule_strategy_both_directions() {
+        let toml = TOML_DEFINITION.replace(
+            "orientation = \"Long\"",
+            "orientation = \"Both\"\n        short_entry = [\"close < prev(close)\", \"rsi(14) < 30\"]",
+        );
+        let toml = toml.replace("\"close < prev(close)\"]", "\"close > prev(close)\"]");
+        let mut strategy = RuleStrategy::from_toml(&toml).unwrap();
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_data(30, "negative", 500.0))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        let n = strategy.candles_needed_for_setup();
+        let end = ts.candles.len();
+        let sb = strategy
+            .check_last_for_setup(ts.view().window(end - n, end))
+            .unwrap();
+
+        assert_eq!(sb.orientation, Some(StrategyOrientation::Short));
+    }
+
+    #[test]
+    fn rule_strategy_check_last_for_setup() {
+        let mut strategy = RuleStrategy::from_toml(TOML_DEFINITION).unwrap();
+        let mut ts = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_data(30, "negative", 500.0))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        let n = strategy.candles_needed_for_setup();
+        let end = ts.candles.len();
+
+        assert!(strategy
+            .check_last_for_setup(ts.view().window(end - n, end))
+            .is_some());
+        assert!(strategy
+            .check_last_for_setup(ts.view().window(0, 5))
+            .is_none());
+        assert!(RuleStrategy::new()
+            .check_last_for_setup(ts.view())
+            .is_none());
+    }
+}
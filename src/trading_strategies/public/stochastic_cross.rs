@@ -0,0 +1,265 @@
+use crate::{
+    indicators::{indicator_type::IndicatorType, stochastic::Stochastic},
+    models::{
+        candle_window::CandleView,
+        interval::Interval,
+        orientation_mode::OrientationMode,
+        setups::setup_builder::SetupBuilder,
+        strategy_orientation::StrategyOrientation,
+        trading_schedule::TradingSchedule,
+        traits::{
+            has_min_length::HasMinLength, requires_indicators::RequiresIndicators,
+            trading_strategy::TradingStrategy,
+        },
+    },
+    resolution_strategies::{
+        dynamic_pivot::DynamicPivotResolution, resolution_strategy::ResolutionStrategy,
+    },
+};
+use std::fmt::{Display, Formatter};
+
+/// # Stochastic %K/%D Cross
+///
+/// Enters when %K crosses %D while the oscillator is in the oversold or
+/// overbought zone. Stochastic values range from 0.0 to 1.0, zone levels are
+/// expressed on the same scale.
+///
+/// ## Directionality
+/// - Long, short or both depending on the `OrientationMode`
+///
+/// ## Interval
+/// - Configurable, daily by default
+///
+/// ## Entry Conditions
+/// - Long: %K crosses above %D, previous %K below the oversold level.
+/// - Short: %K crosses below %D, previous %K above the overbought level.
+///
+/// ## Take-profit & Stop-loss
+/// - DynamicPivot resolution by default, see `with_resolution_strategy`.
+///
+/// ## Trading days
+/// - All by default, see `with_schedule`
+#[derive(Debug, Clone)]
+pub struct StochasticCross {
+    pub k_len: usize,
+    pub k_smoothing: usize,
+    pub d_smoothing: usize,
+    pub overbought: f64,
+    pub oversold: f64,
+    pub mode: OrientationMode,
+    pub interval: Interval,
+    pub resolution_strategy: ResolutionStrategy,
+    pub schedule: TradingSchedule,
+}
+
+const DEFAULT_PIVOT_LEN: usize = 15;
+
+#[allow(dead_code)] // TODO: Remove once used
+impl StochasticCross {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_args(
+        k_len: usize,
+        k_smoothing: usize,
+        d_smoothing: usize,
+        overbought: f64,
+        oversold: f64,
+        mode: OrientationMode,
+        interval: Interval,
+    ) -> Self {
+        StochasticCross {
+            k_len,
+            k_smoothing,
+            d_smoothing,
+            overbought,
+            oversold,
+            mode,
+            interval,
+            resolution_strategy: ResolutionStrategy::DynamicPivot(DynamicPivotResolution {
+                len: DEFAULT_PIVOT_LEN,
+            }),
+            schedule: TradingSchedule::default(),
+        }
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: TradingSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    fn indicator_type(&self) -> IndicatorType {
+        IndicatorType::Stochastic(self.k_len, self.k_smoothing, self.d_smoothing)
+    }
+
+    fn get_orientation(
+        &self,
+        prev: &Stochastic,
+        current: &Stochastic,
+    ) -> Option<StrategyOrientation> {
+        let crossed_up = prev.k <= prev.d && current.k > current.d;
+        let crossed_down = prev.k >= prev.d && current.k < current.d;
+
+        let orientation = if crossed_up && prev.k < self.oversold {
+            StrategyOrientation::Long
+        } else if crossed_down && prev.k > self.overbought {
+            StrategyOrientation::Short
+        } else {
+            return None;
+        };
+
+        self.mode.allows(&orientation).then_some(orientation)
+    }
+}
+
+impl HasMinLength for StochasticCross {
+    fn min_length(&self) -> usize {
+        self.required_indicators()
+            .iter()
+            .map(|indicator_type| indicator_type.min_length())
+            .max()
+            .unwrap_or(1)
+            + 1
+    }
+}
+
+impl TradingStrategy for StochasticCross {
+    /// Stochastic 14/1/3 with 0.8/0.2 zones, both directions, daily.
+    fn new() -> Self {
+        Self::new_args(14, 1, 3, 0.8, 0.2, OrientationMode::Both, Interval::Day1)
+    }
+
+    fn candles_needed_for_setup(&self) -> usize {
+        2
+    }
+
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
+        if candles.len() < 2 {
+            return None;
+        }
+
+        let current = candles.last()?;
+
+        let key = self.indicator_type();
+        let column = candles.column(&key);
+        let prev_stoch = column.nth_last(2)?.as_stochastic()?;
+        let current_stoch = column.last()?.as_stochastic()?;
+
+        let orientation = self.get_orientation(&prev_stoch, &current_stoch)?;
+
+        let sb = SetupBuilder::new()
+            .candle(current)
+            .orientation(&orientation);
+
+        Some(sb)
+    }
+
+    fn clone_box(&self) -> Box<dyn TradingStrategy> {
+        Box::new(self.clone())
+    }
+
+    fn default_resolution_strategy(&self) -> ResolutionStrategy {
+        self.resolution_strategy.clone()
+    }
+
+    fn interval(&self) -> Interval {
+        self.interval.clone()
+    }
+
+    fn trading_schedule(&self) -> TradingSchedule {
+        self.schedule.clone()
+    }
+}
+
+impl RequiresIndicators for StochasticCross {
+    fn required_indicators(&self) -> Vec<IndicatorType> {
+        let mut indicators = vec![self.indicator_type()];
+
+        for indicator_type in self.resolution_strategy.required_indicators() {
+            if !indicators.contains(&indicator_type) {
+                indicators.push(indicator_type);
+            }
+        }
+
+        indicators
+    }
+}
+
+impl Display for StochasticCross {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Stochastic Cross {}/{}/{} ({})",
+            self.k_len, self.k_smoothing, self.d_smoothing, self.mode
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StochasticCross;
+    use crate::models::{
+        candle::Candle,
+        interval::Interval,
+        orientation_mode::OrientationMode,
+        strategy_orientation::StrategyOrientation,
+        timeseries::TimeSeries,
+        timeseries_builder::TimeSeriesBuilder,
+        traits::{requires_indicators::RequiresIndicators, trading_strategy::TradingStrategy},
+    };
+
+    const PRICES: &[f64] = &[
+        10.0, 11.0, 12.0, 13.0, 14.0, 13.0, 12.0, 11.0, 10.0, 9.0, 8.0, 7.0, 8.0, 9.0, 10.0, 11.0,
+        12.0, 13.0, 14.0, 13.0, 12.0,
+    ];
+
+    fn setups(strategy: &mut StochasticCross) -> Vec<(usize, StrategyOrientation)> {
+        let mut ts: TimeSeries = TimeSeriesBuilder::new()
+            .symbol("DUMMY".to_string())
+            .interval(Interval::Day1)
+            .candles(Candle::dummy_from_arr(PRICES))
+            .build();
+        ts.add_indicators(&strategy.required_indicators()).unwrap();
+
+        (2..=ts.candles.len())
+            .filter_map(|end| {
+                let sb = strategy.check_last_for_setup(ts.view().window(end - 2, end))?;
+                let setup = sb.symbol("DUMMY").interval(&Interval::Day1).build().ok()?;
+                Some((end - 1, setup.orientation))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stochastic_cross_both_directions() {
+        let mut strategy =
+            StochasticCross::new_args(4, 1, 2, 0.8, 0.2, OrientationMode::Both, Interval::Day1);
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [
+                (5, StrategyOrientation::Short),
+                (12, StrategyOrientation::Long),
+                (19, StrategyOrientation::Short)
+            ]
+        ));
+    }
+
+    #[test]
+    fn stochastic_cross_respects_orientation_mode() {
+        let mut strategy =
+            StochasticCross::new_args(4, 1, 2, 0.8, 0.2, OrientationMode::LongOnly, Interval::Day1);
+
+        let setups = setups(&mut strategy);
+
+        assert!(matches!(
+            setups.as_slice(),
+            [(12, StrategyOrientation::Long)]
+        ));
+    }
+}
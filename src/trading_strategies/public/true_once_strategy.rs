@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle,
+        candle_window::CandleView,
         interval::Interval,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
@@ -14,11 +14,7 @@ use crate::{
         instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
     },
 };
-use chrono::Weekday;
-use std::{
-    collections::HashSet,
-    fmt::{Display, Formatter},
-};
+use std::fmt::{Display, Formatter};
 
 /// # One Activation Strategy
 ///
@@ -27,7 +23,7 @@ use std::{
 /// Same for take-profit and stop-loss.
 ///
 /// ## Directionality
-/// - Long
+/// - Long by default, see `with_orientation`
 ///
 /// ## Interval
 /// - Any
@@ -36,18 +32,34 @@ use std::{
 /// - Always positive on first ask.
 ///
 /// ## Take-profit
-/// - Always positive one first ask.
+/// - Always positive one first ask, unless another resolution strategy is set
+///   through `with_resolution_strategy`.
 ///
 /// ## Stop-loss
-/// - Always positive on first ask.
+/// - Always positive on first ask, unless another resolution strategy is set
+///   through `with_resolution_strategy`.
 ///
 /// ## Trading days
 /// - All
 ///
 #[derive(Debug, Clone)]
 pub struct TrueOnceStrategy {
-    trading_days: HashSet<Weekday>,
     triggered: bool,
+    orientation: StrategyOrientation,
+    resolution_strategy: ResolutionStrategy,
+}
+
+#[allow(dead_code)] // TODO: Remove once used
+impl TrueOnceStrategy {
+    pub fn with_orientation(mut self, orientation: StrategyOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
 }
 
 impl HasMinLength for TrueOnceStrategy {
@@ -62,8 +74,9 @@ impl TradingStrategy for TrueOnceStrategy {
         Self: Sized,
     {
         Self {
-            trading_days: Self::build_trading_days(),
             triggered: false,
+            orientation: StrategyOrientation::Long,
+            resolution_strategy: ResolutionStrategy::Instant(InstantResolution),
         }
     }
 
@@ -71,14 +84,14 @@ impl TradingStrategy for TrueOnceStrategy {
         1
     }
 
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder> {
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
         if self.triggered {
             return None;
         }
 
         let sb = SetupBuilder::new()
             .candle(&candles[0])
-            .orientation(&StrategyOrientation::Long);
+            .orientation(&self.orientation);
 
         self.triggered = true;
 
@@ -90,36 +103,12 @@ impl TradingStrategy for TrueOnceStrategy {
     }
 
     fn default_resolution_strategy(&self) -> ResolutionStrategy {
-        ResolutionStrategy::Instant(InstantResolution)
-    }
-
-    fn orientation(&self) -> StrategyOrientation {
-        StrategyOrientation::Long
+        self.resolution_strategy.clone()
     }
 
     fn interval(&self) -> Interval {
         Interval::Minute1
     }
-
-    fn trading_days(&self) -> HashSet<Weekday> {
-        self.trading_days.clone()
-    }
-}
-
-impl TrueOnceStrategy {
-    fn build_trading_days() -> HashSet<Weekday> {
-        let mut set = HashSet::new();
-
-        set.insert(Weekday::Mon);
-        set.insert(Weekday::Tue);
-        set.insert(Weekday::Wed);
-        set.insert(Weekday::Thu);
-        set.insert(Weekday::Fri);
-        set.insert(Weekday::Sat);
-        set.insert(Weekday::Sun);
-
-        set
-    }
 }
 
 impl RequiresIndicators for TrueOnceStrategy {
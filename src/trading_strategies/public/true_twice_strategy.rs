@@ -1,7 +1,7 @@
 use crate::{
     indicators::indicator_type::IndicatorType,
     models::{
-        candle::Candle,
+        candle_window::CandleView,
         interval::Interval,
         setups::setup_builder::SetupBuilder,
         strategy_orientation::StrategyOrientation,
@@ -14,11 +14,7 @@ use crate::{
         instant_resolution::InstantResolution, resolution_strategy::ResolutionStrategy,
     },
 };
-use chrono::Weekday;
-use std::{
-    collections::HashSet,
-    fmt::{Display, Formatter},
-};
+use std::fmt::{Display, Formatter};
 
 /// # True Twice Strategy
 ///
@@ -26,7 +22,7 @@ use std::{
 /// have been reached on the second task, after that always negative.
 ///
 /// ## Directionality
-/// - Long
+/// - Long by default, see `with_orientation`
 ///
 /// ## Interval
 /// - Any
@@ -35,21 +31,37 @@ use std::{
 /// - Always positive on first ask.
 ///
 /// ## Take-profit
-/// - Always positive one first ask.
+/// - Always positive one first ask, unless another resolution strategy is set
+///   through `with_resolution_strategy`.
 ///
 /// ## Stop-loss
-/// - Always positive on first ask.
+/// - Always positive on first ask, unless another resolution strategy is set
+///   through `with_resolution_strategy`.
 ///
 /// ## Trading days
 /// - All
 ///
 #[derive(Debug, Clone)]
 pub struct TrueTwiceStrategy {
-    trading_days: HashSet<Weekday>,
     triggered: bool,
+    orientation: StrategyOrientation,
+    resolution_strategy: ResolutionStrategy,
     triggers: usize,
 }
 
+#[allow(dead_code)] // TODO: Remove once used
+impl TrueTwiceStrategy {
+    pub fn with_orientation(mut self, orientation: StrategyOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_resolution_strategy(mut self, resolution_strategy: ResolutionStrategy) -> Self {
+        self.resolution_strategy = resolution_strategy;
+        self
+    }
+}
+
 impl HasMinLength for TrueTwiceStrategy {
     fn min_length(&self) -> usize {
         1
@@ -62,9 +74,10 @@ impl TradingStrategy for TrueTwiceStrategy {
         Self: Sized,
     {
         Self {
-            trading_days: Self::build_trading_days(),
             triggered: false,
             triggers: 0,
+            orientation: StrategyOrientation::Long,
+            resolution_strategy: ResolutionStrategy::Instant(InstantResolution),
         }
     }
 
@@ -72,7 +85,7 @@ impl TradingStrategy for TrueTwiceStrategy {
         1
     }
 
-    fn check_last_for_setup(&mut self, candles: &[Candle]) -> Option<SetupBuilder> {
+    fn check_last_for_setup(&mut self, candles: CandleView<'_>) -> Option<SetupBuilder> {
         if self.triggered {
             return None;
         }
@@ -84,7 +97,7 @@ impl TradingStrategy for TrueTwiceStrategy {
 
         let sb = SetupBuilder::new()
             .candle(&candles[0])
-            .orientation(&StrategyOrientation::Long);
+            .orientation(&self.orientation);
 
         self.triggered = true;
 
@@ -96,36 +109,12 @@ impl TradingStrategy for TrueTwiceStrategy {
     }
 
     fn default_resolution_strategy(&self) -> ResolutionStrategy {
-        ResolutionStrategy::Instant(InstantResolution)
-    }
-
-    fn orientation(&self) -> StrategyOrientation {
-        StrategyOrientation::Long
+        self.resolution_strategy.clone()
     }
 
     fn interval(&self) -> Interval {
         Interval::Minute1
     }
-
-    fn trading_days(&self) -> HashSet<Weekday> {
-        self.trading_days.clone()
-    }
-}
-
-impl TrueTwiceStrategy {
-    fn build_trading_days() -> HashSet<Weekday> {
-        let mut set = HashSet::new();
-
-        set.insert(Weekday::Mon);
-        set.insert(Weekday::Tue);
-        set.insert(Weekday::Wed);
-        set.insert(Weekday::Thu);
-        set.insert(Weekday::Fri);
-        set.insert(Weekday::Sat);
-        set.insert(Weekday::Sun);
-
-        set
-    }
 }
 
 impl RequiresIndicators for TrueTwiceStrategy {
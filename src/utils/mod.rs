@@ -1,6 +1,8 @@
 pub mod constants;
 pub mod data;
 pub mod math;
+pub mod ring_buffer;
+pub mod rolling;
 pub mod string;
 
 use crate::models::setups::setup::Setup;
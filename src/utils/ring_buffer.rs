@@ -0,0 +1,124 @@
+use std::ops::{Deref, DerefMut};
+
+/// Fixed capacity buffer keeping the `capacity` most recent values. Values
+/// are stored contiguously so the buffer can be borrowed as a slice, oldest
+/// value first. Evicted values are only moved out once the backing storage
+/// holds twice the capacity, making pushes amortized O(1).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RingBuffer<T> {
+    capacity: usize,
+    start: usize,
+    values: Vec<T>,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            start: 0,
+            values: vec![],
+        }
+    }
+
+    /// Creates a buffer holding the last `capacity` of the given values.
+    pub fn from_vec(mut values: Vec<T>, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        values.drain(..values.len().saturating_sub(capacity));
+
+        Self {
+            capacity,
+            start: 0,
+            values,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, evicting the oldest values which no longer fit.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.start += self.len().saturating_sub(self.capacity);
+        self.compact();
+    }
+
+    /// Pushes a value, evicting the oldest value if the buffer is full.
+    pub fn push(&mut self, value: T) {
+        if self.len() == self.capacity {
+            self.start += 1;
+        }
+
+        self.values.push(value);
+
+        if self.start >= self.capacity {
+            self.compact();
+        }
+    }
+
+    /// Removes the most recent value.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.values.pop()
+    }
+
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.values.clear();
+    }
+
+    fn compact(&mut self) {
+        self.values.drain(..self.start);
+        self.start = 0;
+    }
+}
+
+impl<T> Default for RingBuffer<T> {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
+impl<T> Deref for RingBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values[self.start..]
+    }
+}
+
+impl<T> DerefMut for RingBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.values[self.start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RingBuffer;
+
+    #[test]
+    fn ring_buffer_keeps_capacity_values() {
+        let mut ring = RingBuffer::new(3);
+
+        for i in 0..10 {
+            ring.push(i);
+
+            let expected: Vec<i32> = ((i - 2).max(0)..=i).collect();
+            assert_eq!(&ring[..], &expected[..]);
+            assert!(ring.values.len() <= 2 * ring.capacity());
+        }
+
+        assert_eq!(ring.pop(), Some(9));
+        assert_eq!(&ring[..], &[7, 8]);
+
+        ring.set_capacity(1);
+        assert_eq!(&ring[..], &[8]);
+
+        let ring = RingBuffer::from_vec(vec![1, 2, 3, 4], 2);
+        assert_eq!(&ring[..], &[3, 4]);
+    }
+}
@@ -0,0 +1,511 @@
+use std::collections::VecDeque;
+
+const NIL: usize = usize::MAX;
+
+/// Window of the `len` most recent values kept in an order statistic tree,
+/// used to get the percentile rank of a new value in O(log len) instead of
+/// iterating the whole window. Missing values take up a slot in the window
+/// but are never counted.
+#[derive(Debug, Clone)]
+pub struct RollingPercentile {
+    len: usize,
+    window: VecDeque<Option<f64>>,
+    tree: Treap,
+}
+
+impl RollingPercentile {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(len + 1),
+            tree: Treap::with_capacity(len),
+        }
+    }
+
+    /// Pushes a new value into the window, dropping the oldest value if the
+    /// window is full. NaN values are treated as missing.
+    pub fn push(&mut self, value: Option<f64>) {
+        // Adding 0.0 turns -0.0 into 0.0 so the tree order matches `<`
+        let value = value
+            .filter(|value| !value.is_nan())
+            .map(|value| value + 0.0);
+
+        if let Some(value) = value {
+            self.tree.insert(value);
+        }
+
+        self.window.push_back(value);
+
+        if self.window.len() > self.len {
+            if let Some(Some(old)) = self.window.pop_front() {
+                self.tree.remove(old);
+            }
+        }
+    }
+
+    /// Number of values in the window strictly less than `value`.
+    pub fn count_below(&self, value: f64) -> usize {
+        self.tree.count_below(value + 0.0)
+    }
+
+    /// Share of the window length with values strictly less than `value`.
+    pub fn rank(&self, value: f64) -> f64 {
+        (self.count_below(value) as f64) / (self.len as f64)
+    }
+}
+
+/// Window of the `len` most recent values with their running mean and sum of
+/// squared deviations, updated in O(1) per value. The deviations are kept
+/// with Welford's method rather than as a sum of squares, which loses
+/// precision when the values are large compared to their spread.
+#[derive(Debug, Clone)]
+pub struct RollingSum {
+    len: usize,
+    window: VecDeque<f64>,
+    mean: f64,
+    m2: f64,
+    non_zero: usize,
+}
+
+impl RollingSum {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            window: VecDeque::with_capacity(len + 1),
+            mean: 0.0,
+            m2: 0.0,
+            non_zero: 0,
+        }
+    }
+
+    /// Pushes a new value into the window, dropping the oldest value if the
+    /// window is full.
+    pub fn push(&mut self, value: f64) {
+        if value != 0.0 {
+            self.non_zero += 1;
+        }
+
+        self.window.push_back(value);
+
+        if self.window.len() <= self.len {
+            let delta = value - self.mean;
+            self.mean += delta / self.window.len() as f64;
+            self.m2 += delta * (value - self.mean);
+
+            return;
+        }
+
+        let old = self.window.pop_front().unwrap_or_default();
+
+        if old != 0.0 {
+            self.non_zero -= 1;
+        }
+
+        if self.len == 1 {
+            self.mean = value;
+            self.m2 = 0.0;
+
+            return;
+        }
+
+        let old_mean = self.mean;
+        self.mean += (value - old) / self.len as f64;
+        self.m2 = (self.m2 + (value - old) * (value - self.mean + old - old_mean)).max(0.0);
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len > 0 && self.window.len() == self.len
+    }
+
+    /// Whether every value in the window is zero.
+    pub fn is_zero(&self) -> bool {
+        self.non_zero == 0
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Sample standard deviation of the window measured from `basis`
+    /// instead of the mean.
+    pub fn std_from(&self, basis: f64) -> f64 {
+        let n = self.window.len() as f64;
+
+        if n <= 1.0 {
+            return 0.0;
+        }
+
+        ((self.m2 + n * (self.mean - basis).powi(2)) / (n - 1.0)).sqrt()
+    }
+}
+
+/// Highest high and lowest low of the `len` most recent candles kept in
+/// monotonic deques, so each update is amortised O(1).
+#[derive(Debug, Clone)]
+pub struct RollingExtremes {
+    len: usize,
+    pushed: usize,
+    highs: VecDeque<(usize, f64)>,
+    lows: VecDeque<(usize, f64)>,
+}
+
+impl RollingExtremes {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            pushed: 0,
+            highs: VecDeque::with_capacity(len),
+            lows: VecDeque::with_capacity(len),
+        }
+    }
+
+    /// Pushes the high and low of a new candle, dropping the oldest candle if
+    /// the window is full.
+    pub fn push(&mut self, high: f64, low: f64) {
+        let i = self.pushed;
+        self.pushed += 1;
+
+        while self.highs.back().is_some_and(|&(_, h)| h <= high) {
+            self.highs.pop_back();
+        }
+        while self.lows.back().is_some_and(|&(_, l)| l >= low) {
+            self.lows.pop_back();
+        }
+
+        self.highs.push_back((i, high));
+        self.lows.push_back((i, low));
+
+        while self.highs.front().is_some_and(|&(j, _)| j + self.len <= i) {
+            self.highs.pop_front();
+        }
+        while self.lows.front().is_some_and(|&(j, _)| j + self.len <= i) {
+            self.lows.pop_front();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len > 0 && self.pushed >= self.len
+    }
+
+    /// Highest high and lowest low of the window, None until it is full.
+    pub fn high_low(&self) -> Option<(f64, f64)> {
+        if !self.is_full() {
+            return None;
+        }
+
+        Some((self.highs.front()?.1, self.lows.front()?.1))
+    }
+}
+
+/// Wilder's smoothing (RMA) of a series, seeded with the average of the
+/// first `len` values.
+#[derive(Debug, Clone)]
+pub struct WilderSmoothing {
+    len: usize,
+    count: usize,
+    sum: f64,
+    value: Option<f64>,
+}
+
+impl WilderSmoothing {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            count: 0,
+            sum: 0.0,
+            value: None,
+        }
+    }
+
+    /// Pushes a new value and returns the smoothed value, None until `len`
+    /// values have been pushed.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        let f_len = self.len as f64;
+
+        self.value = match self.value {
+            Some(prev) => Some((prev * (f_len - 1.0) + value) / f_len),
+            None => {
+                self.count += 1;
+                self.sum += value;
+                (self.len > 0 && self.count == self.len).then(|| self.sum / f_len)
+            }
+        };
+
+        self.value
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: f64,
+    priority: u64,
+    size: usize,
+    left: usize,
+    right: usize,
+}
+
+/// Treap keyed by value with subtree sizes, giving O(log n) expected insert,
+/// remove and rank queries. Nodes live in an arena and removed slots are
+/// reused so a full window doesn't allocate.
+#[derive(Debug, Clone)]
+struct Treap {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    root: usize,
+    seed: u64,
+}
+
+impl Treap {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            root: NIL,
+            seed: 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        let node = self.alloc(value);
+        let (left, right) = self.split(self.root, &|x| x < value);
+        let left = self.merge(left, node);
+        self.root = self.merge(left, right);
+    }
+
+    /// Removes one occurrence of `value` if present.
+    fn remove(&mut self, value: f64) {
+        let (left, rest) = self.split(self.root, &|x| x < value);
+        let (equal, right) = self.split(rest, &|x| x <= value);
+
+        let equal = if equal == NIL {
+            NIL
+        } else {
+            let node = &self.nodes[equal];
+            let (l, r) = (node.left, node.right);
+            self.free.push(equal);
+            self.merge(l, r)
+        };
+
+        let left = self.merge(left, equal);
+        self.root = self.merge(left, right);
+    }
+
+    fn count_below(&self, value: f64) -> usize {
+        let mut count = 0;
+        let mut t = self.root;
+
+        while t != NIL {
+            let node = &self.nodes[t];
+            if node.value < value {
+                count += self.size(node.left) + 1;
+                t = node.right;
+            } else {
+                t = node.left;
+            }
+        }
+
+        count
+    }
+
+    fn alloc(&mut self, value: f64) -> usize {
+        // xorshift64, priorities only need to be well spread
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        let node = Node {
+            value,
+            priority: self.seed,
+            size: 1,
+            left: NIL,
+            right: NIL,
+        };
+
+        match self.free.pop() {
+            Some(i) => {
+                self.nodes[i] = node;
+                i
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size
+        }
+    }
+
+    fn update(&mut self, t: usize) {
+        let node = &self.nodes[t];
+        let size = self.size(node.left) + self.size(node.right) + 1;
+        self.nodes[t].size = size;
+    }
+
+    /// Splits `t` into the nodes whose values satisfy `goes_left` and the
+    /// rest. `goes_left` must be monotonic in the value.
+    fn split(&mut self, t: usize, goes_left: &dyn Fn(f64) -> bool) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+
+        if goes_left(self.nodes[t].value) {
+            let (left, right) = self.split(self.nodes[t].right, goes_left);
+            self.nodes[t].right = left;
+            self.update(t);
+            (t, right)
+        } else {
+            let (left, right) = self.split(self.nodes[t].left, goes_left);
+            self.nodes[t].left = right;
+            self.update(t);
+            (left, t)
+        }
+    }
+
+    /// Merges two treaps where every value in `a` is <= every value in `b`.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL {
+            return b;
+        }
+        if b == NIL {
+            return a;
+        }
+
+        if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.merge(self.nodes[a].right, b);
+            self.nodes[a].right = right;
+            self.update(a);
+            a
+        } else {
+            let left = self.merge(a, self.nodes[b].left);
+            self.nodes[b].left = left;
+            self.update(b);
+            b
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RollingExtremes, RollingPercentile, RollingSum, WilderSmoothing};
+
+    #[test]
+    fn rolling_percentile_rank() {
+        let mut percentile = RollingPercentile::new(4);
+
+        for value in [Some(3.0), None, Some(1.0), Some(2.0)] {
+            percentile.push(value);
+        }
+
+        assert_eq!(percentile.count_below(2.5), 2);
+        assert_eq!(percentile.rank(2.5), 0.5);
+        assert_eq!(percentile.rank(0.5), 0.0);
+        assert_eq!(percentile.rank(4.0), 0.75);
+    }
+
+    #[test]
+    fn rolling_percentile_drops_oldest() {
+        let mut percentile = RollingPercentile::new(3);
+
+        for value in [5.0, 1.0, 2.0, 3.0, 4.0] {
+            percentile.push(Some(value));
+        }
+
+        // Window is now [2.0, 3.0, 4.0]
+        assert_eq!(percentile.count_below(10.0), 3);
+        assert_eq!(percentile.count_below(3.0), 1);
+
+        percentile.push(None);
+        assert_eq!(percentile.count_below(10.0), 2);
+    }
+
+    #[test]
+    fn rolling_percentile_matches_window_scan() {
+        let len = 50;
+        let mut percentile = RollingPercentile::new(len);
+        let values: Vec<f64> = (0..500).map(|i| ((i * 37) % 101) as f64).collect();
+
+        for (i, &value) in values.iter().enumerate() {
+            percentile.push(Some(value));
+
+            let window = &values[(i + 1).saturating_sub(len)..=i];
+            for probe in [0.0, 25.5, 50.0, 100.0, value] {
+                let expected = window.iter().filter(|&&x| x < probe).count();
+                assert_eq!(percentile.count_below(probe), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn rolling_sum_matches_window_scan() {
+        let len = 20;
+        let mut sum = RollingSum::new(len);
+        let values: Vec<f64> = (0..300)
+            .map(|i| 30_000.0 + ((i * 37) % 101) as f64 / 7.0)
+            .collect();
+
+        for (i, &value) in values.iter().enumerate() {
+            sum.push(value);
+
+            let window = &values[(i + 1).saturating_sub(len)..=i];
+            let n = window.len() as f64;
+            let mean = window.iter().sum::<f64>() / n;
+            assert_eq!(sum.is_full(), window.len() == len);
+            assert!((sum.mean() - mean).abs() < 1e-9);
+
+            if window.len() > 1 {
+                let basis = mean + 1.5;
+                let std =
+                    (window.iter().map(|x| (x - basis).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+                assert!((sum.std_from(basis) - std).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn rolling_sum_is_zero() {
+        let mut sum = RollingSum::new(2);
+
+        for value in [1.0, 0.0, 0.0] {
+            sum.push(value);
+        }
+        assert!(sum.is_zero());
+
+        sum.push(2.0);
+        assert!(!sum.is_zero());
+    }
+
+    #[test]
+    fn rolling_extremes_matches_window_scan() {
+        let len = 14;
+        let mut extremes = RollingExtremes::new(len);
+        let values: Vec<f64> = (0..300).map(|i| ((i * 53) % 97) as f64).collect();
+
+        for (i, &value) in values.iter().enumerate() {
+            extremes.push(value + 1.0, value - 1.0);
+
+            let window = &values[(i + 1).saturating_sub(len)..=i];
+            let expected = (window.len() == len).then(|| {
+                let high = window.iter().fold(f64::MIN, |a, &b| a.max(b));
+                let low = window.iter().fold(f64::MAX, |a, &b| a.min(b));
+                (high + 1.0, low - 1.0)
+            });
+            assert_eq!(extremes.high_low(), expected);
+        }
+    }
+
+    #[test]
+    fn wilder_smoothing_seeds_with_average() {
+        let mut wilder = WilderSmoothing::new(3);
+
+        assert_eq!(wilder.push(1.0), None);
+        assert_eq!(wilder.push(2.0), None);
+        assert_eq!(wilder.push(3.0), Some(2.0));
+        assert_eq!(wilder.push(8.0), Some(4.0));
+    }
+}